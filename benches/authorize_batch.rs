@@ -0,0 +1,83 @@
+//! Compares [`Engine::authorize_batch`] with one [`Engine::actor_has_permission`] call per item.
+
+use std::collections::HashMap;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use minos::{
+    engine::{BatchAuthorizeRequest, BatchItem, FindPermissionRequest},
+    Actor, Engine, MinosParser, Resource,
+};
+
+const SIMULATION_CONTENT: &str = include_str!("../assets/simulation/simulation_v0_17M.minos");
+const PERMISSIONS: [&str; 5] = ["read_status", "update_status", "create", "delete", "sudo"];
+
+fn actor(id: usize) -> Actor {
+    Actor {
+        id: id.to_string(),
+        type_: "User".into(),
+        status: Some("Active".into()),
+        groups: vec!["staff".into(), "support".into()],
+        roles: match id % 2 {
+            0 => vec!["admin".into(), "editor".into(), "viewer".into()],
+            _ => vec!["viewer".into()],
+        },
+        attrs: HashMap::new(),
+    }
+}
+
+fn resource(id: usize) -> Resource {
+    Resource {
+        id: Some(id.to_string()),
+        type_: "User".into(),
+        owner: None,
+        status: Some("Active".into()),
+        attrs: HashMap::new(),
+    }
+}
+
+fn authorize_batch(c: &mut Criterion) {
+    let storage = MinosParser::easy_parse_str(SIMULATION_CONTENT).unwrap();
+    let engine = Engine::new(&storage);
+    let actors: Vec<Actor> = (0..4).map(actor).collect();
+    let resources: Vec<Resource> = (0..50).map(resource).collect();
+
+    let mut group = c.benchmark_group("authorize_batch");
+    for size in [10, 100, 500] {
+        let items: Vec<BatchItem> = (0..size)
+            .map(|index| BatchItem {
+                env_name: Some("STD"),
+                actor: &actors[index % actors.len()],
+                resource: &resources[index % resources.len()],
+                permission: PERMISSIONS[index % PERMISSIONS.len()],
+            })
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("one_by_one", size), &items, |b, items| {
+            b.iter(|| {
+                items
+                    .iter()
+                    .map(|item| {
+                        engine.actor_has_permission(FindPermissionRequest {
+                            env_name: item.env_name,
+                            actor: item.actor,
+                            resource: item.resource,
+                            context: None,
+                            permission: item.permission.to_string(),
+                        })
+                    })
+                    .for_each(|result| {
+                        black_box(result).ok();
+                    })
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", size), &items, |b, items| {
+            b.iter(|| {
+                black_box(engine.authorize_batch(BatchAuthorizeRequest { context: None, items }));
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, authorize_batch);
+criterion_main!(benches);
@@ -1,13 +1,26 @@
 pub mod actor;
+pub mod attributes;
 pub mod container;
+pub mod context;
+pub mod decision;
+pub mod engine_builder;
 pub mod engine_info;
+pub mod explanation;
 pub mod minos_engine;
+pub(crate) mod partial_evaluation;
 pub mod permissions;
+pub mod reloadable_container;
 pub mod resource;
 
 pub use actor::*;
+pub use attributes::*;
 pub use container::*;
+pub use context::*;
+pub use decision::*;
+pub use engine_builder::*;
 pub use engine_info::*;
+pub use explanation::*;
 pub use minos_engine::*;
 pub use permissions::*;
+pub use reloadable_container::*;
 pub use resource::*;
@@ -1,13 +1,15 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use getset::Getters;
 
 use crate::{
-    language::requirements::Value,
+    language::{hierarchy::Hierarchy, requirements::Value},
     parser::tokens::{ActorAttribute, Array, Identifier},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+use super::{attributes::transform_attributes, AttributeValue};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Getters)]
 #[get = "pub"]
 pub struct Actor {
     pub id: String,
@@ -15,6 +17,8 @@ pub struct Actor {
     pub status: Option<String>,
     pub groups: Vec<String>,
     pub roles: Vec<String>,
+    /// Custom attributes, referenced in the policies as `actor.attrs.<name>`.
+    pub attrs: HashMap<String, AttributeValue>,
 }
 
 pub trait AsActor {
@@ -30,7 +34,7 @@ pub trait TryIntoActor {
     fn try_into_actor(self) -> Result<Actor, Self::Error>;
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[derive(Debug, Clone, PartialEq, Default, Getters)]
 #[get = "pub"]
 pub(crate) struct ActorRepr {
     pub id: Arc<str>,
@@ -38,10 +42,35 @@ pub(crate) struct ActorRepr {
     pub status: Option<Arc<str>>,
     pub groups: Vec<Arc<str>>,
     pub roles: Vec<Arc<str>>,
+    pub attrs: HashMap<Arc<str>, Value>,
+    /// Groups plus the groups inherited through the [Hierarchy], used by the searches.
+    pub expanded_groups: Vec<Arc<str>>,
+    /// Roles plus the roles inherited through the [Hierarchy], used by the searches.
+    pub expanded_roles: Vec<Arc<str>>,
 }
 
 impl ActorRepr {
-    pub(crate) fn get_attribute(&self, attr: ActorAttribute) -> Option<Value> {
+    pub(crate) fn with_hierarchies(mut self, roles: &Hierarchy, groups: &Hierarchy) -> Self {
+        if !roles.is_empty() {
+            self.expanded_roles = roles.expand(&self.roles);
+        }
+        if !groups.is_empty() {
+            self.expanded_groups = groups.expand(&self.groups);
+        }
+
+        self
+    }
+
+    /// Like [`ActorRepr::get_attribute`], but returns the expanded groups and roles.
+    pub(crate) fn get_search_attribute(&self, attr: &ActorAttribute) -> Option<Value> {
+        match attr {
+            ActorAttribute::Groups => Some(Value::Array(Array(self.expanded_groups.clone()))),
+            ActorAttribute::Roles => Some(Value::Array(Array(self.expanded_roles.clone()))),
+            attr => self.get_attribute(attr),
+        }
+    }
+
+    pub(crate) fn get_attribute(&self, attr: &ActorAttribute) -> Option<Value> {
         match attr {
             ActorAttribute::Type => Some(Value::Identifier(Identifier(self.type_.clone()))),
             ActorAttribute::Id => Some(Value::String(self.id.clone())),
@@ -57,6 +86,7 @@ impl ActorRepr {
                 .status
                 .as_ref()
                 .map(|status| Value::Identifier(Identifier(status.clone()))),
+            ActorAttribute::Attrs(name) => self.attrs.get(&name.0).cloned(),
         }
     }
 
@@ -67,12 +97,17 @@ impl ActorRepr {
 
 impl From<&Actor> for ActorRepr {
     fn from(actor: &Actor) -> Self {
+        let groups = Self::transform_list(&actor.groups);
+        let roles = Self::transform_list(&actor.roles);
         Self {
             id: Arc::from(actor.id.as_str()),
             type_: Arc::from(actor.type_.as_str()),
             status: actor.status.as_ref().map(|s| Arc::from(s.as_str())),
-            groups: Self::transform_list(&actor.groups),
-            roles: Self::transform_list(&actor.roles),
+            expanded_groups: groups.clone(),
+            expanded_roles: roles.clone(),
+            groups,
+            roles,
+            attrs: transform_attributes(&actor.attrs),
         }
     }
 }
@@ -0,0 +1,64 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    language::requirements::Value,
+    parser::tokens::{Array, Number},
+};
+
+/// Value of a custom attribute, referenced in the policies as `actor.attrs.<name>`
+/// or `resource.attrs.<name>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    Single(String),
+    List(Vec<String>),
+    Number(Number),
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        Self::Single(value.to_string())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        Self::Single(value)
+    }
+}
+
+impl From<Vec<String>> for AttributeValue {
+    fn from(value: Vec<String>) -> Self {
+        Self::List(value)
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(value: i64) -> Self {
+        Self::Number(Number::Integer(value))
+    }
+}
+
+impl From<f64> for AttributeValue {
+    fn from(value: f64) -> Self {
+        Self::Number(Number::Decimal(value))
+    }
+}
+
+impl From<&AttributeValue> for Value {
+    fn from(value: &AttributeValue) -> Self {
+        match value {
+            AttributeValue::Single(value) => Value::String(Arc::from(value.as_str())),
+            AttributeValue::List(list) => {
+                Value::Array(Array(list.iter().map(|v| Arc::from(v.as_str())).collect()))
+            }
+            AttributeValue::Number(number) => Value::Number(*number),
+        }
+    }
+}
+
+pub(crate) fn transform_attributes(attrs: &HashMap<String, AttributeValue>) -> HashMap<Arc<str>, Value> {
+    attrs
+        .iter()
+        .map(|(name, value)| (Arc::from(name.as_str()), Value::from(value)))
+        .collect()
+}
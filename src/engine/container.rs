@@ -6,7 +6,9 @@ use getset::Getters;
 use crate::language::storage::Storage;
 use crate::{engine::Engine, errors::MinosResult};
 
-use crate::parser::MinosParser;
+use super::ReloadableContainer;
+
+use crate::parser::{imports::Imports, load_report::LoadReport, MinosParser};
 
 #[derive(Debug, Clone)]
 pub struct EmptyContainer;
@@ -52,20 +54,57 @@ impl Container {
             id,
             description,
             paths,
-            storage,
+            storage: _,
             state: _,
         } = self;
-        let mut storage = storage;
+        let storage = load_storage(&paths, &mut Imports::default())?;
+
+        Ok(Container {
+            id,
+            description,
+            paths,
+            storage,
+            state: PhantomData,
+        })
+    }
+
+    /// Load all files from the provided paths, like [`Container::load`], but doesn't stop
+    /// at the first error. If some file has errors, returns a [LoadReport] with all the errors
+    /// and the [Storage] built from the valid parts.
+    pub fn load_with_report(self) -> Result<Container<StaticContainer>, LoadReport> {
+        let Container {
+            id,
+            description,
+            paths,
+            ..
+        } = self;
+        let mut storage = Storage::default();
+        let mut errors = vec![];
         let mut values_map = HashMap::new();
+        let mut imports = Imports::default();
         for path in &paths {
-            if path.is_dir() {
-                let dir_storage = MinosParser::parse_dir(path, &mut values_map)?;
-                storage.merge(dir_storage);
-            } else if path.is_file() {
-                let file_storage = MinosParser::parse_file(path, &mut values_map)?;
-                storage.merge(file_storage);
+            let files = match path.is_dir() {
+                true => MinosParser::dir_files(path).unwrap_or_else(|err| {
+                    errors.push(err);
+                    vec![]
+                }),
+                false if path.is_file() => vec![path.clone()],
+                false => vec![],
+            };
+            for file in files {
+                if let Some(file_storage) = MinosParser::parse_file_with_report(
+                    &file,
+                    &mut values_map,
+                    &mut imports,
+                    &mut errors,
+                ) {
+                    if let Err(err) = storage.merge(file_storage) {
+                        errors.push(err.in_file(&file));
+                    }
+                }
             }
         }
+        let storage = MinosParser::finish_report(storage, errors)?;
 
         Ok(Container {
             id,
@@ -75,6 +114,48 @@ impl Container {
             state: PhantomData,
         })
     }
+
+    /// Load all files from the provided paths, like [`Container::load`], into a container
+    /// that can be reloaded.
+    pub fn load_reloadable(self) -> MinosResult<ReloadableContainer> {
+        let Container {
+            id,
+            description,
+            paths,
+            ..
+        } = self;
+        let mut imports = Imports::default();
+        let storage = load_storage(&paths, &mut imports)?;
+
+        Ok(ReloadableContainer::new(
+            id,
+            description,
+            paths,
+            storage,
+            imports.files(),
+        ))
+    }
+}
+
+/// Parses the files of the paths and validates the merged [Storage]. The imported files
+/// are recorded in `imports`, even if the load fails.
+pub(crate) fn load_storage(paths: &[PathBuf], imports: &mut Imports) -> MinosResult<Storage> {
+    let mut storage = Storage::default();
+    let mut values_map = HashMap::new();
+    for path in paths {
+        if path.is_dir() {
+            let dir_storage = MinosParser::parse_dir(path, &mut values_map, imports)?;
+            storage.merge(dir_storage)?;
+        } else if path.is_file() {
+            let file_storage = MinosParser::parse_file(path, &mut values_map, imports)?;
+            storage.merge(file_storage).map_err(|err| err.in_file(path))?;
+        }
+    }
+    storage.resolve_inheritance()?;
+    storage.check_hierarchies()?;
+    storage.check_schema()?;
+
+    Ok(storage)
 }
 
 impl Container<StaticContainer> {
@@ -82,7 +163,7 @@ impl Container<StaticContainer> {
         &self.storage
     }
 
-    pub fn engine(&self) -> Engine {
+    pub fn engine(&self) -> Engine<'_> {
         Engine::new(&self.storage)
     }
 }
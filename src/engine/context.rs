@@ -0,0 +1,77 @@
+use std::{collections::HashMap, sync::Arc, time::SystemTime};
+
+use crate::{language::requirements::Value, parser::tokens::ContextAttribute};
+
+use super::{attributes::transform_attributes, AttributeValue};
+
+/// Attributes of the request, like the client ip or the authentication method,
+/// referenced in the policies as `context.<name>`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Context {
+    pub attrs: HashMap<String, AttributeValue>,
+    /// Instant of the request, referenced by the temporal requirements. If it's `None`,
+    /// the engine uses the system clock. The weekday and the hour of the instant are
+    /// taken in UTC.
+    pub time: Option<SystemTime>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an attribute to the context, replacing the previous value.
+    pub fn with_attr(mut self, name: impl ToString, value: impl Into<AttributeValue>) -> Self {
+        self.attrs.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Pins the instant of the request.
+    pub fn with_time(mut self, time: SystemTime) -> Self {
+        self.time = Some(time);
+        self
+    }
+}
+
+impl From<HashMap<String, AttributeValue>> for Context {
+    fn from(attrs: HashMap<String, AttributeValue>) -> Self {
+        Self { attrs, time: None }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ContextRepr {
+    pub attrs: HashMap<Arc<str>, Value>,
+    pub time: SystemTime,
+}
+
+impl ContextRepr {
+    pub(crate) fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    pub(crate) fn get_attribute(&self, attr: &ContextAttribute) -> Option<Value> {
+        self.attrs.get(&attr.0 .0).cloned()
+    }
+}
+
+impl From<&Context> for ContextRepr {
+    fn from(context: &Context) -> Self {
+        Self {
+            attrs: transform_attributes(&context.attrs),
+            time: context.time.unwrap_or_else(SystemTime::now),
+        }
+    }
+}
+
+impl From<Option<&Context>> for ContextRepr {
+    fn from(context: Option<&Context>) -> Self {
+        match context {
+            Some(context) => ContextRepr::from(context),
+            None => ContextRepr {
+                attrs: HashMap::new(),
+                time: SystemTime::now(),
+            },
+        }
+    }
+}
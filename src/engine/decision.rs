@@ -0,0 +1,58 @@
+use getset::Getters;
+
+use crate::language::policy::Permission;
+
+use super::Permissions;
+
+/// Outcome of an authorization request, returned by [`Engine::decide`](super::Engine::decide)
+/// and [`Engine::decide_permission`](super::Engine::decide_permission). The configuration
+/// faults, like a missing resource or environment, are errors instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    /// The permissions granted to the actor, without the revoked ones.
+    Allow(Permissions),
+    /// Some deny policy is applied and no permission remains granted.
+    Deny(DenyReason),
+    /// No policy is applied to the actor.
+    NotApplicable,
+}
+
+impl Decision {
+    pub(crate) fn new(mut granted: Permissions, denied: &[Permission]) -> Self {
+        granted.remove_permissions(denied);
+        if !granted.is_empty() {
+            return Decision::Allow(granted);
+        }
+        if denied.is_empty() {
+            return Decision::NotApplicable;
+        }
+
+        let mut revoked = Permissions::new();
+        for permission in denied {
+            if !revoked.has(&permission.0) {
+                revoked.append_permissions(std::slice::from_ref(permission), &[]);
+            }
+        }
+
+        Decision::Deny(DenyReason { revoked })
+    }
+
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Decision::Allow(_))
+    }
+
+    /// Returns the granted permissions, or `None` if the actor isn't allowed.
+    pub fn permissions(&self) -> Option<&Permissions> {
+        match self {
+            Decision::Allow(permissions) => Some(permissions),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct DenyReason {
+    /// The permissions revoked by the deny policies applied, without duplicates.
+    revoked: Permissions,
+}
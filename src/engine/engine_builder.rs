@@ -0,0 +1,75 @@
+use super::{minos_engine::EngineStorage, Engine};
+
+/// Behavior of the [Engine] when the [Storage](crate::language::storage::Storage) has no resource
+/// for the requested type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownResourceFallback {
+    /// Fails with [`Error::ResourceNotFound`](crate::Error::ResourceNotFound).
+    #[default]
+    Error,
+    /// No policy is applied, so the actor has no permissions.
+    Deny,
+    /// Applies the policies of the resource declared as `resource * { ... }`. Fails with
+    /// [`Error::ResourceNotFound`](crate::Error::ResourceNotFound) if it isn't declared.
+    WildcardResource,
+}
+
+/// Behavior of the [Engine] when the resource has no environment with the requested name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownEnvironmentFallback {
+    /// Applies only the policies of the default environment. This is the default.
+    #[default]
+    DefaultEnvironment,
+    /// Fails with [`Error::EnvironmentNotFound`](crate::Error::EnvironmentNotFound).
+    Error,
+    /// No policy is applied, so the actor has no permissions.
+    Deny,
+}
+
+/// Configures an [Engine], see [`Engine::builder`].
+#[derive(Debug, Clone)]
+pub struct EngineBuilder<'s> {
+    storage: EngineStorage<'s>,
+    unknown_resource: UnknownResourceFallback,
+    unknown_environment: UnknownEnvironmentFallback,
+    check_requests: bool,
+}
+
+impl<'s> EngineBuilder<'s> {
+    pub(crate) fn new(storage: EngineStorage<'s>) -> Self {
+        Self {
+            storage,
+            unknown_resource: UnknownResourceFallback::default(),
+            unknown_environment: UnknownEnvironmentFallback::default(),
+            check_requests: false,
+        }
+    }
+
+    pub fn unknown_resource(mut self, fallback: UnknownResourceFallback) -> Self {
+        self.unknown_resource = fallback;
+        self
+    }
+
+    pub fn unknown_environment(mut self, fallback: UnknownEnvironmentFallback) -> Self {
+        self.unknown_environment = fallback;
+        self
+    }
+
+    /// Checks that the requests use the actor types, resource types, statuses and permissions
+    /// declared in the [Schema](crate::language::schema::Schema). The methods that return
+    /// a result fail with [`Error::Undeclared`](crate::Error::Undeclared) if the request uses
+    /// an undeclared name, and the methods that list resources skip them. Disabled by default.
+    pub fn check_requests(mut self, check_requests: bool) -> Self {
+        self.check_requests = check_requests;
+        self
+    }
+
+    pub fn build(self) -> Engine<'s> {
+        Engine {
+            storage: self.storage,
+            unknown_resource: self.unknown_resource,
+            unknown_environment: self.unknown_environment,
+            check_requests: self.check_requests,
+        }
+    }
+}
@@ -39,7 +39,8 @@ impl<'a> EngineInfo<'a> {
         }
     }
 
-    /// Returns an iterator with the resource's [`Environment`]s, if it exists.
+    /// Returns an iterator with the resource's [`Environment`]s, if it exists. The environments
+    /// of a resource type include the policies inherited with `extends`.
     ///
     /// # Parameters
     ///
@@ -60,7 +61,7 @@ impl<'a> EngineInfo<'a> {
                 self.storage
                     .resources()
                     .get(&r_type)
-                    .map(|r| r.environments().values())
+                    .map(|r| r.effective_environments().values())
             }
             Criteria::ResourceId(id) => {
                 for attr_resource in self.storage.attributed_resources().values() {
@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use getset::Getters;
+
+use crate::{
+    language::{
+        environment::Environment,
+        policy::{Effect, Permission, Policy},
+        requirements::Requirement,
+        rule::Rule,
+    },
+    parser::tokens::Identifier,
+};
+
+use super::{ActorRepr, ContextRepr, Permissions, ResourceRepr};
+
+/// Result of a [Requirement] evaluated during the authorization process.
+#[derive(Debug, Clone, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct RequirementTrace<'s> {
+    requirement: &'s Requirement,
+    /// Is `None` if the operation is not permitted between the requirement's operands.
+    result: Option<bool>,
+    /// Traces of the inner requirements of an `any`, `all` or `not` block, empty otherwise.
+    children: Vec<RequirementTrace<'s>>,
+}
+
+impl<'s> RequirementTrace<'s> {
+    pub(crate) fn evaluate(
+        requirement: &'s Requirement,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Self {
+        let children = match requirement {
+            Requirement::Block(condition) => condition
+                .requirements()
+                .into_iter()
+                .map(|requirement| Self::evaluate(requirement, actor, resource, context))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Self {
+            requirement,
+            result: requirement.apply(actor, resource, context),
+            children,
+        }
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.result.unwrap_or_default()
+    }
+}
+
+/// Results of all the requirements of a [Rule]. Unlike the authorization process,
+/// every requirement is evaluated, even after the first failure.
+#[derive(Debug, Clone, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct RuleTrace<'s> {
+    rule: &'s Rule,
+    requirements: Vec<RequirementTrace<'s>>,
+}
+
+impl<'s> RuleTrace<'s> {
+    pub(crate) fn evaluate(
+        rule: &'s Rule,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Self {
+        let requirements = rule
+            .requirements()
+            .iter()
+            .map(|requirement| RequirementTrace::evaluate(requirement, actor, resource, context))
+            .collect();
+
+        Self { rule, requirements }
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.requirements.iter().all(RequirementTrace::is_satisfied)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct PolicyTrace<'s> {
+    policy: &'s Policy,
+    rules: Vec<RuleTrace<'s>>,
+}
+
+impl<'s> PolicyTrace<'s> {
+    pub(crate) fn evaluate(
+        policy: &'s Policy,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Self {
+        let rules = policy
+            .rules()
+            .iter()
+            .map(|rule| RuleTrace::evaluate(rule, actor, resource, context))
+            .collect();
+
+        Self { policy, rules }
+    }
+
+    /// Returns the first satisfied rule, the one that grants (or denies) the policy's permissions.
+    pub fn applied_rule(&self) -> Option<&RuleTrace<'s>> {
+        self.rules.iter().find(|rule| rule.is_satisfied())
+    }
+
+    pub fn is_applied(&self) -> bool {
+        self.applied_rule().is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct EnvironmentTrace<'s> {
+    environment: &'s Environment,
+    policies: Vec<PolicyTrace<'s>>,
+}
+
+impl<'s> EnvironmentTrace<'s> {
+    pub(crate) fn evaluate(
+        environment: &'s Environment,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Self {
+        let policies = environment
+            .policies()
+            .iter()
+            .map(|policy| PolicyTrace::evaluate(policy, actor, resource, context))
+            .collect();
+
+        Self {
+            environment,
+            policies,
+        }
+    }
+}
+
+/// Detailed report of an authorization process, returned by [`Engine::explain`].
+///
+/// [`Engine::explain`]: super::Engine::explain
+#[derive(Debug, Clone, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct Explanation<'s> {
+    actor_id: Arc<str>,
+    /// Identifier of the resource block consulted.
+    resource_identifier: Identifier,
+    /// Id of the resource block consulted, only for attributed resources.
+    resource_id: Option<&'s Arc<str>>,
+    /// Environments in the order they were consulted.
+    environments: Vec<EnvironmentTrace<'s>>,
+    /// Granted permissions, after removing the denied ones.
+    permissions: Permissions,
+}
+
+impl<'s> Explanation<'s> {
+    pub(crate) fn new(
+        actor_id: Arc<str>,
+        resource_identifier: Identifier,
+        resource_id: Option<&'s Arc<str>>,
+        environments: Vec<EnvironmentTrace<'s>>,
+    ) -> Self {
+        let mut permissions = Permissions::new();
+        let mut denied_permissions = vec![];
+        for policy in environments.iter().flat_map(|env| env.policies()) {
+            if !policy.is_applied() {
+                continue;
+            }
+
+            match policy.policy().effect() {
+                Effect::Allow => permissions
+                    .append_permissions(policy.policy().permissions(), policy.policy().patterns()),
+                Effect::Deny => denied_permissions.extend_from_slice(policy.policy().permissions()),
+            }
+        }
+        permissions.remove_permissions(&denied_permissions);
+
+        Self {
+            actor_id,
+            resource_identifier,
+            resource_id,
+            environments,
+            permissions,
+        }
+    }
+
+    pub fn is_authorized(&self) -> bool {
+        !self.permissions.is_empty()
+    }
+
+    fn applied_rules(&self, permission: &str, effect: Effect) -> Vec<&RuleTrace<'s>> {
+        let permission = Permission::from(permission);
+        self.environments
+            .iter()
+            .flat_map(|env| env.policies())
+            .filter(|policy| policy.policy().effect() == &effect && policy.policy().covers(&permission))
+            .filter_map(PolicyTrace::applied_rule)
+            .collect()
+    }
+
+    /// Returns the rules that grant the permission. The permission can be still denied,
+    /// see [`Explanation::denying_rules`].
+    pub fn granting_rules(&self, permission: &str) -> Vec<&RuleTrace<'s>> {
+        self.applied_rules(permission, Effect::Allow)
+    }
+
+    /// Returns the rules of the deny policies that revoke the permission.
+    pub fn denying_rules(&self, permission: &str) -> Vec<&RuleTrace<'s>> {
+        self.applied_rules(permission, Effect::Deny)
+    }
+}
@@ -1,21 +1,38 @@
 use either::Either;
-use std::{borrow::Cow, sync::Arc};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    ops::Deref,
+    sync::Arc,
+};
 
 use crate::{
     errors::{Error, MinosResult},
     language::{
-        environment::Environment, policy::Permission, resource::AttributedResource,
-        resource::Resource as InternalResource, storage::Storage,
+        condition::Condition,
+        environment::Environment,
+        policy::{Effect, Permission},
+        requirements::{Assertion, Attribute, ComparableValue, Requirement, Value},
+        resource::AttributedResource,
+        resource::Resource as InternalResource,
+        storage::Storage,
     },
+    parser::tokens::{Identifier, ResourceAttribute},
 };
 
-use super::{Actor, ActorRepr, EngineInfo, Permissions, Resource, ResourceRepr};
+use super::{
+    engine_builder::{EngineBuilder, UnknownEnvironmentFallback, UnknownResourceFallback},
+    partial_evaluation::PartialEvaluator,
+    Actor, ActorRepr, Context, ContextRepr, Decision, EngineInfo, EnvironmentTrace, Explanation,
+    Permissions, Resource, ResourceRepr,
+};
 
 #[derive(Debug)]
 pub struct AuthorizeRequest<'a> {
     pub env_name: Option<&'a str>,
     pub actor: &'a Actor,
     pub resource: &'a Resource,
+    /// Attributes of the request, referenced in the policies as `context.<name>`.
+    pub context: Option<&'a Context>,
 }
 
 #[derive(Debug)]
@@ -23,6 +40,8 @@ pub struct FindPermissionRequest<'a> {
     pub env_name: Option<&'a str>,
     pub actor: &'a Actor,
     pub resource: &'a Resource,
+    /// Attributes of the request, referenced in the policies as `context.<name>`.
+    pub context: Option<&'a Context>,
     pub permission: String,
 }
 
@@ -31,49 +50,313 @@ pub struct FindPermissionsRequest<'a> {
     pub env_name: Option<&'a str>,
     pub actor: &'a Actor,
     pub resource: &'a Resource,
+    /// Attributes of the request, referenced in the policies as `context.<name>`.
+    pub context: Option<&'a Context>,
     pub permissions: Vec<String>,
 }
 
+impl<'a> AuthorizeRequest<'a> {
+    /// Request without context.
+    pub fn new(env_name: Option<&'a str>, actor: &'a Actor, resource: &'a Resource) -> Self {
+        Self {
+            env_name,
+            actor,
+            resource,
+            context: None,
+        }
+    }
+
+    pub fn with_context(mut self, context: &'a Context) -> Self {
+        self.context = Some(context);
+        self
+    }
+}
+
+impl<'a> FindPermissionRequest<'a> {
+    /// Request without context.
+    pub fn new(
+        env_name: Option<&'a str>,
+        actor: &'a Actor,
+        resource: &'a Resource,
+        permission: String,
+    ) -> Self {
+        Self {
+            env_name,
+            actor,
+            resource,
+            context: None,
+            permission,
+        }
+    }
+
+    pub fn with_context(mut self, context: &'a Context) -> Self {
+        self.context = Some(context);
+        self
+    }
+}
+
+impl<'a> FindPermissionsRequest<'a> {
+    /// Request without context.
+    pub fn new(
+        env_name: Option<&'a str>,
+        actor: &'a Actor,
+        resource: &'a Resource,
+        permissions: Vec<String>,
+    ) -> Self {
+        Self {
+            env_name,
+            actor,
+            resource,
+            context: None,
+            permissions,
+        }
+    }
+
+    pub fn with_context(mut self, context: &'a Context) -> Self {
+        self.context = Some(context);
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct FilterResourcesRequest<'a, I> {
+    pub env_name: Option<&'a str>,
+    pub actor: &'a Actor,
+    /// Attributes of the request, referenced in the policies as `context.<name>`.
+    pub context: Option<&'a Context>,
+    /// The candidate resources.
+    pub resources: I,
+    pub permission: &'a str,
+}
+
+#[derive(Debug)]
+pub struct AttributedResourcesRequest<'a> {
+    pub env_name: Option<&'a str>,
+    pub actor: &'a Actor,
+    /// Attributes of the request, referenced in the policies as `context.<name>`.
+    pub context: Option<&'a Context>,
+}
+
+#[derive(Debug)]
+pub struct BatchItem<'a> {
+    pub env_name: Option<&'a str>,
+    pub actor: &'a Actor,
+    pub resource: &'a Resource,
+    pub permission: &'a str,
+}
+
+#[derive(Debug)]
+pub struct BatchAuthorizeRequest<'a> {
+    /// Attributes of the request, referenced in the policies as `context.<name>`. Shared
+    /// by all the items.
+    pub context: Option<&'a Context>,
+    pub items: &'a [BatchItem<'a>],
+}
+
+#[derive(Debug)]
+pub struct WhoCanAccessRequest<'a> {
+    pub env_name: Option<&'a str>,
+    pub resource: &'a Resource,
+    pub permission: &'a str,
+}
+
+#[derive(Debug)]
+pub struct ResourceConditionRequest<'a> {
+    pub env_name: Option<&'a str>,
+    pub actor: &'a Actor,
+    /// Attributes of the request, referenced in the policies as `context.<name>`.
+    pub context: Option<&'a Context>,
+    /// Type of the resources, the other resource attributes remain unknown.
+    pub resource_type: &'a str,
+    pub permission: &'a str,
+}
+
+/// Place of the undeclared names found by [`EngineBuilder::check_requests`].
+const REQUEST_PLACE: &str = "the request";
+
+/// Resource type, id of the attributed resource and environment's name.
+type EnvironmentsKey<'a> = (&'a str, Option<Arc<str>>, Option<&'a str>);
+
 struct InternalAuthorizeRequest<'a> {
     pub env_name: Option<&'a str>,
     pub actor: &'a ActorRepr,
     pub resource: &'a ResourceRepr,
-    pub minos_resource: Either<&'a InternalResource, &'a AttributedResource>,
+    pub context: &'a ContextRepr,
+    pub minos_resource: MinosResource<'a>,
 }
 
 struct InternalFindPermissionRequest<'a> {
     pub env_name: Option<&'a str>,
     pub actor: &'a ActorRepr,
     pub resource: &'a ResourceRepr,
-    pub minos_resource: Either<&'a InternalResource, &'a AttributedResource>,
+    pub context: &'a ContextRepr,
+    pub minos_resource: MinosResource<'a>,
     pub permission: &'a str,
 }
 
+/// Resource block that contains the policies of a request, or `None` if the resource type
+/// is unknown and the engine is configured with [`UnknownResourceFallback::Deny`].
+type MinosResource<'r> = Option<Either<&'r InternalResource, &'r AttributedResource>>;
+
+/// [Storage] of an [Engine], borrowed or shared with the clones of the engine.
+#[derive(Debug, Clone)]
+pub(crate) enum EngineStorage<'s> {
+    Borrowed(&'s Storage),
+    Shared(Arc<Storage>),
+}
+
+impl Deref for EngineStorage<'_> {
+    type Target = Storage;
+    fn deref(&self) -> &Self::Target {
+        match self {
+            EngineStorage::Borrowed(storage) => storage,
+            EngineStorage::Shared(storage) => storage,
+        }
+    }
+}
+
+/// Engine that owns its [Storage]: it's `Send + Sync + 'static`, and the clones share
+/// the same [Storage] instead of copying it.
+pub type SharedEngine = Engine<'static>;
+
 #[derive(Debug, Clone)]
 pub struct Engine<'s> {
-    storage: Cow<'s, Storage>,
+    pub(crate) storage: EngineStorage<'s>,
+    pub(crate) unknown_resource: UnknownResourceFallback,
+    pub(crate) unknown_environment: UnknownEnvironmentFallback,
+    pub(crate) check_requests: bool,
 }
 
 impl<'s> Engine<'s> {
     pub fn new(storage: &'s Storage) -> Self {
-        Self {
-            storage: Cow::Borrowed(storage),
+        Self::builder(storage).build()
+    }
+
+    /// Returns an [EngineBuilder] to configure the behavior with unknown resource types
+    /// and environments.
+    pub fn builder(storage: &'s Storage) -> EngineBuilder<'s> {
+        EngineBuilder::new(EngineStorage::Borrowed(storage))
+    }
+
+    /// Returns an [EngineBuilder] of a [SharedEngine].
+    pub fn shared_builder(storage: Arc<Storage>) -> EngineBuilder<'static> {
+        EngineBuilder::new(EngineStorage::Shared(storage))
+    }
+
+    /// Returns the environments consulted by the request: the default environment,
+    /// if exists, and the named environment, followed by the same environments of the
+    /// wildcard resource `resource * { ... }`. An unknown named environment is handled
+    /// by the [UnknownEnvironmentFallback].
+    fn request_environments<'r>(
+        &'r self,
+        minos_resource: MinosResource<'r>,
+        env_name: Option<&str>,
+    ) -> MinosResult<Vec<&'r Environment>> {
+        let Some(minos_resource) = minos_resource else {
+            return Ok(vec![]);
+        };
+        let wildcard = self
+            .storage
+            .wildcard_resource()
+            .filter(|wildcard| !minos_resource.left().is_some_and(|r| std::ptr::eq(r, *wildcard)));
+
+        let mut environments = Vec::with_capacity(4);
+        let default_env = minos_resource.either(
+            InternalResource::default_environment,
+            AttributedResource::default_environment,
+        );
+        environments.extend(default_env);
+        environments.extend(wildcard.and_then(InternalResource::default_environment));
+
+        if let Some(env_name) = env_name {
+            let env =
+                minos_resource.either(|r| r.get_environment(env_name), |r| r.get_environment(env_name));
+            let wildcard_env = wildcard.and_then(|wildcard| wildcard.get_environment(env_name));
+            match (env, wildcard_env) {
+                (None, None) => match self.unknown_environment {
+                    UnknownEnvironmentFallback::Error => {
+                        Err(Error::EnvironmentNotFound(env_name.to_string()))?
+                    }
+                    UnknownEnvironmentFallback::Deny => return Ok(vec![]),
+                    UnknownEnvironmentFallback::DefaultEnvironment => {}
+                },
+                (env, wildcard_env) => {
+                    environments.extend(env);
+                    environments.extend(wildcard_env);
+                }
+            }
         }
+
+        Ok(environments)
     }
 
     fn append_permissions(
         permissions: &mut Permissions,
+        denied_permissions: &mut Vec<Permission>,
         environment: &Environment,
         actor: &ActorRepr,
         resource: &ResourceRepr,
+        context: &ContextRepr,
     ) {
         for policy in environment.policies() {
-            if let Some(inner_permissions) = policy.apply(actor, resource) {
-                permissions.append_permissions(inner_permissions);
+            if let Some(inner_permissions) = policy.apply(actor, resource, context) {
+                match policy.effect() {
+                    Effect::Allow => {
+                        permissions.append_permissions(inner_permissions, policy.patterns())
+                    }
+                    Effect::Deny => denied_permissions.extend_from_slice(inner_permissions),
+                }
             }
         }
     }
 
+    /// Fails with [`Error::Undeclared`] if the requests are checked, see
+    /// [`EngineBuilder::check_requests`], and the actor's type or status isn't declared.
+    fn check_actor(&self, actor: &Actor) -> MinosResult<()> {
+        if !self.check_requests {
+            return Ok(());
+        }
+
+        let schema = self.storage.schema();
+        schema.check_actor_type(&actor.type_, REQUEST_PLACE)?;
+        match &actor.status {
+            Some(status) => schema.check_status(status, REQUEST_PLACE),
+            None => Ok(()),
+        }
+    }
+
+    /// Like [`Engine::check_actor`], with the resource's type and status and the permission.
+    fn check_resource(&self, resource: &Resource, permission: Option<&str>) -> MinosResult<()> {
+        if !self.check_requests {
+            return Ok(());
+        }
+
+        let schema = self.storage.schema();
+        if let Some(status) = &resource.status {
+            schema.check_status(status, REQUEST_PLACE)?;
+        }
+        self.check_resource_type(&resource.type_, permission)
+    }
+
+    fn check_resource_type(&self, resource_type: &str, permission: Option<&str>) -> MinosResult<()> {
+        if !self.check_requests {
+            return Ok(());
+        }
+
+        let schema = self.storage.schema();
+        schema.check_resource_type(resource_type, REQUEST_PLACE)?;
+        match permission {
+            Some(permission) => schema.check_permission(resource_type, permission, REQUEST_PLACE),
+            None => Ok(()),
+        }
+    }
+
+    /// Expands the actor's roles and groups through the storage's hierarchies.
+    fn actor_repr(&self, actor: &Actor) -> ActorRepr {
+        ActorRepr::from(actor)
+            .with_hierarchies(self.storage.roles_hierarchy(), self.storage.groups_hierarchy())
+    }
+
     fn find_attributed_resource(
         &self,
         resource_id: Arc<str>,
@@ -84,54 +367,67 @@ impl<'s> Engine<'s> {
             .get(&(resource.type_().into(), resource_id))
     }
 
-    /// Returns [Permissions] for attributed resource
-    fn authorize_attributed_resource(
-        &self,
-        request: InternalAuthorizeRequest,
-    ) -> MinosResult<Permissions> {
-        let actor = request.actor;
-        let resource = request.resource;
-        let attr_resource = request.minos_resource.unwrap_right();
-
-        let mut permissions = Permissions::new();
-        if let Some(default_env) = attr_resource.default_environment() {
-            Self::append_permissions(&mut permissions, default_env, actor, resource);
-        }
-
-        if let Some(env_name) = request.env_name {
-            let env = attr_resource
-                .get_environment(env_name)
-                .ok_or(Error::EnvironmentNotFound(env_name.to_string()))?;
-            Self::append_permissions(&mut permissions, env, actor, resource);
-        }
-
+    fn authorize_resource(&self, request: InternalAuthorizeRequest) -> MinosResult<Permissions> {
+        let actor_id = request.actor.id().clone();
+        let permissions = self.resource_permissions(request)?;
         if permissions.is_empty() {
-            return Err(Error::ActorNotAuthorized(actor.id().to_string()));
+            return Err(Error::ActorNotAuthorized(actor_id.to_string()));
         }
 
         Ok(permissions)
     }
 
-    fn authorize_resource(&self, request: InternalAuthorizeRequest) -> MinosResult<Permissions> {
-        let inner_resource = request.minos_resource.unwrap_left();
+    /// Returns the allowed [Permissions] minus the denied ones, collected from
+    /// the default and the named environments.
+    fn resource_permissions(&self, request: InternalAuthorizeRequest) -> MinosResult<Permissions> {
+        let environments = self.request_environments(request.minos_resource, request.env_name)?;
         let mut permissions = Permissions::new();
+        let mut denied_permissions = vec![];
 
-        if let Some(default_env) = inner_resource.default_environment() {
-            Self::append_permissions(&mut permissions, default_env, request.actor, request.resource);
+        for environment in environments {
+            Self::append_permissions(
+                &mut permissions,
+                &mut denied_permissions,
+                environment,
+                request.actor,
+                request.resource,
+                request.context,
+            );
         }
+        permissions.remove_permissions(&denied_permissions);
 
-        if let Some(env_name) = request.env_name {
-            let env = inner_resource
-                .get_environment(env_name)
-                .ok_or(Error::EnvironmentNotFound(env_name.to_string()))?;
-            Self::append_permissions(&mut permissions, env, request.actor, request.resource);
-        }
+        Ok(permissions)
+    }
 
-        if permissions.is_empty() {
-            return Err(Error::ActorNotAuthorized(request.actor.id().to_string()));
+    /// Returns the [AttributedResource] that matches with the resource's type and id or,
+    /// if not exists, the [InternalResource] that matches with the resource's type.
+    fn find_minos_resource(&self, resource: &ResourceRepr) -> MinosResult<MinosResource<'_>> {
+        if let Some(resource_id) = resource.id() {
+            if let Some(attr_resource) = self.find_attributed_resource(resource_id.clone(), resource) {
+                return Ok(Some(Either::Right(attr_resource)));
+            }
         }
 
-        Ok(permissions)
+        self.find_resource_by_type(resource.type_())
+            .map(|resource| resource.map(Either::Left))
+    }
+
+    /// Returns the [InternalResource] of the type, or applies the configured fallback.
+    fn find_resource_by_type(&self, type_: &str) -> MinosResult<Option<&InternalResource>> {
+        let resources = self.storage.resources();
+        let resource = match resources.get(&type_.into()) {
+            Some(resource) => resource,
+            None => match self.unknown_resource {
+                UnknownResourceFallback::Error => Err(Error::ResourceNotFound(type_.to_string()))?,
+                UnknownResourceFallback::Deny => return Ok(None),
+                UnknownResourceFallback::WildcardResource => self
+                    .storage
+                    .wildcard_resource()
+                    .ok_or(Error::ResourceNotFound(type_.to_string()))?,
+            },
+        };
+
+        Ok(Some(resource))
     }
 
     /// Return the granted [Permissions] if the [Actor] is authorized.
@@ -140,101 +436,161 @@ impl<'s> Engine<'s> {
     /// * Tha resource not exist into the [Storage].
     /// * The environment's name not exist into the [Storage].
     pub fn authorize(&self, request: AuthorizeRequest) -> MinosResult<Permissions> {
-        let env_name = request.env_name;
-        let actor = &ActorRepr::from(request.actor);
+        self.check_actor(request.actor)?;
+        self.check_resource(request.resource, None)?;
+        let actor = &self.actor_repr(request.actor);
         let resource = &ResourceRepr::from(request.resource);
+        let context = &ContextRepr::from(request.context);
+
+        self.authorize_resource(InternalAuthorizeRequest {
+            env_name: request.env_name,
+            actor,
+            resource,
+            context,
+            minos_resource: self.find_minos_resource(resource)?,
+        })
+    }
 
-        if let Some(resource_id) = resource.id() {
-            if let Some(attr_resource) = self.find_attributed_resource(resource_id.clone(), resource) {
-                return self.authorize_attributed_resource(InternalAuthorizeRequest {
-                    env_name,
-                    actor,
-                    resource,
-                    minos_resource: Either::Right(attr_resource),
-                });
-            }
-        }
+    /// Evaluates the request like [`Engine::authorize`], but an actor without permissions
+    /// isn't an error: the [Decision] tells if the policies grant permissions, revoke them,
+    /// or don't apply to the actor.
+    ///
+    /// This function fails if:
+    /// * Tha resource not exist into the [Storage].
+    /// * The environment's name not exist into the [Storage].
+    pub fn decide(&self, request: AuthorizeRequest) -> MinosResult<Decision> {
+        self.check_actor(request.actor)?;
+        self.check_resource(request.resource, None)?;
+        let actor = &self.actor_repr(request.actor);
+        let resource = &ResourceRepr::from(request.resource);
+        let context = &ContextRepr::from(request.context);
+        let minos_resource = self.find_minos_resource(resource)?;
+        let mut permissions = Permissions::new();
+        let mut denied_permissions = vec![];
 
-        if let Some(inner_resource) = self.storage.resources().get(&resource.type_().into()) {
-            return self.authorize_resource(InternalAuthorizeRequest {
-                env_name,
+        for environment in self.request_environments(minos_resource, request.env_name)? {
+            Self::append_permissions(
+                &mut permissions,
+                &mut denied_permissions,
+                environment,
                 actor,
                 resource,
-                minos_resource: Either::Left(inner_resource),
-            });
+                context,
+            );
         }
 
-        Err(Error::ResourceNotFound(resource.type_.to_string()))
+        Ok(Decision::new(permissions, &denied_permissions))
     }
 
-    fn is_permission_in_env(
-        environment: &Environment,
+    /// Like [`Engine::actor_has_permission`], but returns the [Decision] over the permission.
+    ///
+    /// This method fails if:
+    /// * Tha resource not exist into the [Storage].
+    /// * The environment's name not exist into the [Storage].
+    pub fn decide_permission(&self, request: FindPermissionRequest) -> MinosResult<Decision> {
+        self.check_actor(request.actor)?;
+        self.check_resource(request.resource, Some(&request.permission))?;
+        let actor = &self.actor_repr(request.actor);
+        let resource = &ResourceRepr::from(request.resource);
+        let context = &ContextRepr::from(request.context);
+        let minos_resource = self.find_minos_resource(resource)?;
+        let environments = self.request_environments(minos_resource, request.env_name)?;
+
+        Ok(Self::permission_decision(
+            &environments,
+            actor,
+            resource,
+            context,
+            &Permission::from(request.permission.as_str()),
+        ))
+    }
+
+    /// Evaluates the request like [`Engine::authorize`], but returns an [Explanation] with the
+    /// result of every policy, rule and requirement consulted. A denied request isn't an error,
+    /// see [`Explanation::is_authorized`].
+    ///
+    /// This function fails if:
+    /// * Tha resource not exist into the [Storage].
+    /// * The environment's name not exist into the [Storage].
+    pub fn explain(&self, request: AuthorizeRequest) -> MinosResult<Explanation<'_>> {
+        self.check_actor(request.actor)?;
+        self.check_resource(request.resource, None)?;
+        let actor = &self.actor_repr(request.actor);
+        let resource = &ResourceRepr::from(request.resource);
+        let context = &ContextRepr::from(request.context);
+        let minos_resource = self.find_minos_resource(resource)?;
+        let environments = self
+            .request_environments(minos_resource, request.env_name)?
+            .into_iter()
+            .map(|env| EnvironmentTrace::evaluate(env, actor, resource, context))
+            .collect();
+
+        Ok(Explanation::new(
+            actor.id().clone(),
+            match minos_resource {
+                Some(minos_resource) => minos_resource
+                    .either(InternalResource::identifier, AttributedResource::identifier)
+                    .clone(),
+                None => Identifier::from(resource.type_()),
+            },
+            minos_resource.and_then(Either::right).map(AttributedResource::id),
+            environments,
+        ))
+    }
+
+    /// Returns true if at least one allow policy grants the permission and no deny
+    /// policy revokes it.
+    fn is_permission_granted(
+        environments: &[&Environment],
         actor: &ActorRepr,
         resource: &ResourceRepr,
+        context: &ContextRepr,
         permission: &Permission,
     ) -> bool {
-        for policy in environment.policies() {
-            if policy.actor_has_permission(actor, resource, permission) {
-                return true;
-            }
-        }
-
-        false
+        Self::permission_decision(environments, actor, resource, context, permission).is_allowed()
     }
 
-    fn find_permission_in_attributed_resource(
-        &self,
-        request: InternalFindPermissionRequest,
-    ) -> MinosResult<bool> {
-        let actor = request.actor;
-        let resource = request.resource;
-        let attr_resource = request.minos_resource.unwrap_right();
-        let permission = &Permission::from(request.permission);
-        if let Some(default_env) = attr_resource.default_environment() {
-            return Ok(Self::is_permission_in_env(
-                default_env,
-                actor,
-                resource,
-                permission,
-            ));
-        }
+    fn permission_decision(
+        environments: &[&Environment],
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+        permission: &Permission,
+    ) -> Decision {
+        let permissions = std::slice::from_ref(permission);
+        let mut granted = false;
+        for policy in environments.iter().flat_map(|env| env.policies()) {
+            if !policy.actor_has_permission(actor, resource, context, permission) {
+                continue;
+            }
 
-        if let Some(env_name) = request.env_name {
-            let env = attr_resource
-                .get_environment(env_name)
-                .ok_or(Error::EnvironmentNotFound(env_name.to_string()))?;
-            return Ok(Self::is_permission_in_env(env, actor, resource, permission));
+            match policy.effect() {
+                Effect::Allow => granted = true,
+                Effect::Deny => return Decision::new(Permissions::new(), permissions),
+            }
         }
 
-        Ok(false)
+        match granted {
+            true => {
+                let mut granted = Permissions::new();
+                granted.append_permissions(permissions, &[]);
+                Decision::new(granted, &[])
+            }
+            false => Decision::NotApplicable,
+        }
     }
 
     fn find_permission_in_resource(&self, request: InternalFindPermissionRequest) -> MinosResult<bool> {
-        let inner_resource = request.minos_resource.unwrap_left();
-        let actor = request.actor;
-        let resource = request.resource;
+        let environments = self.request_environments(request.minos_resource, request.env_name)?;
         let permission = &Permission::from(request.permission);
 
-        if let Some(default_env) = inner_resource.default_environment() {
-            if Self::is_permission_in_env(default_env, actor, resource, permission) {
-                return Ok(true);
-            }
-        }
-
-        if let Some(env_name) = request.env_name {
-            let env = inner_resource
-                .get_environment(env_name)
-                .ok_or(Error::EnvironmentNotFound(env_name.to_string()))?;
-
-            return Ok(Self::is_permission_in_env(
-                env,
-                request.actor,
-                request.resource,
-                permission,
-            ));
-        }
-
-        Ok(false)
+        Ok(Self::is_permission_granted(
+            &environments,
+            request.actor,
+            request.resource,
+            request.context,
+            permission,
+        ))
     }
 
     /// Check if the actor has the selected permission over the resource.
@@ -243,34 +599,69 @@ impl<'s> Engine<'s> {
     /// * Tha resource not exist into the [Storage].
     /// * The environment's name not exist into the [Storage].
     pub fn actor_has_permission(&self, request: FindPermissionRequest) -> MinosResult<bool> {
-        let env_name = request.env_name;
-        let actor = &ActorRepr::from(request.actor);
+        self.check_actor(request.actor)?;
+        self.check_resource(request.resource, Some(&request.permission))?;
+        let actor = &self.actor_repr(request.actor);
         let resource = &ResourceRepr::from(request.resource);
-        let permission = request.permission;
+        let context = &ContextRepr::from(request.context);
+
+        self.find_permission_in_resource(InternalFindPermissionRequest {
+            env_name: request.env_name,
+            actor,
+            resource,
+            context,
+            minos_resource: self.find_minos_resource(resource)?,
+            permission: &request.permission,
+        })
+    }
 
-        if let Some(resource_id) = resource.id() {
-            if let Some(attr_resource) = self.find_attributed_resource(resource_id.clone(), resource) {
-                return self.find_permission_in_attributed_resource(InternalFindPermissionRequest {
-                    env_name,
+    /// Checks every item like [`Engine::actor_has_permission`] and returns the results in
+    /// the same order. The actors and resources referenced by several items are converted
+    /// once. The environments are resolved once per resource type, attributed resource and
+    /// environment's name.
+    pub fn authorize_batch<'a>(&self, request: BatchAuthorizeRequest<'a>) -> Vec<MinosResult<bool>> {
+        let context = &ContextRepr::from(request.context);
+        // keyed by reference, the different values with the same id are converted apart
+        let mut actors: HashMap<*const Actor, ActorRepr> = HashMap::new();
+        let mut resources: HashMap<*const Resource, ResourceRepr> = HashMap::new();
+        let mut environments: HashMap<EnvironmentsKey<'a>, Vec<&Environment>> = HashMap::new();
+
+        request
+            .items
+            .iter()
+            .map(|item| {
+                self.check_actor(item.actor)?;
+                self.check_resource(item.resource, Some(item.permission))?;
+                let actor = actors
+                    .entry(std::ptr::from_ref(item.actor))
+                    .or_insert_with(|| self.actor_repr(item.actor));
+                let resource = resources
+                    .entry(std::ptr::from_ref(item.resource))
+                    .or_insert_with(|| ResourceRepr::from(item.resource));
+                let minos_resource = self.find_minos_resource(resource)?;
+                let key = (
+                    item.resource.type_.as_str(),
+                    minos_resource
+                        .and_then(Either::right)
+                        .map(|attr_resource| attr_resource.id().clone()),
+                    item.env_name,
+                );
+                let environments = match environments.entry(key) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(self.request_environments(minos_resource, item.env_name)?)
+                    }
+                };
+
+                Ok(Self::is_permission_granted(
+                    environments,
                     actor,
                     resource,
-                    minos_resource: Either::Right(attr_resource),
-                    permission: &permission,
-                });
-            }
-        }
-
-        if let Some(inner_resource) = self.storage.resources().get(&resource.type_().into()) {
-            return self.find_permission_in_resource(InternalFindPermissionRequest {
-                env_name,
-                actor,
-                resource,
-                minos_resource: Either::Left(inner_resource),
-                permission: &permission,
-            });
-        }
-
-        Err(Error::ResourceNotFound(resource.type_.to_string()))
+                    context,
+                    &Permission::from(item.permission),
+                ))
+            })
+            .collect()
     }
 
     /// Check if the user has the selected permissions over the resource.
@@ -283,61 +674,206 @@ impl<'s> Engine<'s> {
     /// WARNING: this function search permissions individually, with performance penalties for
     /// long permissions list. In this case use [`Engine::authorize`]
     pub fn actor_has_permissions(&self, request: FindPermissionsRequest) -> MinosResult<bool> {
+        self.check_actor(request.actor)?;
+        self.check_resource(request.resource, None)?;
+        for permission in &request.permissions {
+            self.check_resource_type(&request.resource.type_, Some(permission))?;
+        }
         let env_name = request.env_name;
-        let actor = &ActorRepr::from(request.actor);
+        let actor = &self.actor_repr(request.actor);
         let resource = &ResourceRepr::from(request.resource);
-        let permissions = request.permissions;
-        let mut n_permissions_granted = 0;
+        let context = &ContextRepr::from(request.context);
+        let minos_resource = self.find_minos_resource(resource)?;
 
-        if let Some(resource_id) = resource.id() {
-            if let Some(attr_resource) = self.find_attributed_resource(resource_id.clone(), resource) {
-                for permission in &permissions {
-                    if self.find_permission_in_attributed_resource(InternalFindPermissionRequest {
-                        env_name,
-                        actor,
-                        resource,
-                        minos_resource: Either::Right(attr_resource),
-                        permission,
-                    })? {
-                        n_permissions_granted += 1;
-                    }
-                }
-                return Ok(n_permissions_granted == permissions.len());
+        for permission in &request.permissions {
+            if !self.find_permission_in_resource(InternalFindPermissionRequest {
+                env_name,
+                actor,
+                resource,
+                context,
+                minos_resource,
+                permission,
+            })? {
+                return Ok(false);
             }
         }
 
-        if let Some(inner_resource) = self.storage.resources().get(&resource.type_().into()) {
-            for permission in &permissions {
-                if self.find_permission_in_resource(InternalFindPermissionRequest {
-                    env_name,
+        Ok(true)
+    }
+
+    /// Returns the resources over which the actor has the permission, in the same order.
+    /// The actor and the context are converted only once for all the resources.
+    ///
+    /// The resources without policies or without the named environment are filtered out.
+    /// If the requests are checked, see [`EngineBuilder::check_requests`], an undeclared actor
+    /// has no resources and the resources with undeclared names are filtered out.
+    pub fn filter_resources<'r, I>(&self, request: FilterResourcesRequest<'_, I>) -> Vec<&'r Resource>
+    where
+        I: IntoIterator<Item = &'r Resource>,
+    {
+        if self.check_actor(request.actor).is_err() {
+            return vec![];
+        }
+        let actor = &self.actor_repr(request.actor);
+        let context = &ContextRepr::from(request.context);
+
+        request
+            .resources
+            .into_iter()
+            .filter(|candidate| {
+                if self.check_resource(candidate, Some(request.permission)).is_err() {
+                    return false;
+                }
+                let resource = &ResourceRepr::from(*candidate);
+                let Ok(minos_resource) = self.find_minos_resource(resource) else {
+                    return false;
+                };
+
+                self.find_permission_in_resource(InternalFindPermissionRequest {
+                    env_name: request.env_name,
                     actor,
                     resource,
-                    minos_resource: Either::Left(inner_resource),
-                    permission,
-                })? {
-                    n_permissions_granted += 1;
-                }
-            }
-        } else {
-            Err(Error::ResourceNotFound(resource.type_.to_string()))?
+                    context,
+                    minos_resource,
+                    permission: request.permission,
+                })
+                .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Returns the type, the id and the granted [Permissions] of every [AttributedResource]
+    /// that grants some permission to the actor. The resources are evaluated without owner,
+    /// status or custom attributes.
+    ///
+    /// The resources without the named environment are skipped. If the requests are checked,
+    /// see [`EngineBuilder::check_requests`], an undeclared actor has no resources.
+    pub fn attributed_resources_permissions(
+        &self,
+        request: AttributedResourcesRequest,
+    ) -> Vec<(Identifier, Arc<str>, Permissions)> {
+        if self.check_actor(request.actor).is_err() {
+            return vec![];
         }
+        let actor = &self.actor_repr(request.actor);
+        let context = &ContextRepr::from(request.context);
 
-        Ok(n_permissions_granted == permissions.len())
+        self.storage
+            .attributed_resources()
+            .values()
+            .filter_map(|attr_resource| {
+                let permissions = self
+                    .resource_permissions(InternalAuthorizeRequest {
+                        env_name: request.env_name,
+                        actor,
+                        resource: &ResourceRepr::from(attr_resource),
+                        context,
+                        minos_resource: Some(Either::Right(attr_resource)),
+                    })
+                    .ok()
+                    .filter(|permissions| !permissions.is_empty())?;
+
+                Some((
+                    attr_resource.identifier().clone(),
+                    attr_resource.id().clone(),
+                    permissions,
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns the [Condition] that the actor and the request context must satisfy to have
+    /// the permission over the resource, derived from the policies without evaluating
+    /// concrete actors. Is [`Condition::False`] if nobody has the permission. The search
+    /// of a role or a group is satisfied by the roles or groups that inherit it too.
+    ///
+    /// This method fails if:
+    /// * Tha resource not exist into the [Storage].
+    /// * The environment's name not exist into the [Storage].
+    pub fn who_can_access(&self, request: WhoCanAccessRequest) -> MinosResult<Condition> {
+        self.check_resource(request.resource, Some(request.permission))?;
+        let resource = ResourceRepr::from(request.resource);
+        let minos_resource = self.find_minos_resource(&resource)?;
+        let environments = self.request_environments(minos_resource, request.env_name)?;
+        let evaluator = PartialEvaluator::with_known_resource(resource.clone())
+            .with_hierarchies(self.storage.roles_hierarchy(), self.storage.groups_hierarchy());
+
+        Ok(evaluator.permission_condition(&environments, &Permission::from(request.permission)))
+    }
+
+    /// Returns the [Condition] over the resource attributes that a resource of the type
+    /// must satisfy to grant the permission to the actor, like the `WHERE` clause of a
+    /// list query, see [`SqlPredicate`](crate::sql::SqlPredicate). The attributed resources
+    /// of the type are selected by their id.
+    ///
+    /// This method fails if:
+    /// * Tha resource type not exist into the [Storage].
+    /// * The environment's name not exist into the [Storage].
+    pub fn resource_condition(&self, request: ResourceConditionRequest) -> MinosResult<Condition> {
+        self.check_actor(request.actor)?;
+        self.check_resource_type(request.resource_type, Some(request.permission))?;
+        let evaluator = PartialEvaluator::with_known_requester(
+            self.actor_repr(request.actor),
+            ContextRepr::from(request.context),
+            request.resource_type.into(),
+        );
+        let permission = Permission::from(request.permission);
+        let identifier = Identifier::from(request.resource_type);
+
+        let mut attributed_resources: Vec<&AttributedResource> = self
+            .storage
+            .attributed_resources()
+            .values()
+            .filter(|attr_resource| attr_resource.identifier() == &identifier)
+            .collect();
+        attributed_resources.sort_by_key(|attr_resource| attr_resource.id());
+
+        let mut alternatives = Vec::with_capacity(attributed_resources.len() + 1);
+        let mut attributed_ids = Vec::with_capacity(attributed_resources.len());
+        for attr_resource in attributed_resources {
+            let environments =
+                self.request_environments(Some(Either::Right(attr_resource)), request.env_name)?;
+            let id = Condition::Requirement(Requirement::Assertion(Assertion::new(
+                Attribute::Resource(ResourceAttribute::Id),
+                ComparableValue::Value(Value::String(attr_resource.id().clone())),
+            )));
+            alternatives.push(Condition::and([
+                id.clone(),
+                evaluator.permission_condition(&environments, &permission),
+            ]));
+            attributed_ids.push(id);
+        }
+
+        let resource = match self.find_resource_by_type(request.resource_type) {
+            Err(Error::ResourceNotFound(_)) if !alternatives.is_empty() => None,
+            result => result?,
+        };
+        let environments = self.request_environments(resource.map(Either::Left), request.env_name)?;
+        alternatives.push(Condition::and([
+            Condition::negate(Condition::or(attributed_ids)),
+            evaluator.permission_condition(&environments, &permission),
+        ]));
+
+        Ok(Condition::or(alternatives))
     }
 
     pub fn policies_len(&self) -> usize {
         self.storage.policies_len()
     }
 
-    pub fn info(&self) -> EngineInfo {
-        EngineInfo::new(self.storage.as_ref())
+    pub fn info(&self) -> EngineInfo<'_> {
+        EngineInfo::new(&self.storage)
     }
 }
 
 impl From<Storage> for Engine<'_> {
     fn from(storage: Storage) -> Self {
-        Self {
-            storage: Cow::Owned(storage),
-        }
+        EngineBuilder::new(EngineStorage::Shared(Arc::new(storage))).build()
+    }
+}
+
+impl From<Arc<Storage>> for Engine<'_> {
+    fn from(storage: Arc<Storage>) -> Self {
+        EngineBuilder::new(EngineStorage::Shared(storage)).build()
     }
 }
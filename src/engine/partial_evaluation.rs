@@ -0,0 +1,380 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    language::{
+        condition::Condition,
+        environment::Environment,
+        hierarchy::Hierarchy,
+        policy::{Effect, Permission, Policy},
+        requirements::{
+            Assertion, Attribute, ComparableValue, Comparison, Negation, Requirement, Search, Value,
+        },
+    },
+    parser::tokens::{ActorAttribute, Array, Identifier, ResourceAttribute},
+};
+
+use super::{ActorRepr, ContextRepr, ResourceRepr};
+
+/// Side of the request that is unknown when the policies are evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unknown {
+    /// The actor and the request context.
+    Requester,
+    /// The resource attributes, except the type.
+    Resource,
+}
+
+/// Operands of a binary requirement after replacing the known attribute by its value.
+enum Operands {
+    /// Both operands are unknown, or the known operand is already a value.
+    Unchanged,
+    /// The unknown attribute and the value of the known attribute. `swapped` indicates
+    /// that the unknown attribute was the right operand.
+    Bound {
+        attribute: Attribute,
+        value: Value,
+        swapped: bool,
+    },
+    /// The known attribute is missing, so the result depends on whether the unknown
+    /// attribute is missing too.
+    Missing(Attribute),
+}
+
+/// Evaluates the policies when one side of the request is unknown. The requirements
+/// over the unknown side remain in the resulting [Condition], with the known attributes
+/// replaced by their values.
+pub(crate) struct PartialEvaluator {
+    unknown: Unknown,
+    actor: ActorRepr,
+    resource: ResourceRepr,
+    context: ContextRepr,
+    /// Used to search the roles of an unknown actor.
+    roles_hierarchy: Hierarchy,
+    /// Used to search the groups of an unknown actor.
+    groups_hierarchy: Hierarchy,
+}
+
+impl PartialEvaluator {
+    pub(crate) fn with_known_resource(resource: ResourceRepr) -> Self {
+        Self {
+            unknown: Unknown::Requester,
+            actor: ActorRepr::default(),
+            resource,
+            context: ContextRepr::from(None),
+            roles_hierarchy: Hierarchy::default(),
+            groups_hierarchy: Hierarchy::default(),
+        }
+    }
+
+    /// The searches of a role or a group of the unknown actor are satisfied by the roles
+    /// or groups that inherit it too.
+    pub(crate) fn with_hierarchies(mut self, roles: &Hierarchy, groups: &Hierarchy) -> Self {
+        self.roles_hierarchy = roles.clone();
+        self.groups_hierarchy = groups.clone();
+        self
+    }
+
+    /// The resource type is known because the policies are declared by type.
+    pub(crate) fn with_known_requester(
+        actor: ActorRepr,
+        context: ContextRepr,
+        resource_type: Arc<str>,
+    ) -> Self {
+        Self {
+            unknown: Unknown::Resource,
+            actor,
+            resource: ResourceRepr {
+                id: None,
+                type_: resource_type,
+                owner: None,
+                status: None,
+                attrs: HashMap::new(),
+            },
+            context,
+            roles_hierarchy: Hierarchy::default(),
+            groups_hierarchy: Hierarchy::default(),
+        }
+    }
+
+    /// Returns the condition to have the permission in the environments: some allow policy
+    /// grants the permission and no deny policy revokes it.
+    pub(crate) fn permission_condition(
+        &self,
+        environments: &[&Environment],
+        permission: &Permission,
+    ) -> Condition {
+        let policies = || {
+            environments
+                .iter()
+                .flat_map(|env| env.policies())
+                .filter(|policy| policy.covers(permission))
+        };
+        let granted = Condition::or(
+            policies()
+                .filter(|policy| policy.effect() == &Effect::Allow)
+                .map(|policy| self.policy_condition(policy)),
+        );
+        let denied = Condition::or(
+            policies()
+                .filter(|policy| policy.effect() == &Effect::Deny)
+                .map(|policy| self.policy_condition(policy)),
+        );
+
+        Condition::and([granted, Condition::negate(denied)])
+    }
+
+    /// A policy is applied if at least one of its rules is satisfied.
+    fn policy_condition(&self, policy: &Policy) -> Condition {
+        Condition::or(policy.rules().iter().map(|rule| {
+            Condition::and(
+                rule.requirements()
+                    .iter()
+                    .map(|requirement| self.block_condition(requirement, false)),
+            )
+        }))
+    }
+
+    /// Replaces the requirements of the `any`, `all` and `not` blocks by their residual
+    /// conditions. A requirement whose operation is not permitted is neither satisfied nor
+    /// unsatisfied, so it's replaced by the constant that doesn't satisfy the rule, according
+    /// to the enclosing `not` blocks.
+    fn block_condition(&self, requirement: &Requirement, negated: bool) -> Condition {
+        match requirement {
+            Requirement::Block(condition) => self.residual_condition(condition, negated),
+            requirement => self
+                .requirement_condition(requirement)
+                .unwrap_or(Condition::from(negated)),
+        }
+    }
+
+    fn residual_condition(&self, condition: &Condition, negated: bool) -> Condition {
+        let conditions = |conditions: &[Condition], negated: bool| {
+            conditions
+                .iter()
+                .map(|condition| self.residual_condition(condition, negated))
+                .collect::<Vec<Condition>>()
+        };
+
+        match condition {
+            Condition::Requirement(requirement) => self.block_condition(requirement, negated),
+            Condition::And(inner) => Condition::and(conditions(inner, negated)),
+            Condition::Or(inner) => Condition::or(conditions(inner, negated)),
+            Condition::Not(inner) => Condition::negate(self.residual_condition(inner, !negated)),
+            Condition::Missing(attribute) if !self.is_unknown(attribute) => {
+                Condition::from(self.resolve(attribute).is_none())
+            }
+            condition => condition.clone(),
+        }
+    }
+
+    fn is_unknown(&self, attribute: &Attribute) -> bool {
+        match (self.unknown, attribute) {
+            (Unknown::Requester, Attribute::Actor(_) | Attribute::Context(_)) => true,
+            (Unknown::Resource, Attribute::Resource(attr)) => attr != &ResourceAttribute::Type,
+            _ => false,
+        }
+    }
+
+    fn is_unknown_operand(&self, operand: &ComparableValue) -> bool {
+        matches!(operand, ComparableValue::Attribute(attribute) if self.is_unknown(attribute))
+    }
+
+    fn references_unknown(&self, requirement: &Requirement) -> bool {
+        match requirement {
+            Requirement::Assertion(assertion) => {
+                self.is_unknown(assertion.left()) || self.is_unknown_operand(assertion.right())
+            }
+            Requirement::Negation(negation) => {
+                self.is_unknown(negation.left()) || self.is_unknown_operand(negation.right())
+            }
+            Requirement::Search(search) => {
+                self.is_unknown(search.left()) || self.is_unknown_operand(search.right())
+            }
+            Requirement::Comparison(comparison) => {
+                self.is_unknown(comparison.left()) || self.is_unknown_operand(comparison.right())
+            }
+            Requirement::Match(match_) => self.is_unknown(match_.left()),
+            Requirement::Temporal(_) => self.unknown == Unknown::Requester,
+            Requirement::Block(condition) => condition
+                .requirements()
+                .into_iter()
+                .any(|requirement| self.references_unknown(requirement)),
+        }
+    }
+
+    fn resolve(&self, attribute: &Attribute) -> Option<Value> {
+        attribute.resolve(&self.actor, &self.resource, &self.context)
+    }
+
+    fn bind(&self, left: &Attribute, right: &ComparableValue) -> Operands {
+        let (attribute, known, swapped) = match right {
+            ComparableValue::Attribute(right) if self.is_unknown(left) && !self.is_unknown(right) => {
+                (left, right, false)
+            }
+            ComparableValue::Attribute(right) if !self.is_unknown(left) && self.is_unknown(right) => {
+                (right, left, true)
+            }
+            _ => return Operands::Unchanged,
+        };
+
+        match self.resolve(known) {
+            Some(value) => Operands::Bound {
+                attribute: attribute.clone(),
+                value,
+                swapped,
+            },
+            None => Operands::Missing(attribute.clone()),
+        }
+    }
+
+    /// Returns `None` if the operation is not permitted.
+    fn requirement_condition(&self, requirement: &Requirement) -> Option<Condition> {
+        if !self.references_unknown(requirement) {
+            return requirement
+                .apply(&self.actor, &self.resource, &self.context)
+                .map(Condition::from);
+        }
+
+        let residual = match requirement {
+            Requirement::Assertion(assertion) => match self.bind(assertion.left(), assertion.right()) {
+                Operands::Unchanged => requirement.clone(),
+                Operands::Bound { attribute, value, .. } => {
+                    Requirement::Assertion(Assertion::new(attribute, ComparableValue::Value(value)))
+                }
+                // the custom attributes don't compare missing values, the others are equal
+                // if both are missing
+                Operands::Missing(_) if is_custom(assertion.left(), assertion.right()) => return None,
+                Operands::Missing(attribute) => return Some(missing_condition(attribute)),
+            },
+            Requirement::Negation(negation) => match self.bind(negation.left(), negation.right()) {
+                Operands::Unchanged => requirement.clone(),
+                Operands::Bound { attribute, value, .. } => {
+                    Requirement::Negation(Negation::new(attribute, ComparableValue::Value(value)))
+                }
+                Operands::Missing(_) if is_custom(negation.left(), negation.right()) => return None,
+                Operands::Missing(attribute) => {
+                    return Some(Condition::negate(missing_condition(attribute)))
+                }
+            },
+            Requirement::Comparison(comparison) => {
+                match self.bind(comparison.left(), comparison.right()) {
+                    Operands::Unchanged => requirement.clone(),
+                    Operands::Bound {
+                        attribute,
+                        value,
+                        swapped,
+                    } => {
+                        let operator = match swapped {
+                            true => comparison.operator().flip(),
+                            false => *comparison.operator(),
+                        };
+                        let number = value.as_number()?;
+                        Requirement::Comparison(Comparison::new(
+                            attribute,
+                            operator,
+                            ComparableValue::Value(Value::Number(number)),
+                        ))
+                    }
+                    Operands::Missing(_) => return None,
+                }
+            }
+            Requirement::Search(search) => return self.search_condition(search),
+            requirement => requirement.clone(),
+        };
+
+        Some(Condition::Requirement(residual))
+    }
+
+    /// A known list that contains an unknown attribute is the alternative of the
+    /// equalities between the attribute and every value of the list.
+    fn search_condition(&self, search: &Search) -> Option<Condition> {
+        if self.is_unknown(search.left()) {
+            let right = match search.right() {
+                ComparableValue::Attribute(attr) if !self.is_unknown(attr) => {
+                    ComparableValue::Value(self.resolve(attr)?)
+                }
+                right => right.clone(),
+            };
+
+            return Some(self.hierarchy_search_condition(search.left(), right));
+        }
+
+        let ComparableValue::Attribute(attribute) = search.right() else {
+            return None;
+        };
+        let reference = match search.left() {
+            Attribute::Actor(attr) => self.actor.get_search_attribute(attr)?,
+            attr => self.resolve(attr)?,
+        };
+        let Value::Array(Array(values)) = reference else {
+            return None;
+        };
+
+        Some(Condition::or(values.into_iter().map(|value| {
+            Condition::Requirement(Requirement::Assertion(Assertion::new(
+                attribute.clone(),
+                ComparableValue::Value(typed_value(attribute, value)),
+            )))
+        })))
+    }
+
+    /// The search of a role or a group in the unknown actor is the alternative of the
+    /// searches of the role or group and every name that inherits it. The search of a list
+    /// requires every value of the list.
+    fn hierarchy_search_condition(&self, left: &Attribute, right: ComparableValue) -> Condition {
+        let hierarchy = match left {
+            Attribute::Actor(ActorAttribute::Roles) => &self.roles_hierarchy,
+            Attribute::Actor(ActorAttribute::Groups) => &self.groups_hierarchy,
+            _ => &Hierarchy::default(),
+        };
+        let search = |right: ComparableValue| {
+            Condition::Requirement(Requirement::Search(Search::new(left.clone(), right)))
+        };
+        let search_seniors = |name: &Arc<str>| {
+            Condition::or(
+                hierarchy
+                    .seniors(name)
+                    .into_iter()
+                    .map(|senior| search(ComparableValue::Value(Value::String(senior)))),
+            )
+        };
+
+        match &right {
+            _ if hierarchy.is_empty() => search(right),
+            ComparableValue::Value(Value::Array(Array(values))) => {
+                Condition::and(values.iter().map(search_seniors))
+            }
+            ComparableValue::Value(value) => match value.as_text() {
+                Some(name) => search_seniors(name),
+                None => search(right),
+            },
+            ComparableValue::Attribute(_) => search(right),
+        }
+    }
+}
+
+/// The actor's id, type, roles and groups and the resource type are never missing.
+fn missing_condition(attribute: Attribute) -> Condition {
+    match attribute {
+        Attribute::Actor(
+            ActorAttribute::Id | ActorAttribute::Type | ActorAttribute::Roles | ActorAttribute::Groups,
+        )
+        | Attribute::Resource(ResourceAttribute::Type) => Condition::False,
+        attribute => Condition::Missing(attribute),
+    }
+}
+
+fn is_custom(left: &Attribute, right: &ComparableValue) -> bool {
+    left.is_custom() || right.is_custom_attribute()
+}
+
+/// The types and the status are identifiers, the other attributes are strings.
+fn typed_value(attribute: &Attribute, text: Arc<str>) -> Value {
+    match attribute {
+        Attribute::Actor(ActorAttribute::Type | ActorAttribute::Status)
+        | Attribute::Resource(ResourceAttribute::Type | ResourceAttribute::Status) => {
+            Value::Identifier(Identifier(text))
+        }
+        _ => Value::String(text),
+    }
+}
@@ -3,28 +3,66 @@ use std::ops::Deref;
 
 use crate::language::policy::Permission;
 
-/// Hight level abstraction of [Permission] list.
+/// Hight level abstraction of [Permission] list. The granted permissions can include
+/// patterns like `"document:*"`, see [`Permission::matches`].
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct Permissions(Vec<String>);
+pub struct Permissions {
+    granted: Vec<String>,
+    /// Granted permissions that are patterns.
+    patterns: Vec<Permission>,
+    /// Denied permissions that only revoke a part of some granted pattern.
+    revoked: Vec<Permission>,
+}
 
 impl Permissions {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Indicates if the item is granted, or matches some granted pattern, and no denied
+    /// permission matches it.
     pub fn has(&self, item: impl ToString) -> bool {
-        self.0.contains(&item.to_string())
+        let item = item.to_string();
+        (self.granted.contains(&item) || self.patterns.iter().any(|pattern| pattern.matches(&item)))
+            && !self.revoked.iter().any(|denied| denied.matches(&item))
+    }
+
+    /// Appends the permissions, and the ones of them that are patterns.
+    pub(crate) fn append_permissions(&mut self, permissions: &[Permission], patterns: &[Permission]) {
+        self.granted.extend(permissions.iter().map(|p| p.0.to_string()));
+        self.patterns.extend_from_slice(patterns);
+    }
+
+    /// Removes all the granted permissions that match with the denied ones. The denied
+    /// permissions that don't cover a granted pattern are kept to be checked by [`Permissions::has`].
+    pub(crate) fn remove_permissions(&mut self, permissions: &[Permission]) {
+        self.granted
+            .retain(|granted| !permissions.iter().any(|p| p.matches(granted)));
+        self.patterns
+            .retain(|pattern| !permissions.iter().any(|p| p.matches(&pattern.0)));
+
+        for denied in permissions {
+            let overlaps = self
+                .patterns
+                .iter()
+                .any(|pattern| pattern.matches(&denied.0) || denied.is_pattern());
+            if overlaps && !self.revoked.contains(denied) {
+                self.revoked.push(denied.clone());
+            }
+        }
     }
 
-    pub(crate) fn append_permissions(&mut self, permissions: &[Permission]) {
-        self.0.extend(permissions.iter().map(|p| p.0.to_string()));
+    /// Denied permissions that revoke a part of some granted pattern, like `"document:delete"`
+    /// with the granted `"document:*"`.
+    pub fn revoked(&self) -> &[Permission] {
+        &self.revoked
     }
 
     delegate! {
-        to self.0 {
+        to self.granted {
             pub fn len(&self) -> usize;
             pub fn is_empty(&self) -> bool;
-            pub fn iter(&self) -> ::std::slice::Iter<String>;
+            pub fn iter(&self) -> ::std::slice::Iter<'_, String>;
             pub fn contains(&self, permission: &String) -> bool;
         }
     }
@@ -32,13 +70,13 @@ impl Permissions {
 
 impl AsRef<[String]> for Permissions {
     fn as_ref(&self) -> &[String] {
-        &self.0
+        &self.granted
     }
 }
 
 impl Deref for Permissions {
     type Target = [String];
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.granted
     }
 }
@@ -0,0 +1,195 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use getset::Getters;
+
+use crate::{errors::MinosResult, language::storage::Storage, parser::imports::Imports, Error};
+
+use super::{container::load_storage, Engine, SharedEngine};
+
+/// Container that re-parses its paths on demand, see [`ReloadableContainer::reload`], or when
+/// the files change, see [`ReloadableContainer::watch`].
+///
+/// The [Storage] is shared behind an [Arc]: a reload replaces it atomically, and the engines
+/// built with a previous [snapshot](ReloadableContainer::storage) keep using it.
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct ReloadableContainer {
+    id: String,
+    description: String,
+    paths: Vec<PathBuf>,
+
+    #[getset(skip)]
+    storage: RwLock<Arc<Storage>>,
+
+    #[getset(skip)]
+    last_error: Mutex<Option<Error>>,
+
+    #[getset(skip)]
+    generation: AtomicU64,
+
+    /// The files imported by the last load, which can be out of the paths.
+    #[getset(skip)]
+    imported_files: Mutex<Vec<PathBuf>>,
+
+    /// Held during a reload, so the concurrent reloads are applied in order.
+    #[getset(skip)]
+    reload_lock: Mutex<()>,
+}
+
+impl ReloadableContainer {
+    pub(crate) fn new(
+        id: String,
+        description: String,
+        paths: Vec<PathBuf>,
+        storage: Storage,
+        imported_files: Vec<PathBuf>,
+    ) -> Self {
+        Self {
+            id,
+            description,
+            paths,
+            storage: RwLock::new(Arc::new(storage)),
+            last_error: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            imported_files: Mutex::new(imported_files),
+            reload_lock: Mutex::new(()),
+        }
+    }
+
+    /// Returns the current snapshot of the [Storage].
+    pub fn storage(&self) -> Arc<Storage> {
+        self.storage.read().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+
+    /// Returns an engine with the current snapshot of the [Storage].
+    pub fn engine(&self) -> SharedEngine {
+        Engine::from(self.storage())
+    }
+
+    /// Number of successful reloads.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Returns the error of the last reload, or `None` if it succeeded.
+    pub fn last_error(&self) -> Option<Error> {
+        self.last_error
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Parses and validates the paths again and replaces the [Storage]. If it fails,
+    /// the previous [Storage] is kept and the error is returned and saved, see
+    /// [`ReloadableContainer::last_error`]. A reload waits for the running one, if any.
+    pub fn reload(&self) -> MinosResult<()> {
+        let _reloading = self.reload_lock.lock().unwrap_or_else(|err| err.into_inner());
+        let mut imports = Imports::default();
+        let result = load_storage(&self.paths, &mut imports).map(|storage| {
+            *self.storage.write().unwrap_or_else(|err| err.into_inner()) = Arc::new(storage);
+            self.generation.fetch_add(1, Ordering::AcqRel);
+        });
+
+        *self.last_error.lock().unwrap_or_else(|err| err.into_inner()) = result.clone().err();
+        *self.imported_files.lock().unwrap_or_else(|err| err.into_inner()) = imports.files();
+
+        result
+    }
+
+    fn imported_files(&self) -> Vec<PathBuf> {
+        self.imported_files
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// Starts a thread that checks the modification times of the minos files every `interval`,
+    /// and reloads the container when some file is added, removed or modified. The files
+    /// imported with `import "file";` are checked too, even if they are out of the paths.
+    /// The thread stops when the returned [ContainerWatcher] is dropped.
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> ContainerWatcher {
+        let container = Arc::clone(self);
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_stopped = Arc::clone(&stopped);
+
+        let handle = thread::spawn(move || {
+            let mut files = modified_files(&container.paths, &container.imported_files());
+            while !thread_stopped.load(Ordering::Acquire) {
+                thread::park_timeout(interval);
+                let current_files = modified_files(&container.paths, &container.imported_files());
+                if current_files != files {
+                    files = current_files;
+                    let _ = container.reload();
+                }
+            }
+        });
+
+        ContainerWatcher {
+            stopped,
+            handle: Some(handle),
+        }
+    }
+}
+
+/// Handle of the thread started by [`ReloadableContainer::watch`].
+#[derive(Debug)]
+pub struct ContainerWatcher {
+    stopped: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for ContainerWatcher {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Returns the minos files of the paths and the imported files with their modification
+/// times, sorted by path. A missing imported file has no modification time.
+fn modified_files(paths: &[PathBuf], imported_files: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut files = vec![];
+    for path in paths {
+        collect_files(path, &mut files);
+    }
+    for path in imported_files {
+        files.push((path.clone(), modified_time(path)));
+    }
+    files.sort();
+    files.dedup();
+
+    files
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn collect_files(path: &Path, files: &mut Vec<(PathBuf, Option<SystemTime>)>) {
+    if path.is_dir() {
+        let Ok(dir) = fs::read_dir(path) else {
+            return;
+        };
+        for entry in dir.flatten() {
+            let path = entry.path();
+            let is_minos_file = path.extension().map(|p| p == "minos").unwrap_or_default();
+            if path.is_dir() || is_minos_file {
+                collect_files(&path, files);
+            }
+        }
+    } else if path.is_file() {
+        files.push((path.to_path_buf(), modified_time(path)));
+    }
+}
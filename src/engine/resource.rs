@@ -1,19 +1,23 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use getset::Getters;
 
 use crate::{
-    language::requirements::Value,
+    language::{requirements::Value, resource::AttributedResource},
     parser::tokens::{Identifier, ResourceAttribute},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+use super::{attributes::transform_attributes, AttributeValue};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Getters)]
 #[get = "pub"]
 pub struct Resource {
     pub id: Option<String>,
     pub type_: String,
     pub owner: Option<String>,
     pub status: Option<String>,
+    /// Custom attributes, referenced in the policies as `resource.attrs.<name>`.
+    pub attrs: HashMap<String, AttributeValue>,
 }
 
 pub trait AsResource {
@@ -29,17 +33,18 @@ pub trait TryIntoResource {
     fn try_into_resource(self) -> Result<Resource, Self::Error>;
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[derive(Debug, Clone, PartialEq, Getters)]
 #[get = "pub"]
 pub(crate) struct ResourceRepr {
     pub id: Option<Arc<str>>,
     pub type_: Arc<str>,
     pub owner: Option<Arc<str>>,
     pub status: Option<Arc<str>>,
+    pub attrs: HashMap<Arc<str>, Value>,
 }
 
 impl ResourceRepr {
-    pub(crate) fn get_attribute(&self, attr: ResourceAttribute) -> Option<Value> {
+    pub(crate) fn get_attribute(&self, attr: &ResourceAttribute) -> Option<Value> {
         match attr {
             ResourceAttribute::Id => self.id.as_ref().map(|id| Value::String(id.clone())),
             ResourceAttribute::Type => Some(Value::Identifier(Identifier(self.type_.clone()))),
@@ -48,6 +53,7 @@ impl ResourceRepr {
                 .status
                 .as_ref()
                 .map(|status| Value::Identifier(Identifier(status.clone()))),
+            ResourceAttribute::Attrs(name) => self.attrs.get(&name.0).cloned(),
         }
     }
 }
@@ -59,6 +65,21 @@ impl From<&Resource> for ResourceRepr {
             type_: Arc::from(resource.type_.as_str()),
             owner: resource.owner.as_ref().map(|owner| Arc::from(owner.as_str())),
             status: resource.status.as_ref().map(|status| Arc::from(status.as_str())),
+            attrs: transform_attributes(&resource.attrs),
+        }
+    }
+}
+
+/// A resource with the type and the id of the [AttributedResource], without owner,
+/// status or custom attributes.
+impl From<&AttributedResource> for ResourceRepr {
+    fn from(resource: &AttributedResource) -> Self {
+        Self {
+            id: Some(resource.id().clone()),
+            type_: resource.identifier().0.clone(),
+            owner: None,
+            status: None,
+            attrs: HashMap::new(),
         }
     }
 }
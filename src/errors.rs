@@ -1,7 +1,14 @@
-use std::{io, sync::Arc};
+use std::{
+    fmt::{self, Display, Write},
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use crate::parser::{v0_16, v0_16_m};
+use crate::parser::{v0_16, v0_16_m, v0_17, v0_17_m};
+use getset::Getters;
 use parse_display::ParseError;
+use pest::{error::InputLocation, RuleType};
 use thiserror::Error as ThisError;
 
 pub type MinosResult<T> = Result<T, Error>;
@@ -39,6 +46,66 @@ pub enum Error {
     #[error("macro '{0}' not found")]
     MacroNotExist(String),
 
+    #[error("macro '{name}' expects {expected} arguments, found {found}")]
+    MacroArity {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("parameter '${parameter}' not declared in macro '{name}'")]
+    MacroParameterNotExist { name: String, parameter: String },
+
+    #[error("parameter '${0}' used outside of a macro definition")]
+    MacroParameterOutsideMacro(String),
+
+    #[error("macro '{0}' imported with different definitions")]
+    DuplicatedMacro(String),
+
+    #[error("invalid pattern '{pattern}': {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+
+    #[error("invalid timestamp '{0}', expected RFC 3339 format")]
+    InvalidTimestamp(String),
+
+    #[error("empty hour range '{0}..{0}', the whole day is '0..24'")]
+    EmptyHourRange(u8),
+
+    #[error("cycle found in the hierarchy: {0}")]
+    HierarchyCycle(String),
+
+    #[error("cycle found in the resources inheritance: {0}")]
+    InheritanceCycle(String),
+
+    #[error("resource '{resource}' extends '{parent}' and '{other_parent}'")]
+    ConflictingParents {
+        resource: String,
+        parent: String,
+        other_parent: String,
+    },
+
+    #[error("the requirement '{0}' can't be written in SQL")]
+    UnsupportedSqlRequirement(String),
+
+    #[error("the resource attribute '{0}' has no column")]
+    MissingColumn(String),
+
+    #[error("cycle found in the imports: {0}")]
+    ImportCycle(String),
+
+    #[error("error in the imported file '{path}': {error}")]
+    Import { path: String, error: Box<Error> },
+
+    #[error("{kind} '{name}' is not declared in the schema, used in {place}")]
+    Undeclared {
+        kind: &'static str,
+        name: String,
+        place: String,
+    },
+
+    #[error("{0}")]
+    Located(Box<LocatedError>),
+
     // 3-party errors
     #[error("io err: {0}")]
     Io(String),
@@ -49,6 +116,12 @@ pub enum Error {
     #[error(transparent)]
     RuleV0_16M(Box<pest::error::Error<v0_16_m::Rule>>),
 
+    #[error(transparent)]
+    RuleV0_17(Box<pest::error::Error<v0_17::Rule>>),
+
+    #[error(transparent)]
+    RuleV0_17M(Box<pest::error::Error<v0_17_m::Rule>>),
+
     #[error(transparent)]
     ParseError(Arc<ParseError>),
 }
@@ -71,8 +144,209 @@ impl From<pest::error::Error<v0_16_m::Rule>> for Error {
     }
 }
 
+impl From<pest::error::Error<v0_17::Rule>> for Error {
+    fn from(err: pest::error::Error<v0_17::Rule>) -> Self {
+        Self::RuleV0_17(Box::new(err))
+    }
+}
+
+impl From<pest::error::Error<v0_17_m::Rule>> for Error {
+    fn from(err: pest::error::Error<v0_17_m::Rule>) -> Self {
+        Self::RuleV0_17M(Box::new(err))
+    }
+}
+
 impl From<ParseError> for Error {
     fn from(err: ParseError) -> Self {
         Self::ParseError(Arc::new(err))
     }
 }
+
+impl Error {
+    /// Adds the position of the offset in the source, unless the error has it already.
+    pub(crate) fn at(self, source: &str, offset: usize) -> Self {
+        match self {
+            Error::Located(mut located) => {
+                if located.position.is_none() {
+                    located.position = Some(SourcePosition::new(source, offset));
+                }
+                Error::Located(located)
+            }
+            error => Error::Located(Box::new(LocatedError {
+                path: None,
+                position: Some(SourcePosition::new(source, offset)),
+                error,
+            })),
+        }
+    }
+
+    /// Adds the path of the file where the error is found, unless the error has it already.
+    pub(crate) fn in_file(self, path: &Path) -> Self {
+        match self {
+            Error::Located(mut located) => {
+                if located.path.is_none() {
+                    located.path = Some(path.to_path_buf());
+                }
+                Error::Located(located)
+            }
+            error => Error::Located(Box::new(LocatedError {
+                path: Some(path.to_path_buf()),
+                position: None,
+                error,
+            })),
+        }
+    }
+
+    /// Returns the error without its location.
+    pub fn without_location(self) -> Self {
+        match self {
+            Error::Located(located) => located.error,
+            error => error,
+        }
+    }
+
+    /// The message of the syntax errors without the snippet of the source.
+    fn message(&self) -> String {
+        match self {
+            Error::RuleV0_16(err) => err.variant.message().to_string(),
+            Error::RuleV0_16M(err) => err.variant.message().to_string(),
+            Error::RuleV0_17(err) => err.variant.message().to_string(),
+            Error::RuleV0_17M(err) => err.variant.message().to_string(),
+            Error::Located(located) => located.error.message(),
+            error => error.to_string(),
+        }
+    }
+}
+
+/// Converts the syntax error, located in the source.
+pub(crate) fn syntax_error<R: RuleType>(err: pest::error::Error<R>, source: &str) -> Error
+where
+    Error: From<pest::error::Error<R>>,
+{
+    let offset = match err.location {
+        InputLocation::Pos(offset) => offset,
+        InputLocation::Span((start, _)) => start,
+    };
+
+    Error::from(err).at(source, offset)
+}
+
+/// Position of an error in a minos file. The lines and the columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct SourcePosition {
+    /// Byte offset in the file.
+    offset: usize,
+    line: usize,
+    column: usize,
+    /// Text of the line, without the line break.
+    line_text: String,
+}
+
+impl SourcePosition {
+    pub(crate) fn new(source: &str, offset: usize) -> Self {
+        let offset = (0..=offset.min(source.len()))
+            .rev()
+            .find(|offset| source.is_char_boundary(*offset))
+            .unwrap_or_default();
+        let line_start = source[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |index| offset + index);
+
+        Self {
+            offset,
+            line: source[..line_start].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            line_text: source[line_start..line_end].trim_end_matches('\r').to_string(),
+        }
+    }
+}
+
+/// Error found in a minos file, see [`Error::Located`]. The errors of a requirement, like an
+/// invalid timestamp, are located at the requirement, or at the macro call that expands it. The
+/// other errors of the model construction are located at the start of the resource.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct LocatedError {
+    path: Option<PathBuf>,
+    position: Option<SourcePosition>,
+    error: Error,
+}
+
+/// Returns `<path>:<line>:<column>`, omitting the unknown parts.
+pub(crate) fn source_location(path: Option<&Path>, position: Option<&SourcePosition>) -> String {
+    let path = path.map(|path| path.display().to_string());
+    let position = position.map(|position| format!("{}:{}", position.line, position.column));
+
+    [path, position]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>()
+        .join(":")
+}
+
+/// Renders the header with a snippet of the line and a caret under the column, see
+/// [`LocatedError::render`].
+pub(crate) fn render_source(
+    header: &str,
+    path: Option<&Path>,
+    position: Option<&SourcePosition>,
+) -> String {
+    let mut rendered = header.to_string();
+    let Some(position) = position else {
+        if path.is_some() {
+            let _ = write!(rendered, "\n --> {}", source_location(path, None));
+        }
+        return rendered;
+    };
+
+    let gutter = " ".repeat(position.line.to_string().len());
+    let indent: String = position
+        .line_text
+        .chars()
+        .take(position.column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let _ = write!(
+        rendered,
+        "\n{gutter}--> {}\n{gutter} |\n{} | {}\n{gutter} | {indent}^",
+        source_location(path, Some(position)),
+        position.line,
+        position.line_text,
+    );
+
+    rendered
+}
+
+impl LocatedError {
+    /// Returns `<path>:<line>:<column>`, omitting the unknown parts.
+    fn location(&self) -> String {
+        source_location(self.path.as_deref(), self.position.as_ref())
+    }
+
+    /// Renders the error with a snippet of the line and a caret under the column, like:
+    ///
+    /// ```text
+    /// error: invalid timestamp 'yesterday', expected RFC 3339 format
+    ///  --> policies/document.minos:7:13
+    ///   |
+    /// 7 |             context.time before "yesterday";
+    ///   |             ^
+    /// ```
+    ///
+    /// Without position, only the message and the path are rendered.
+    pub fn render(&self) -> String {
+        render_source(
+            &format!("error: {}", self.error.message()),
+            self.path.as_deref(),
+            self.position.as_ref(),
+        )
+    }
+}
+
+impl Display for LocatedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location(), self.error.message())
+    }
+}
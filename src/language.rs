@@ -1,6 +1,10 @@
+pub mod condition;
 pub mod environment;
+pub mod hierarchy;
 pub mod policy;
 pub mod requirements;
 pub mod resource;
 pub mod rule;
+pub mod schema;
 pub mod storage;
+pub mod time;
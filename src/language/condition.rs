@@ -0,0 +1,169 @@
+use crate::engine::{ActorRepr, ContextRepr, ResourceRepr};
+
+use super::requirements::{Attribute, Requirement};
+
+/// Boolean expression of requirements, the result of evaluating the policies
+/// when some attributes are unknown. The `any`, `all` and `not` blocks of the rules
+/// are conditions too, see [`Requirement::Block`].
+///
+/// The constructors [`Condition::and`], [`Condition::or`] and [`Condition::negate`]
+/// simplify the expression, so a condition without unknown attributes is always
+/// [`Condition::True`] or [`Condition::False`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    True,
+    False,
+    Requirement(Requirement),
+    /// The attribute has no value. Like the engine, `actor.status = resource.status` is
+    /// satisfied when both attributes are missing, so with a missing `actor.status` it
+    /// becomes this condition over `resource.status`.
+    Missing(Attribute),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl From<bool> for Condition {
+    fn from(value: bool) -> Self {
+        match value {
+            true => Condition::True,
+            false => Condition::False,
+        }
+    }
+}
+
+impl Condition {
+    pub fn and(conditions: impl IntoIterator<Item = Condition>) -> Self {
+        let mut operands = vec![];
+        for condition in conditions {
+            match condition {
+                Condition::True => {}
+                Condition::False => return Condition::False,
+                Condition::And(inner) => operands.extend(inner),
+                condition => operands.push(condition),
+            }
+        }
+
+        match operands.len() {
+            0 => Condition::True,
+            1 => operands.remove(0),
+            _ => Condition::And(operands),
+        }
+    }
+
+    pub fn or(conditions: impl IntoIterator<Item = Condition>) -> Self {
+        let mut operands = vec![];
+        for condition in conditions {
+            match condition {
+                Condition::False => {}
+                Condition::True => return Condition::True,
+                Condition::Or(inner) => operands.extend(inner),
+                condition => operands.push(condition),
+            }
+        }
+
+        match operands.len() {
+            0 => Condition::False,
+            1 => operands.remove(0),
+            _ => Condition::Or(operands),
+        }
+    }
+
+    pub fn negate(condition: Condition) -> Self {
+        match condition {
+            Condition::True => Condition::False,
+            Condition::False => Condition::True,
+            Condition::Not(inner) => *inner,
+            condition => Condition::Not(Box::new(condition)),
+        }
+    }
+
+    /// Evaluates the condition like the requirements: `None` if some operation that
+    /// decides the result is not permitted.
+    pub(crate) fn apply(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<bool> {
+        match self {
+            Condition::True => Some(true),
+            Condition::False => Some(false),
+            Condition::Requirement(requirement) => requirement.apply(actor, resource, context),
+            Condition::Missing(attribute) => Some(attribute.resolve(actor, resource, context).is_none()),
+            Condition::And(conditions) => Self::apply_all(conditions, actor, resource, context),
+            Condition::Or(conditions) => Self::apply_any(conditions, actor, resource, context),
+            Condition::Not(condition) => condition.apply(actor, resource, context).map(|result| !result),
+        }
+    }
+
+    /// Returns `Some(true)` if some condition is satisfied, or `None` if none is satisfied
+    /// but some operation is not permitted.
+    fn apply_any(
+        conditions: &[Condition],
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<bool> {
+        let mut result = Some(false);
+        for condition in conditions {
+            match condition.apply(actor, resource, context) {
+                Some(true) => return Some(true),
+                Some(false) => {}
+                None => result = None,
+            }
+        }
+
+        result
+    }
+
+    /// Returns `Some(false)` if some condition is not satisfied, or `None` if none fails
+    /// but some operation is not permitted.
+    fn apply_all(
+        conditions: &[Condition],
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<bool> {
+        let mut result = Some(true);
+        for condition in conditions {
+            match condition.apply(actor, resource, context) {
+                Some(true) => {}
+                Some(false) => return Some(false),
+                None => result = None,
+            }
+        }
+
+        result
+    }
+
+    /// Returns the requirements of the condition, in order. The requirements of an inner
+    /// block are returned by the block's condition.
+    pub fn requirements(&self) -> Vec<&Requirement> {
+        match self {
+            Condition::Requirement(requirement) => vec![requirement],
+            Condition::And(conditions) | Condition::Or(conditions) => {
+                conditions.iter().flat_map(Condition::requirements).collect()
+            }
+            Condition::Not(condition) => condition.requirements(),
+            Condition::True | Condition::False | Condition::Missing(_) => vec![],
+        }
+    }
+
+    /// Returns the keyword of the block with this condition, see [`Requirement::Block`].
+    pub(crate) fn block_keyword(&self) -> &'static str {
+        match self {
+            Condition::Or(_) => "any",
+            Condition::Not(_) => "not",
+            _ => "all",
+        }
+    }
+
+    pub fn is_true(&self) -> bool {
+        matches!(self, Condition::True)
+    }
+
+    pub fn is_false(&self) -> bool {
+        matches!(self, Condition::False)
+    }
+}
@@ -1,8 +1,9 @@
-use derived::Ctor;
+use std::path::{Path, PathBuf};
+
 use getset::{Getters, MutGetters};
 
 use crate::{
-    errors::Error,
+    errors::{Error, SourcePosition},
     parser::tokens::{Identifier, Token},
     MinosResult,
 };
@@ -11,16 +12,40 @@ use super::policy::Policy;
 
 pub const DEFAULT_ENV_IDENTIFIER: &str = "DEFAULT";
 
-#[derive(Debug, Clone, Ctor, Getters, MutGetters, PartialEq)]
+#[derive(Debug, Clone, Getters, MutGetters)]
 pub struct Environment {
     #[get = "pub"]
     identifier: Identifier,
     #[getset(get = "pub", get_mut = "pub")]
     policies: Vec<Policy>,
+    /// The file of the environment, if it's declared with a name in a file.
+    #[get = "pub"]
+    path: Option<PathBuf>,
+    /// Position of the environment's first declaration in its file.
+    #[get = "pub"]
+    position: Option<SourcePosition>,
+}
+
+/// The environments with the same policies are equal wherever they are declared.
+impl PartialEq for Environment {
+    fn eq(&self, other: &Self) -> bool {
+        self.identifier == other.identifier && self.policies == other.policies
+    }
 }
 
 impl Environment {
+    /// Environment without location.
+    pub fn new(identifier: Identifier, policies: Vec<Policy>) -> Self {
+        Self {
+            identifier,
+            policies,
+            path: None,
+            position: None,
+        }
+    }
+
     fn from_named_env(tokens: &[Token]) -> MinosResult<Self> {
+        let (tokens, position) = Token::split_position(tokens);
         let identifier = tokens[0].inner_identifier().unwrap().clone();
         let policies = tokens
             .iter()
@@ -28,7 +53,10 @@ impl Environment {
             .map(Policy::try_from)
             .collect::<MinosResult<Vec<Policy>>>()?;
 
-        Ok(Self { identifier, policies })
+        Ok(Self {
+            position: position.cloned(),
+            ..Self::new(identifier, policies)
+        })
     }
 
     fn from_default_env(tokens: &[Token]) -> MinosResult<Self> {
@@ -37,10 +65,23 @@ impl Environment {
             .map(Policy::try_from)
             .collect::<MinosResult<Vec<Policy>>>()?;
 
-        Ok(Self {
-            identifier: Identifier(DEFAULT_ENV_IDENTIFIER.into()),
-            policies,
-        })
+        Ok(Self::new(Identifier(DEFAULT_ENV_IDENTIFIER.into()), policies))
+    }
+
+    /// Sets the file of the environment and its policies, unless they have it already.
+    pub(crate) fn in_file(&mut self, path: &Path) {
+        if self.path.is_none() && self.position.is_some() {
+            self.path = Some(path.to_path_buf());
+        }
+        for policy in &mut self.policies {
+            policy.in_file(path);
+        }
+    }
+
+    pub(crate) fn without_patterns(&mut self) {
+        for policy in &mut self.policies {
+            policy.without_patterns();
+        }
     }
 
     pub fn add_policy(&mut self, policy: Policy) {
@@ -0,0 +1,153 @@
+use std::{collections::HashSet, sync::Arc};
+
+use crate::{
+    errors::{Error, MinosResult},
+    parser::tokens::Token,
+};
+
+/// Inheritance between roles or groups, declared like `roles { admin > editor > viewer; }`:
+/// an actor with the role `admin` has the roles `editor` and `viewer` too.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Hierarchy {
+    /// Pairs of (senior, junior) in declaration order.
+    inheritances: Vec<(Arc<str>, Arc<str>)>,
+}
+
+impl Hierarchy {
+    pub fn inheritances(&self) -> &[(Arc<str>, Arc<str>)] {
+        &self.inheritances
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inheritances.is_empty()
+    }
+
+    /// Declares that `senior` inherits `junior`. Repeated declarations are ignored.
+    pub fn add_inheritance(&mut self, senior: Arc<str>, junior: Arc<str>) {
+        let inheritance = (senior, junior);
+        if !self.inheritances.contains(&inheritance) {
+            self.inheritances.push(inheritance);
+        }
+    }
+
+    pub fn merge(&mut self, hierarchy: Hierarchy) {
+        for (senior, junior) in hierarchy.inheritances {
+            self.add_inheritance(senior, junior);
+        }
+    }
+
+    fn juniors<'h>(&'h self, senior: &'h str) -> impl Iterator<Item = &'h Arc<str>> + 'h {
+        self.inheritances
+            .iter()
+            .filter(move |(name, _)| name.as_ref() == senior)
+            .map(|(_, junior)| junior)
+    }
+
+    /// Returns the names followed by all the names they inherit transitively, without duplicates.
+    pub fn expand(&self, names: &[Arc<str>]) -> Vec<Arc<str>> {
+        let mut expanded = names.to_vec();
+        let mut index = 0;
+        while index < expanded.len() {
+            let juniors: Vec<Arc<str>> = self
+                .juniors(&expanded[index])
+                .filter(|junior| !expanded.contains(junior))
+                .cloned()
+                .collect();
+            expanded.extend(juniors);
+            index += 1;
+        }
+
+        expanded
+    }
+
+    /// Returns the name followed by all the names that inherit it transitively, without
+    /// duplicates: the names whose [expansion](Hierarchy::expand) contains the name.
+    pub fn seniors(&self, name: &Arc<str>) -> Vec<Arc<str>> {
+        let mut seniors = vec![name.clone()];
+        let mut index = 0;
+        while index < seniors.len() {
+            let new_seniors: Vec<Arc<str>> = self
+                .inheritances
+                .iter()
+                .filter(|(senior, junior)| junior == &seniors[index] && !seniors.contains(senior))
+                .map(|(senior, _)| senior.clone())
+                .collect();
+            seniors.extend(new_seniors);
+            index += 1;
+        }
+
+        seniors
+    }
+
+    /// Fails with [`Error::HierarchyCycle`] if some name inherits itself, directly or transitively.
+    pub fn check_cycles(&self) -> MinosResult<()> {
+        let mut checked = HashSet::new();
+        for (senior, _) in &self.inheritances {
+            let mut path = vec![senior.clone()];
+            self.find_cycle(&mut path, &mut checked)?;
+        }
+
+        Ok(())
+    }
+
+    /// Depth-first search of a cycle from the last name of the path.
+    fn find_cycle(&self, path: &mut Vec<Arc<str>>, checked: &mut HashSet<Arc<str>>) -> MinosResult<()> {
+        let current = path[path.len() - 1].clone();
+        if checked.contains(&current) {
+            return Ok(());
+        }
+
+        for junior in self.juniors(&current) {
+            if let Some(start) = path.iter().position(|name| name == junior) {
+                let cycle: Vec<&str> = path[start..]
+                    .iter()
+                    .chain([junior])
+                    .map(|name| name.as_ref())
+                    .collect();
+                return Err(Error::HierarchyCycle(cycle.join(" > ")));
+            }
+
+            path.push(junior.clone());
+            self.find_cycle(path, checked)?;
+            path.pop();
+        }
+        checked.insert(current);
+
+        Ok(())
+    }
+}
+
+impl TryFrom<&Vec<Token>> for Hierarchy {
+    type Error = Error;
+
+    /// Builds the hierarchy with the chains of the declaration, like `admin > editor > viewer`.
+    fn try_from(chains: &Vec<Token>) -> Result<Self, Self::Error> {
+        let mut hierarchy = Hierarchy::default();
+        for chain in chains {
+            let Token::HierarchyChain(names) = chain else {
+                Err(Error::InvalidToken {
+                    expected: "HierarchyChain",
+                    found: chain.to_string(),
+                })?
+            };
+
+            let names = names
+                .iter()
+                .map(|name| match name {
+                    Token::Identifier(identifier) => Ok(identifier.0.clone()),
+                    Token::String(value) => Ok(value.clone()),
+                    _ => Err(Error::InvalidToken {
+                        expected: "Identifier or String",
+                        found: name.to_string(),
+                    }),
+                })
+                .collect::<MinosResult<Vec<Arc<str>>>>()?;
+
+            for pair in names.windows(2) {
+                hierarchy.add_inheritance(pair[0].clone(), pair[1].clone());
+            }
+        }
+
+        Ok(hierarchy)
+    }
+}
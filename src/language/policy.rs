@@ -1,11 +1,15 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use derived::Ctor;
 use getset::Getters;
+use parse_display::Display;
 
 use crate::{
-    engine::{ActorRepr, ResourceRepr},
-    errors::Error,
+    engine::{ActorRepr, ContextRepr, ResourceRepr},
+    errors::{Error, SourcePosition},
     parser::tokens::{Array, Token},
     MinosResult,
 };
@@ -26,37 +30,147 @@ impl From<String> for Permission {
     }
 }
 
-#[derive(Debug, Clone, Ctor, Getters, PartialEq)]
+impl Permission {
+    /// Indicates if the permission contains `*`, which matches any sequence of characters.
+    pub fn is_pattern(&self) -> bool {
+        self.0.contains('*')
+    }
+
+    /// Indicates if the requested permission is this permission or matches with this pattern,
+    /// like `"document:read"` with `"document:*"`.
+    pub fn matches(&self, requested: &str) -> bool {
+        match self.is_pattern() {
+            true => matches_pattern(&self.0, requested),
+            false => self.0.as_ref() == requested,
+        }
+    }
+}
+
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = text.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Indicates if the [Policy] grants or revokes its permissions.
+#[derive(Debug, Clone, Copy, Display, PartialEq, Eq, Default)]
+pub enum Effect {
+    #[default]
+    #[display("allow")]
+    Allow,
+
+    /// The permissions are revoked even if other policies grant them.
+    #[display("deny")]
+    Deny,
+}
+
+#[derive(Debug, Clone, Getters)]
 #[getset(get = "pub")]
 pub struct Policy {
+    effect: Effect,
     permissions: Vec<Permission>,
     rules: Vec<Arc<Rule>>,
     rules_map: HashMap<Permission, Vec<Arc<Rule>>>,
+    /// The permissions that match other permissions, see [`Permission::matches`]. The files
+    /// with a syntax before `0.17` have no patterns, so their permissions are literal.
+    patterns: Vec<Permission>,
+    /// The file of the policy, if it's parsed from a file.
+    path: Option<PathBuf>,
+    /// Position of the policy in its file.
+    position: Option<SourcePosition>,
+}
+
+/// The policies with the same content are equal wherever they are declared.
+impl PartialEq for Policy {
+    fn eq(&self, other: &Self) -> bool {
+        self.effect == other.effect
+            && self.permissions == other.permissions
+            && self.rules == other.rules
+            && self.rules_map == other.rules_map
+            && self.patterns == other.patterns
+    }
 }
 
 impl Policy {
+    /// Policy without location.
+    pub fn new(
+        effect: Effect,
+        permissions: Vec<Permission>,
+        rules: Vec<Arc<Rule>>,
+        rules_map: HashMap<Permission, Vec<Arc<Rule>>>,
+    ) -> Self {
+        let patterns = permissions.iter().filter(|p| p.is_pattern()).cloned().collect();
+        Self {
+            effect,
+            permissions,
+            rules,
+            rules_map,
+            patterns,
+            path: None,
+            position: None,
+        }
+    }
+
+    /// Sets the file of the policy, unless it has it already.
+    pub(crate) fn in_file(&mut self, path: &Path) {
+        if self.path.is_none() {
+            self.path = Some(path.to_path_buf());
+        }
+    }
+
+    /// Makes all the permissions literal, for the files with a syntax before `0.17`.
+    pub(crate) fn without_patterns(&mut self) {
+        self.patterns.clear();
+    }
+
     /// Indicates if an [Actor] has a specific [Permission] on a [Resource].
     pub(crate) fn actor_has_permission(
         &self,
         actor: &ActorRepr,
         resource: &ResourceRepr,
+        context: &ContextRepr,
         permission: &Permission,
     ) -> bool {
-        if let Some(rules) = self.rules_map.get(permission) {
-            for rule in rules {
-                if rule.apply(actor, resource) {
-                    return true;
-                }
-            }
-        }
+        let rules = match self.rules_map.get(permission) {
+            Some(rules) => rules,
+            None if self.covers(permission) => &self.rules,
+            None => return false,
+        };
+
+        rules.iter().any(|rule| rule.apply(actor, resource, context))
+    }
 
-        false
+    /// Indicates if the [Permission] is one of the policy's permissions or matches
+    /// with some of its patterns.
+    pub(crate) fn covers(&self, permission: &Permission) -> bool {
+        self.rules_map.contains_key(permission)
+            || self.patterns.iter().any(|pattern| pattern.matches(&permission.0))
     }
 
     /// Returns the [Permission] list if the actor satisfies at least one of the rules.
-    pub(crate) fn apply(&self, actor: &ActorRepr, resource: &ResourceRepr) -> Option<&[Permission]> {
+    /// The caller must check the policy's [Effect] to know if the permissions are granted or revoked.
+    pub(crate) fn apply(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<&[Permission]> {
         for rule in &self.rules {
-            if rule.apply(actor, resource) {
+            if rule.apply(actor, resource, context) {
                 return Some(&self.permissions);
             }
         }
@@ -73,8 +187,17 @@ impl TryFrom<&Token> for Policy {
             expected: "Policy",
             found: token.to_string(),
         })?;
-
-        let Array(permissions) = inner_tokens[0].inner_allow().unwrap()[0].inner_array().unwrap();
+        let (inner_tokens, position) = Token::split_position(inner_tokens);
+
+        let (effect, permissions_tokens) = match &inner_tokens[0] {
+            Token::Allow(inner) => (Effect::Allow, inner),
+            Token::Deny(inner) => (Effect::Deny, inner),
+            token => Err(Error::InvalidToken {
+                expected: "Allow or Deny",
+                found: token.to_string(),
+            })?,
+        };
+        let Array(permissions) = permissions_tokens[0].inner_array().unwrap();
 
         let rules = inner_tokens
             .iter()
@@ -91,9 +214,8 @@ impl TryFrom<&Token> for Policy {
         let permissions = permissions.iter().map(|v| Permission(v.clone())).collect();
 
         Ok(Policy {
-            permissions,
-            rules,
-            rules_map,
+            position: position.cloned(),
+            ..Policy::new(effect, permissions, rules, rules_map)
         })
     }
 }
@@ -2,28 +2,74 @@ use std::sync::Arc;
 
 use derived::Ctor;
 use getset::Getters;
+use parse_display::Display;
+use regex::Regex;
 
 use crate::{
-    engine::{ActorRepr, ResourceRepr},
-    errors::Error,
-    parser::tokens::{ActorAttribute, Array},
+    engine::{ActorRepr, ContextRepr, ResourceRepr},
+    errors::{Error, MinosResult},
+    parser::tokens::{ActorAttribute, Array, Number, Operator},
 };
 
-use crate::parser::tokens::{Identifier, ResourceAttribute, Token};
+use super::{condition::Condition, time::TimeCondition};
+use crate::parser::tokens::{ContextAttribute, Identifier, ResourceAttribute, Token};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Requirement {
     Assertion(Assertion),
     Negation(Negation),
     Search(Search),
+    Comparison(Comparison),
+    Match(Match),
+    Temporal(TimeCondition),
+    /// The `any`, `all` and `not` blocks, an [`Condition::Or`], an [`Condition::And`] or
+    /// a [`Condition::Not`] of an [`Condition::And`] of the inner requirements.
+    Block(Box<Condition>),
 }
 
 impl Requirement {
-    pub(crate) fn apply(&self, actor: &ActorRepr, resource: &ResourceRepr) -> Option<bool> {
+    pub(crate) fn apply(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<bool> {
         match self {
-            Requirement::Assertion(assertion) => assertion.apply(actor, resource),
-            Requirement::Negation(negation) => negation.apply(actor, resource),
-            Requirement::Search(search) => search.apply(actor, resource),
+            Requirement::Assertion(assertion) => assertion.apply(actor, resource, context),
+            Requirement::Negation(negation) => negation.apply(actor, resource, context),
+            Requirement::Search(search) => search.apply(actor, resource, context),
+            Requirement::Comparison(comparison) => comparison.apply(actor, resource, context),
+            Requirement::Match(match_) => match_.apply(actor, resource, context),
+            Requirement::Temporal(condition) => condition.apply(context),
+            Requirement::Block(condition) => condition.apply(actor, resource, context),
+        }
+    }
+
+    /// Calls `visit` with every text compared with an attribute, like `Active` in
+    /// `actor.status = Active`, including the texts of the arrays and of the inner blocks.
+    pub fn visit_compared_texts(&self, visit: &mut impl FnMut(&Attribute, &Arc<str>)) {
+        let (attribute, value) = match self {
+            Requirement::Assertion(assertion) => (assertion.left(), assertion.right()),
+            Requirement::Negation(negation) => (negation.left(), negation.right()),
+            Requirement::Search(search) => (search.left(), search.right()),
+            Requirement::Block(condition) => {
+                for requirement in condition.requirements() {
+                    requirement.visit_compared_texts(visit);
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        match value {
+            ComparableValue::Value(Value::Identifier(identifier)) => visit(attribute, &identifier.0),
+            ComparableValue::Value(Value::String(text)) => visit(attribute, text),
+            ComparableValue::Value(Value::Array(array)) => {
+                for text in &array.0 {
+                    visit(attribute, text);
+                }
+            }
+            _ => {}
         }
     }
 }
@@ -32,18 +78,40 @@ impl TryFrom<&Token> for Requirement {
     type Error = Error;
 
     fn try_from(token: &Token) -> Result<Self, Self::Error> {
-        let inner_tokens = token.inner_requirement().ok_or(Error::InvalidToken {
-            expected: "Requirement",
-            found: token.to_string(),
-        })?;
+        // the blocks aren't simplified, so they are written back as they are declared
+        let parse_block = |tokens: &Vec<Token>| -> Result<Vec<Condition>, Error> {
+            tokens
+                .iter()
+                .map(|token| Requirement::try_from(token).map(Condition::Requirement))
+                .collect()
+        };
+        let inner_tokens = match token {
+            Token::AnyBlock(inner) => {
+                return Ok(Self::Block(Box::new(Condition::Or(parse_block(inner)?))))
+            }
+            Token::AllBlock(inner) => {
+                return Ok(Self::Block(Box::new(Condition::And(parse_block(inner)?))))
+            }
+            Token::NotBlock(inner) => {
+                let all = Condition::And(parse_block(inner)?);
+                return Ok(Self::Block(Box::new(Condition::Not(Box::new(all)))));
+            }
+            token => token.inner_requirement().ok_or(Error::InvalidToken {
+                expected: "Requirement, AnyBlock, AllBlock or NotBlock",
+                found: token.to_string(),
+            })?,
+        };
 
         let token = &inner_tokens[0];
         let requirement = match token {
             Token::Assertion(inner) => Self::Assertion(Assertion::try_from(inner)?),
             Token::Negation(inner) => Self::Negation(Negation::try_from(inner)?),
             Token::Search(inner) => Self::Search(Search::try_from(inner)?),
+            Token::Comparison(inner) => Self::Comparison(Comparison::try_from(inner)?),
+            Token::Match(inner) => Self::Match(Match::try_from(inner)?),
+            Token::Temporal(inner) => Self::Temporal(TimeCondition::try_from(inner)?),
             _ => Err(Error::InvalidToken {
-                expected: "Assertion, Negation or Search",
+                expected: "Assertion, Negation, Search, Comparison, Match or Temporal",
                 found: token.to_string(),
             })?,
         };
@@ -52,20 +120,48 @@ impl TryFrom<&Token> for Requirement {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Attribute {
     Actor(ActorAttribute),
     Resource(ResourceAttribute),
+    Context(ContextAttribute),
+}
+
+impl Attribute {
+    /// Indicates if the attribute is declared as `actor.attrs.<name>`, `resource.attrs.<name>`
+    /// or `context.<name>`.
+    pub fn is_custom(&self) -> bool {
+        matches!(
+            self,
+            Attribute::Actor(ActorAttribute::Attrs(_))
+                | Attribute::Resource(ResourceAttribute::Attrs(_))
+                | Attribute::Context(_)
+        )
+    }
+
+    pub(crate) fn resolve(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<Value> {
+        match self {
+            Attribute::Actor(attr) => actor.get_attribute(attr),
+            Attribute::Resource(attr) => resource.get_attribute(attr),
+            Attribute::Context(attr) => context.get_attribute(attr),
+        }
+    }
 }
 
 impl TryFrom<&Token> for Attribute {
     type Error = Error;
     fn try_from(token: &Token) -> Result<Self, Self::Error> {
         let attribute = match token {
-            Token::ActorAttribute(attr) => Self::Actor(*attr),
-            Token::ResourceAttribute(attr) => Self::Resource(*attr),
+            Token::ActorAttribute(attr) => Self::Actor(attr.clone()),
+            Token::ResourceAttribute(attr) => Self::Resource(attr.clone()),
+            Token::ContextAttribute(attr) => Self::Context(attr.clone()),
             _ => Err(Error::InvalidToken {
-                expected: "ActorAttribute or ResourceAttribute",
+                expected: "ActorAttribute, ResourceAttribute or ContextAttribute",
                 found: token.to_string(),
             })?,
         };
@@ -80,17 +176,53 @@ pub enum ComparableValue {
     Value(Value),
 }
 
+impl ComparableValue {
+    pub fn is_custom_attribute(&self) -> bool {
+        matches!(self, ComparableValue::Attribute(attr) if attr.is_custom())
+    }
+
+    pub(crate) fn resolve(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<Value> {
+        match self {
+            ComparableValue::Attribute(attr) => attr.resolve(actor, resource, context),
+            ComparableValue::Value(value) => Some(value.clone()),
+        }
+    }
+}
+
+/// Compares two operands when at least one of them is a custom attribute. Returns `None`
+/// if some operand is missing.
+fn compare_custom_operands(
+    left: &Attribute,
+    right: &ComparableValue,
+    actor: &ActorRepr,
+    resource: &ResourceRepr,
+    context: &ContextRepr,
+) -> Option<bool> {
+    let left = left.resolve(actor, resource, context)?;
+    let right = right.resolve(actor, resource, context)?;
+
+    Some(left.is_equivalent(&right))
+}
+
 impl TryFrom<&Token> for ComparableValue {
     type Error = Error;
     fn try_from(token: &Token) -> Result<Self, Self::Error> {
         let value = match token {
-            Token::ActorAttribute(attr) => Self::Attribute(Attribute::Actor(*attr)),
-            Token::ResourceAttribute(attr) => Self::Attribute(Attribute::Resource(*attr)),
+            Token::ActorAttribute(attr) => Self::Attribute(Attribute::Actor(attr.clone())),
+            Token::ResourceAttribute(attr) => Self::Attribute(Attribute::Resource(attr.clone())),
+            Token::ContextAttribute(attr) => Self::Attribute(Attribute::Context(attr.clone())),
             Token::String(value) => Self::Value(Value::String(value.clone())),
             Token::Array(arr) => Self::Value(Value::Array(arr.clone())),
             Token::Identifier(ident) => Self::Value(Value::Identifier(ident.clone())),
+            Token::Number(number) => Self::Value(Value::Number(*number)),
             _ => Err(Error::InvalidToken {
-                expected: "ActorAttribute, ResourceAttribute, String, Array or Identifier",
+                expected: "ActorAttribute, ResourceAttribute, ContextAttribute, String, Array, \
+                    Identifier or Number",
                 found: token.to_string(),
             })?,
         };
@@ -104,6 +236,39 @@ pub enum Value {
     String(Arc<str>),
     Array(Array),
     Identifier(Identifier),
+    Number(Number),
+}
+
+impl Value {
+    /// Returns the inner text of strings and identifiers.
+    pub fn as_text(&self) -> Option<&Arc<str>> {
+        match self {
+            Value::String(value) => Some(value),
+            Value::Identifier(Identifier(value)) => Some(value),
+            Value::Array(_) | Value::Number(_) => None,
+        }
+    }
+
+    /// Returns the number, or the number written in a string or identifier.
+    pub fn as_number(&self) -> Option<Number> {
+        match self {
+            Value::Number(number) => Some(*number),
+            value => value.as_text().and_then(|text| Number::parse(text)),
+        }
+    }
+
+    /// Like equality, but a string and an identifier with the same text are equivalents,
+    /// and a number is equivalent to the same number written in a string.
+    pub fn is_equivalent(&self, other: &Value) -> bool {
+        if matches!(self, Value::Number(_)) || matches!(other, Value::Number(_)) {
+            return self.as_number().is_some() && self.as_number() == other.as_number();
+        }
+
+        match (self.as_text(), other.as_text()) {
+            (Some(left), Some(right)) => left == right,
+            _ => self == other,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Ctor, Getters)]
@@ -115,19 +280,28 @@ pub struct Assertion {
 
 impl Assertion {
     /// Returns an assertion result if the operation are permited.
-    pub(crate) fn apply(&self, actor: &ActorRepr, resource: &ResourceRepr) -> Option<bool> {
+    pub(crate) fn apply(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<bool> {
+        if self.left.is_custom() || self.right.is_custom_attribute() {
+            return compare_custom_operands(&self.left, &self.right, actor, resource, context);
+        }
+
         match (&self.left, &self.right) {
             (Attribute::Actor(left), ComparableValue::Attribute(Attribute::Resource(rigth))) => {
-                Some(actor.get_attribute(*left) == resource.get_attribute(*rigth))
+                Some(actor.get_attribute(left) == resource.get_attribute(rigth))
             }
             (Attribute::Resource(left), ComparableValue::Attribute(Attribute::Actor(rigth))) => {
-                Some(actor.get_attribute(*rigth) == resource.get_attribute(*left))
+                Some(actor.get_attribute(rigth) == resource.get_attribute(left))
             }
             (Attribute::Actor(attr), ComparableValue::Value(value)) => {
-                Some(actor.get_attribute(*attr).as_ref() == Some(value))
+                Some(actor.get_attribute(attr).as_ref() == Some(value))
             }
             (Attribute::Resource(attr), ComparableValue::Value(value)) => {
-                Some(resource.get_attribute(*attr).as_ref() == Some(value))
+                Some(resource.get_attribute(attr).as_ref() == Some(value))
             }
             _ => None,
         }
@@ -152,19 +326,29 @@ pub struct Negation {
 }
 
 impl Negation {
-    pub(crate) fn apply(&self, actor: &ActorRepr, resource: &ResourceRepr) -> Option<bool> {
+    pub(crate) fn apply(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<bool> {
+        if self.left.is_custom() || self.right.is_custom_attribute() {
+            return compare_custom_operands(&self.left, &self.right, actor, resource, context)
+                .map(|eq| !eq);
+        }
+
         match (&self.left, &self.right) {
             (Attribute::Actor(left), ComparableValue::Attribute(Attribute::Resource(rigth))) => {
-                Some(actor.get_attribute(*left) != resource.get_attribute(*rigth))
+                Some(actor.get_attribute(left) != resource.get_attribute(rigth))
             }
             (Attribute::Resource(left), ComparableValue::Attribute(Attribute::Actor(rigth))) => {
-                Some(actor.get_attribute(*rigth) != resource.get_attribute(*left))
+                Some(actor.get_attribute(rigth) != resource.get_attribute(left))
             }
             (Attribute::Actor(attr), ComparableValue::Value(value)) => {
-                Some(actor.get_attribute(*attr).as_ref() != Some(value))
+                Some(actor.get_attribute(attr).as_ref() != Some(value))
             }
             (Attribute::Resource(attr), ComparableValue::Value(value)) => {
-                Some(resource.get_attribute(*attr).as_ref() != Some(value))
+                Some(resource.get_attribute(attr).as_ref() != Some(value))
             }
             _ => None,
         }
@@ -199,28 +383,55 @@ impl Search {
         true
     }
 
-    pub(crate) fn apply(&self, actor: &ActorRepr, resource: &ResourceRepr) -> Option<bool> {
+    /// Searches the value into the reference list. Returns `None` if the reference isn't a list.
+    fn find_value_in_list(reference: &Value, to_find: &Value) -> Option<bool> {
+        let Value::Array(Array(reference)) = reference else {
+            return None;
+        };
+
+        match to_find {
+            Value::Array(values) => Some(Self::find_list_in_list(reference, values)),
+            value => value.as_text().map(|text| reference.contains(text)),
+        }
+    }
+
+    pub(crate) fn apply(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<bool> {
+        if self.left.is_custom() || self.right.is_custom_attribute() {
+            let reference = match &self.left {
+                Attribute::Actor(attr) => actor.get_search_attribute(attr)?,
+                attr => attr.resolve(actor, resource, context)?,
+            };
+            let to_find = self.right.resolve(actor, resource, context)?;
+
+            return Self::find_value_in_list(&reference, &to_find);
+        }
+
         match (&self.left, &self.right) {
             (Attribute::Actor(ActorAttribute::Groups), ComparableValue::Value(Value::Array(value))) => {
-                Some(Self::find_list_in_list(actor.groups(), value))
+                Some(Self::find_list_in_list(actor.expanded_groups(), value))
             }
             (Attribute::Actor(ActorAttribute::Groups), ComparableValue::Value(Value::String(value))) => {
-                Some(actor.groups().contains(value))
+                Some(actor.expanded_groups().contains(value))
             }
             (Attribute::Actor(ActorAttribute::Roles), ComparableValue::Value(Value::Array(value))) => {
-                Some(Self::find_list_in_list(actor.roles(), value))
+                Some(Self::find_list_in_list(actor.expanded_roles(), value))
             }
             (Attribute::Actor(ActorAttribute::Roles), ComparableValue::Value(Value::String(value))) => {
-                Some(actor.roles().contains(value))
+                Some(actor.expanded_roles().contains(value))
             }
             (
                 Attribute::Actor(ActorAttribute::Groups),
                 ComparableValue::Attribute(Attribute::Resource(attr)),
             ) => {
-                let value = resource.get_attribute(*attr);
+                let value = resource.get_attribute(attr);
                 match value {
-                    Some(Value::String(value)) => Some(actor.groups().contains(&value)),
-                    Some(Value::Identifier(value)) => Some(actor.groups().contains(&value.0)),
+                    Some(Value::String(value)) => Some(actor.expanded_groups().contains(&value)),
+                    Some(Value::Identifier(value)) => Some(actor.expanded_groups().contains(&value.0)),
                     _ => None,
                 }
             }
@@ -228,10 +439,10 @@ impl Search {
                 Attribute::Actor(ActorAttribute::Roles),
                 ComparableValue::Attribute(Attribute::Resource(attr)),
             ) => {
-                let value = resource.get_attribute(*attr);
+                let value = resource.get_attribute(attr);
                 match value {
-                    Some(Value::String(value)) => Some(actor.roles().contains(&value)),
-                    Some(Value::Identifier(value)) => Some(actor.roles().contains(&value.0)),
+                    Some(Value::String(value)) => Some(actor.expanded_roles().contains(&value)),
+                    Some(Value::Identifier(value)) => Some(actor.expanded_roles().contains(&value.0)),
                     _ => None,
                 }
             }
@@ -249,3 +460,208 @@ impl TryFrom<&Vec<Token>> for Search {
         Ok(Self { left, right })
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum ComparisonOperator {
+    #[display("<")]
+    LessThan,
+
+    #[display("<=")]
+    LessOrEqual,
+
+    #[display(">")]
+    GreaterThan,
+
+    #[display(">=")]
+    GreaterOrEqual,
+}
+
+impl ComparisonOperator {
+    /// Returns the operator that gives the same result with the operands swapped.
+    pub fn flip(&self) -> Self {
+        match self {
+            ComparisonOperator::LessThan => ComparisonOperator::GreaterThan,
+            ComparisonOperator::LessOrEqual => ComparisonOperator::GreaterOrEqual,
+            ComparisonOperator::GreaterThan => ComparisonOperator::LessThan,
+            ComparisonOperator::GreaterOrEqual => ComparisonOperator::LessOrEqual,
+        }
+    }
+
+    pub fn compare(&self, left: &Number, right: &Number) -> bool {
+        match self {
+            ComparisonOperator::LessThan => left < right,
+            ComparisonOperator::LessOrEqual => left <= right,
+            ComparisonOperator::GreaterThan => left > right,
+            ComparisonOperator::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+impl TryFrom<&Token> for ComparisonOperator {
+    type Error = Error;
+    fn try_from(token: &Token) -> Result<Self, Self::Error> {
+        let operator = match token {
+            Token::Operator(Operator::LessThan) => Self::LessThan,
+            Token::Operator(Operator::LessOrEqual) => Self::LessOrEqual,
+            Token::Operator(Operator::GreaterThan) => Self::GreaterThan,
+            Token::Operator(Operator::GreaterOrEqual) => Self::GreaterOrEqual,
+            _ => Err(Error::InvalidToken {
+                expected: "Comparison Operator",
+                found: token.to_string(),
+            })?,
+        };
+
+        Ok(operator)
+    }
+}
+
+/// Ordered comparison between numbers, like `actor.attrs.level >= 3`.
+#[derive(Debug, Clone, PartialEq, Ctor, Getters)]
+#[getset(get = "pub")]
+pub struct Comparison {
+    left: Attribute,
+    operator: ComparisonOperator,
+    right: ComparableValue,
+}
+
+impl Comparison {
+    /// Strings and identifiers are compared by the number that they contain. Returns `None`
+    /// if some operand is missing or isn't a number.
+    pub(crate) fn apply(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<bool> {
+        let left = self.left.resolve(actor, resource, context)?.as_number()?;
+        let right = self.right.resolve(actor, resource, context)?.as_number()?;
+
+        Some(self.operator.compare(&left, &right))
+    }
+}
+
+impl TryFrom<&Vec<Token>> for Comparison {
+    type Error = Error;
+    fn try_from(token: &Vec<Token>) -> Result<Self, Self::Error> {
+        let left = Attribute::try_from(token.first().ok_or(Error::MissingToken)?)?;
+        let operator = ComparisonOperator::try_from(token.get(1).ok_or(Error::MissingToken)?)?;
+        let right = ComparableValue::try_from(token.get(2).ok_or(Error::MissingToken)?)?;
+
+        Ok(Self {
+            left,
+            operator,
+            right,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum PatternFlavour {
+    /// `*` matches any sequence of characters and `?` matches a single character.
+    #[display("glob")]
+    Glob,
+
+    #[display("regex")]
+    Regex,
+}
+
+/// Glob or regex pattern, compiled when the file is parsed.
+#[derive(Debug, Clone, Getters)]
+pub struct Pattern {
+    #[getset(get = "pub")]
+    flavour: PatternFlavour,
+    #[getset(get = "pub")]
+    source: Arc<str>,
+    regex: Regex,
+}
+
+impl Pattern {
+    pub fn new(flavour: PatternFlavour, source: Arc<str>) -> MinosResult<Self> {
+        let regex = match flavour {
+            PatternFlavour::Glob => Regex::new(&Self::glob_to_regex(&source)),
+            PatternFlavour::Regex => Regex::new(&source),
+        }
+        .map_err(|err| Error::InvalidPattern {
+            pattern: source.to_string(),
+            reason: err.to_string(),
+        })?;
+
+        Ok(Self {
+            flavour,
+            source,
+            regex,
+        })
+    }
+
+    fn glob_to_regex(glob: &str) -> String {
+        let mut regex = String::from("^");
+        for char in glob.chars() {
+            match char {
+                '*' => regex.push_str(".*"),
+                '?' => regex.push('.'),
+                char => regex.push_str(&regex::escape(char.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        regex.push('$');
+
+        regex
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.flavour == other.flavour && self.source == other.source
+    }
+}
+
+impl TryFrom<&Token> for Pattern {
+    type Error = Error;
+    fn try_from(token: &Token) -> Result<Self, Self::Error> {
+        match token {
+            Token::GlobPattern(source) => Self::new(PatternFlavour::Glob, source.clone()),
+            Token::RegexPattern(source) => Self::new(PatternFlavour::Regex, source.clone()),
+            _ => Err(Error::InvalidToken {
+                expected: "GlobPattern or RegexPattern",
+                found: token.to_string(),
+            }),
+        }
+    }
+}
+
+/// Pattern matching over strings and identifiers, like `resource.id ~= glob("org-42/*")`.
+#[derive(Debug, Clone, PartialEq, Ctor, Getters)]
+#[getset(get = "pub")]
+pub struct Match {
+    left: Attribute,
+    pattern: Pattern,
+}
+
+impl Match {
+    /// A list matches if some of its values matches. Returns `None` if the attribute
+    /// is missing or is a number.
+    pub(crate) fn apply(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> Option<bool> {
+        match self.left.resolve(actor, resource, context)? {
+            Value::Array(Array(values)) => Some(values.iter().any(|value| self.pattern.is_match(value))),
+            value => value.as_text().map(|text| self.pattern.is_match(text)),
+        }
+    }
+}
+
+impl TryFrom<&Vec<Token>> for Match {
+    type Error = Error;
+    fn try_from(token: &Vec<Token>) -> Result<Self, Self::Error> {
+        let left = Attribute::try_from(token.first().ok_or(Error::MissingToken)?)?;
+        let pattern = Pattern::try_from(token.get(2).ok_or(Error::MissingToken)?)?;
+
+        Ok(Self { left, pattern })
+    }
+}
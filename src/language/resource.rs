@@ -1,7 +1,7 @@
 use std::{collections::HashMap, sync::Arc};
 
 use derived::Ctor;
-use getset::Getters;
+use getset::{Getters, MutGetters};
 
 use crate::{
     errors::{Error, MinosResult},
@@ -10,14 +10,44 @@ use crate::{
 
 use super::environment::{Environment, DEFAULT_ENV_IDENTIFIER};
 
-#[derive(Debug, Clone, Ctor, Getters, PartialEq)]
+#[derive(Debug, Clone, Getters, MutGetters, PartialEq)]
 #[getset(get = "pub")]
 pub struct Resource {
     identifier: Identifier,
+    /// The resource declared after `extends`.
+    parent: Option<Identifier>,
+    /// The environments declared in the resource, without the inherited ones.
+    #[getset(get_mut = "pub(crate)")]
     environments: HashMap<Identifier, Environment>,
+    /// The declared environments merged with the parent's environments,
+    /// see [`Storage::resolve_inheritance`].
+    ///
+    /// [`Storage::resolve_inheritance`]: super::storage::Storage::resolve_inheritance
+    #[getset(skip)]
+    resolved_environments: Option<HashMap<Identifier, Environment>>,
 }
 
 impl Resource {
+    /// Resource without parent.
+    pub fn new(identifier: Identifier, environments: HashMap<Identifier, Environment>) -> Self {
+        Self::with_parent(identifier, None, environments)
+    }
+
+    /// Resource that extends the `parent`, if any. The inherited environments are added by
+    /// [`Storage::resolve_inheritance`](super::storage::Storage::resolve_inheritance).
+    pub fn with_parent(
+        identifier: Identifier,
+        parent: Option<Identifier>,
+        environments: HashMap<Identifier, Environment>,
+    ) -> Self {
+        Self {
+            identifier,
+            parent,
+            environments,
+            resolved_environments: None,
+        }
+    }
+
     fn collect_envs_from_tokens<'a>(
         iterator: impl Iterator<Item = &'a Token>,
     ) -> MinosResult<Vec<Environment>> {
@@ -49,24 +79,52 @@ impl Resource {
     }
 
     /// Merge two [Environment]. If exist repeatedly environments,
-    ///  the inner rules will be merged.
-    pub fn merge(&mut self, resource: Resource) {
+    ///  the inner rules will be merged. Fails with [`Error::ConflictingParents`] if both
+    /// resources extend different parents, in this case the resource isn't modified.
+    pub fn merge(&mut self, resource: Resource) -> MinosResult<()> {
+        match (&self.parent, resource.parent) {
+            (Some(parent), Some(other_parent)) if parent != &other_parent => {
+                return Err(Error::ConflictingParents {
+                    resource: self.identifier.to_string(),
+                    parent: parent.to_string(),
+                    other_parent: other_parent.to_string(),
+                });
+            }
+            (None, parent) => self.parent = parent,
+            _ => {}
+        }
+
         for (_, env) in resource.environments {
             self.add_environment(env);
         }
+        self.resolved_environments = None;
+
+        Ok(())
+    }
+
+    /// Returns the environments with the inherited policies, if the inheritance is resolved,
+    /// or the declared environments.
+    pub fn effective_environments(&self) -> &HashMap<Identifier, Environment> {
+        self.resolved_environments.as_ref().unwrap_or(&self.environments)
+    }
+
+    pub(crate) fn set_resolved_environments(&mut self, environments: HashMap<Identifier, Environment>) {
+        self.resolved_environments = Some(environments);
     }
 
     pub fn default_environment(&self) -> Option<&Environment> {
-        self.environments.get(&Identifier(DEFAULT_ENV_IDENTIFIER.into()))
+        self.effective_environments()
+            .get(&Identifier(DEFAULT_ENV_IDENTIFIER.into()))
     }
 
     pub fn get_environment(&self, env: &str) -> Option<&Environment> {
-        self.environments.get(&Identifier::from(env))
+        self.effective_environments().get(&Identifier::from(env))
     }
 
+    /// Counts the policies of the effective environments.
     pub fn policies_len(&self) -> usize {
         let mut len = 0;
-        for env in self.environments().values() {
+        for env in self.effective_environments().values() {
             len += env.policies().len();
         }
 
@@ -84,21 +142,24 @@ impl TryFrom<&Token> for Resource {
         })?;
 
         let identifier = inner_tokens[0].inner_identifier().unwrap().clone();
-        let env_list = Self::collect_envs_from_tokens(inner_tokens.iter().skip(1))?;
+        let parent = match inner_tokens.get(1) {
+            Some(Token::Extends(parent)) => Some(parent.clone()),
+            _ => None,
+        };
+        let skipped_tokens = if parent.is_some() { 2 } else { 1 };
+        let env_list = Self::collect_envs_from_tokens(inner_tokens.iter().skip(skipped_tokens))?;
         let environments = Self::collect_hash_map_env_from_vec(env_list);
 
-        Ok(Self {
-            identifier,
-            environments,
-        })
+        Ok(Self::with_parent(identifier, parent, environments))
     }
 }
 
-#[derive(Debug, Clone, Ctor, Getters, PartialEq)]
+#[derive(Debug, Clone, Ctor, Getters, MutGetters, PartialEq)]
 #[getset(get = "pub")]
 pub struct AttributedResource {
     identifier: Identifier,
     id: Arc<str>,
+    #[getset(get_mut = "pub(crate)")]
     environments: HashMap<Identifier, Environment>,
 }
 
@@ -1,25 +1,46 @@
-use derived::Ctor;
 use getset::Getters;
 
 use crate::{
-    engine::{ActorRepr, ResourceRepr},
-    errors::{Error, MinosResult},
+    engine::{ActorRepr, ContextRepr, ResourceRepr},
+    errors::{Error, MinosResult, SourcePosition},
     parser::tokens::Token,
 };
 
 use super::requirements::Requirement;
 
-#[derive(Debug, Clone, Ctor, Getters, PartialEq)]
+#[derive(Debug, Clone, Getters)]
 #[getset(get = "pub")]
 pub struct Rule {
     requirements: Vec<Requirement>,
+    /// Position of the rule in its file, in the file of its [Policy](super::policy::Policy).
+    position: Option<SourcePosition>,
+}
+
+/// The rules with the same requirements are equal wherever they are declared.
+impl PartialEq for Rule {
+    fn eq(&self, other: &Self) -> bool {
+        self.requirements == other.requirements
+    }
 }
 
 impl Rule {
+    /// Rule without position.
+    pub fn new(requirements: Vec<Requirement>) -> Self {
+        Self {
+            requirements,
+            position: None,
+        }
+    }
+
     /// Apply all requirements and return true only if actor satisfies all.
-    pub(crate) fn apply(&self, actor: &ActorRepr, resource: &ResourceRepr) -> bool {
+    pub(crate) fn apply(
+        &self,
+        actor: &ActorRepr,
+        resource: &ResourceRepr,
+        context: &ContextRepr,
+    ) -> bool {
         for requirement in &self.requirements {
-            if !requirement.apply(actor, resource).unwrap_or_default() {
+            if !requirement.apply(actor, resource, context).unwrap_or_default() {
                 return false;
             }
         }
@@ -36,11 +57,13 @@ impl TryFrom<&Token> for Rule {
             expected: "Rule",
             found: token.to_string(),
         })?;
+        let (inner_tokens, position) = Token::split_position(inner_tokens);
         let requirements: MinosResult<Vec<Requirement>> =
             inner_tokens.iter().map(Requirement::try_from).collect();
 
         Ok(Rule {
             requirements: requirements?,
+            position: position.cloned(),
         })
     }
 }
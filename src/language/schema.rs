@@ -0,0 +1,218 @@
+use std::{collections::HashMap, sync::Arc};
+
+use getset::Getters;
+
+use crate::{
+    errors::{Error, MinosResult},
+    parser::tokens::{Identifier, Token},
+};
+
+use super::{environment::Environment, policy::Permission, requirements::Attribute};
+use crate::parser::tokens::{ActorAttribute, ResourceAttribute};
+
+/// Names declared in the `schema { ... }` blocks, like:
+///
+/// ```text
+/// schema {
+///     actor types User, Service;
+///     resource types Document;
+///     statuses Active, Archived;
+///     permissions for Document "read", "write";
+/// }
+/// ```
+///
+/// A kind of name without declarations isn't checked, so the schema can be adopted gradually.
+#[derive(Debug, Clone, PartialEq, Default, Getters)]
+#[getset(get = "pub")]
+pub struct Schema {
+    /// Declared with `actor types ...;`.
+    actor_types: Vec<Arc<str>>,
+    /// Declared with `resource types ...;`.
+    resource_types: Vec<Arc<str>>,
+    /// Values of `actor.status` and `resource.status`, declared with `statuses ...;`.
+    statuses: Vec<Arc<str>>,
+    /// Declared with `permissions for <type> ...;`. The permissions for `*` are valid
+    /// for every type.
+    permissions: HashMap<Identifier, Vec<Arc<str>>>,
+}
+
+impl Schema {
+    pub fn is_empty(&self) -> bool {
+        self.actor_types.is_empty()
+            && self.resource_types.is_empty()
+            && self.statuses.is_empty()
+            && self.permissions.is_empty()
+    }
+
+    /// Adds the declarations of the schema. Repeated names are ignored.
+    pub fn merge(&mut self, schema: Schema) {
+        add_names(&mut self.actor_types, schema.actor_types);
+        add_names(&mut self.resource_types, schema.resource_types);
+        add_names(&mut self.statuses, schema.statuses);
+        for (resource_type, permissions) in schema.permissions {
+            add_names(self.permissions.entry(resource_type).or_default(), permissions);
+        }
+    }
+
+    /// Fails with [`Error::Undeclared`] if actor types are declared and the type isn't one of them.
+    pub fn check_actor_type(&self, actor_type: &str, place: &str) -> MinosResult<()> {
+        check_name(&self.actor_types, "actor type", actor_type, place)
+    }
+
+    /// Fails with [`Error::Undeclared`] if resource types are declared and the type isn't
+    /// one of them. The wildcard type `*` is always valid.
+    pub fn check_resource_type(&self, resource_type: &str, place: &str) -> MinosResult<()> {
+        match resource_type == Identifier::WILDCARD {
+            true => Ok(()),
+            false => check_name(&self.resource_types, "resource type", resource_type, place),
+        }
+    }
+
+    /// Fails with [`Error::Undeclared`] if statuses are declared and the status isn't one of them.
+    pub fn check_status(&self, status: &str, place: &str) -> MinosResult<()> {
+        check_name(&self.statuses, "status", status, place)
+    }
+
+    /// Fails with [`Error::Undeclared`] if permissions are declared for the type, or for `*`,
+    /// and the permission doesn't match any of them. A pattern like `"document:*"` is valid
+    /// if it matches some declared permission. The permissions of the wildcard type `*` are
+    /// checked against the permissions declared for any type.
+    pub fn check_permission(
+        &self,
+        resource_type: &str,
+        permission: &str,
+        place: &str,
+    ) -> MinosResult<()> {
+        let declared: Vec<&Arc<str>> = self
+            .permissions
+            .iter()
+            .filter(|(identifier, _)| {
+                resource_type == Identifier::WILDCARD
+                    || identifier.0.as_ref() == resource_type
+                    || identifier.0.as_ref() == Identifier::WILDCARD
+            })
+            .flat_map(|(_, permissions)| permissions)
+            .collect();
+        let requested = Permission::from(permission);
+        let is_declared = declared.is_empty()
+            || declared.iter().any(|declared| {
+                Permission((*declared).clone()).matches(permission) || requested.matches(declared)
+            });
+
+        match is_declared {
+            true => Ok(()),
+            false => Err(undeclared("permission", permission, place)),
+        }
+    }
+
+    /// Checks the types, statuses and permissions used by the policies of the environments.
+    pub(crate) fn check_environments<'e>(
+        &self,
+        resource_type: &str,
+        environments: impl IntoIterator<Item = &'e Environment>,
+        place: &str,
+    ) -> MinosResult<()> {
+        for environment in environments {
+            let place = format!("{place}, environment {}", environment.identifier());
+            for policy in environment.policies() {
+                for permission in policy.permissions() {
+                    self.check_permission(resource_type, &permission.0, &place)?;
+                }
+
+                let mut result = Ok(());
+                let requirements = policy.rules().iter().flat_map(|rule| rule.requirements());
+                for requirement in requirements {
+                    requirement.visit_compared_texts(&mut |attribute, value| {
+                        if result.is_ok() {
+                            result = self.check_compared_value(attribute, value, &place);
+                        }
+                    });
+                }
+                result?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_compared_value(&self, attribute: &Attribute, value: &str, place: &str) -> MinosResult<()> {
+        match attribute {
+            Attribute::Actor(ActorAttribute::Type) => self.check_actor_type(value, place),
+            Attribute::Resource(ResourceAttribute::Type) => self.check_resource_type(value, place),
+            Attribute::Actor(ActorAttribute::Status)
+            | Attribute::Resource(ResourceAttribute::Status) => self.check_status(value, place),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn add_names(names: &mut Vec<Arc<str>>, new_names: Vec<Arc<str>>) {
+    for name in new_names {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+}
+
+fn check_name(declared: &[Arc<str>], kind: &'static str, name: &str, place: &str) -> MinosResult<()> {
+    match declared.is_empty() || declared.iter().any(|declared| declared.as_ref() == name) {
+        true => Ok(()),
+        false => Err(undeclared(kind, name, place)),
+    }
+}
+
+fn undeclared(kind: &'static str, name: &str, place: &str) -> Error {
+    Error::Undeclared {
+        kind,
+        name: name.to_string(),
+        place: place.to_string(),
+    }
+}
+
+impl TryFrom<&Vec<Token>> for Schema {
+    type Error = Error;
+
+    /// Builds the schema with the declarations of the block.
+    fn try_from(declarations: &Vec<Token>) -> Result<Self, Self::Error> {
+        let mut schema = Schema::default();
+        for declaration in declarations {
+            match declaration {
+                Token::ActorTypes(names) => add_names(&mut schema.actor_types, names_of(names)?),
+                Token::ResourceTypes(names) => add_names(&mut schema.resource_types, names_of(names)?),
+                Token::Statuses(names) => add_names(&mut schema.statuses, names_of(names)?),
+                Token::SchemaPermissions(tokens) => {
+                    let (resource_type, permissions) =
+                        tokens.split_first().ok_or(Error::MissingToken)?;
+                    let resource_type = resource_type.inner_identifier().ok_or(Error::InvalidToken {
+                        expected: "Identifier",
+                        found: resource_type.to_string(),
+                    })?;
+                    add_names(
+                        schema.permissions.entry(resource_type.clone()).or_default(),
+                        names_of(permissions)?,
+                    );
+                }
+                _ => Err(Error::InvalidToken {
+                    expected: "ActorTypes, ResourceTypes, Statuses or SchemaPermissions",
+                    found: declaration.to_string(),
+                })?,
+            }
+        }
+
+        Ok(schema)
+    }
+}
+
+fn names_of(tokens: &[Token]) -> MinosResult<Vec<Arc<str>>> {
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::Identifier(identifier) => Ok(identifier.0.clone()),
+            Token::String(value) => Ok(value.clone()),
+            _ => Err(Error::InvalidToken {
+                expected: "Identifier or String",
+                found: token.to_string(),
+            }),
+        })
+        .collect()
+}
@@ -1,43 +1,113 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, LazyLock},
+};
 
-use derived::Ctor;
 use getset::Getters;
 
 use crate::{
-    parser::tokens::{Identifier, Token},
-    Error,
+    parser::tokens::{FileVersion, Identifier, Token},
+    Error, MinosResult,
 };
 
-use super::resource::{AttributedResource, Resource};
+use super::{
+    environment::Environment,
+    hierarchy::Hierarchy,
+    resource::{AttributedResource, Resource},
+    schema::Schema,
+};
 
 /// A collection of [Resource] and [AttributedResource].
-#[derive(Debug, Clone, Ctor, Getters, PartialEq, Default)]
+#[derive(Debug, Clone, Getters, PartialEq, Default)]
 #[getset(get = "pub")]
 pub struct Storage {
+    /// The highest syntax version of the parsed files.
+    version: FileVersion,
+    /// Declared with `roles { ... }`.
+    roles_hierarchy: Hierarchy,
+    /// Declared with `groups { ... }`.
+    groups_hierarchy: Hierarchy,
+    /// Declared with `schema { ... }`, see [`Storage::check_schema`].
+    schema: Schema,
     resources: HashMap<Identifier, Resource>,
     attributed_resources: HashMap<(Identifier, Arc<str>), AttributedResource>,
 }
 
 impl Storage {
-    pub fn merge(&mut self, storage: Storage) {
+    /// Storage of the resources, without hierarchies nor schema.
+    pub fn new(
+        resources: HashMap<Identifier, Resource>,
+        attributed_resources: HashMap<(Identifier, Arc<str>), AttributedResource>,
+    ) -> Self {
+        Self {
+            resources,
+            attributed_resources,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the file of the environments and the policies without it, see [`Policy::path`].
+    ///
+    /// [`Policy::path`]: super::policy::Policy::path
+    pub(crate) fn in_file(&mut self, path: &Path) {
+        for environment in self.environments_mut() {
+            environment.in_file(path);
+        }
+    }
+
+    fn environments_mut(&mut self) -> impl Iterator<Item = &mut Environment> {
+        self.resources
+            .values_mut()
+            .flat_map(|resource| resource.environments_mut().values_mut())
+            .chain(
+                self.attributed_resources
+                    .values_mut()
+                    .flat_map(|resource| resource.environments_mut().values_mut()),
+            )
+    }
+
+    /// Merges the resources and the hierarchies of both storages. The merged hierarchies
+    /// can contain cycles, see [`Storage::check_hierarchies`].
+    ///
+    /// Fails with [`Error::ConflictingParents`] if a resource of both storages extends
+    /// different parents. The other resources are merged anyway.
+    pub fn merge(&mut self, storage: Storage) -> MinosResult<()> {
+        self.version = self.version.max(storage.version);
+        self.roles_hierarchy.merge(storage.roles_hierarchy);
+        self.groups_hierarchy.merge(storage.groups_hierarchy);
+        self.schema.merge(storage.schema);
+
+        let mut result = Ok(());
         for (_, resource) in storage.resources {
-            self.add_resource(resource);
+            if let Err(err) = self.add_resource(resource) {
+                result = result.and(Err(err));
+            }
         }
 
         for (_, attributed_resource) in storage.attributed_resources {
             self.add_attributed_resource(attributed_resource);
         }
+
+        result
+    }
+
+    /// Returns the resource declared as `resource * { ... }`, whose policies apply to every type.
+    pub fn wildcard_resource(&self) -> Option<&Resource> {
+        static WILDCARD: LazyLock<Identifier> = LazyLock::new(|| Identifier::WILDCARD.into());
+        self.resources.get(&WILDCARD)
     }
 
     /// Add a [Resource] into [Storage]. if the resource's [Identifier] already exists,
-    /// the two resources will be merged.
-    pub fn add_resource(&mut self, resource: Resource) {
+    /// the two resources will be merged, see [`Resource::merge`].
+    pub fn add_resource(&mut self, resource: Resource) -> MinosResult<()> {
         if let Some(inner_resource) = self.resources.get_mut(resource.identifier()) {
-            inner_resource.merge(resource);
-            return;
+            return inner_resource.merge(resource);
         }
 
         self.resources.insert(resource.identifier().clone(), resource);
+
+        Ok(())
     }
 
     /// Add a [AttributedResource] into [Storage]. if the resource's [Identifier] already exists,
@@ -55,10 +125,124 @@ impl Storage {
             .insert((resource.identifier().clone(), resource.id().clone()), resource);
     }
 
+    /// Merges the environments of every [Resource] with the environments of its parents,
+    /// declared with `extends`. The inherited policies are placed before the resource's own
+    /// policies. Must be called after merging all the storages.
+    ///
+    /// Fails if a parent doesn't exist or if a resource extends itself.
+    pub fn resolve_inheritance(&mut self) -> MinosResult<()> {
+        let mut resolved = HashMap::new();
+        let children: Vec<Identifier> = self
+            .resources
+            .values()
+            .filter(|resource| resource.parent().is_some())
+            .map(|resource| resource.identifier().clone())
+            .collect();
+
+        for child in &children {
+            self.resolve_environments(child, &mut vec![], &mut resolved)?;
+        }
+
+        for child in children {
+            if let (Some(resource), Some(environments)) =
+                (self.resources.get_mut(&child), resolved.remove(&child))
+            {
+                resource.set_resolved_environments(environments);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resolve_environments(
+        &self,
+        identifier: &Identifier,
+        path: &mut Vec<Identifier>,
+        resolved: &mut HashMap<Identifier, HashMap<Identifier, Environment>>,
+    ) -> MinosResult<HashMap<Identifier, Environment>> {
+        if let Some(environments) = resolved.get(identifier) {
+            return Ok(environments.clone());
+        }
+
+        if let Some(start) = path.iter().position(|name| name == identifier) {
+            let cycle: Vec<&str> = path[start..]
+                .iter()
+                .chain([identifier])
+                .map(|name| name.0.as_ref())
+                .collect();
+            return Err(Error::InheritanceCycle(cycle.join(" extends ")));
+        }
+
+        let resource = self
+            .resources
+            .get(identifier)
+            .ok_or(Error::ResourceNotFound(identifier.to_string()))?;
+        let Some(parent) = resource.parent() else {
+            return Ok(resource.environments().clone());
+        };
+
+        path.push(identifier.clone());
+        let mut environments = self.resolve_environments(parent, path, resolved)?;
+        path.pop();
+
+        for environment in resource.environments().values() {
+            match environments.get_mut(environment.identifier()) {
+                Some(inherited) => inherited.add_policies(&mut environment.policies().clone()),
+                None => {
+                    environments.insert(environment.identifier().clone(), environment.clone());
+                }
+            }
+        }
+        resolved.insert(identifier.clone(), environments.clone());
+
+        Ok(environments)
+    }
+
+    /// Fails if a role or a group inherits itself.
+    pub fn check_hierarchies(&self) -> MinosResult<()> {
+        self.roles_hierarchy.check_cycles()?;
+        self.groups_hierarchy.check_cycles()
+    }
+
+    /// Fails with [`Error::Undeclared`] if a resource or a policy uses a type, a status or
+    /// a permission that the [Schema] doesn't declare. Must be called after merging all
+    /// the storages.
+    pub fn check_schema(&self) -> MinosResult<()> {
+        if self.schema.is_empty() {
+            return Ok(());
+        }
+
+        let mut resources: Vec<&Resource> = self.resources.values().collect();
+        resources.sort_by_key(|resource| &resource.identifier().0);
+        for resource in resources {
+            let resource_type = &resource.identifier().0;
+            let place = format!("resource {resource_type}");
+            self.schema.check_resource_type(resource_type, &place)?;
+            self.schema
+                .check_environments(resource_type, resource.environments().values(), &place)?;
+        }
+
+        let mut attributed_resources: Vec<&AttributedResource> =
+            self.attributed_resources.values().collect();
+        attributed_resources.sort_by_key(|resource| (&resource.identifier().0, resource.id()));
+        for resource in attributed_resources {
+            let resource_type = &resource.identifier().0;
+            let place = format!("resource {resource_type} '{}'", resource.id());
+            self.schema.check_resource_type(resource_type, &place)?;
+            self.schema
+                .check_environments(resource_type, resource.environments().values(), &place)?;
+        }
+
+        Ok(())
+    }
+
     pub fn policies_len(&self) -> usize {
         let mut len = 0;
         for resource in self.resources().values() {
-            len += resource.policies_len();
+            // the inherited policies are counted in their own resource
+            for env in resource.environments().values() {
+                len += env.policies().len();
+            }
         }
 
         for attr_resource in self.attributed_resources().values() {
@@ -73,24 +257,54 @@ impl TryFrom<Token> for Storage {
     type Error = Error;
 
     fn try_from(token: Token) -> Result<Self, Self::Error> {
+        Self::from_file_token(token, |_, err| err)
+    }
+}
+
+impl Storage {
+    /// Builds the storage with the file's [Token]. `locate` adds to an error the location
+    /// of the file's inner token at the index.
+    pub(crate) fn from_file_token(
+        token: Token,
+        locate: impl Fn(usize, Error) -> Error,
+    ) -> MinosResult<Self> {
         let inner_tokens = token.inner_file().ok_or(Error::InvalidToken {
             expected: "File",
             found: token.to_string(),
         })?;
 
-        let mut storage = Storage::default();
-        for inner_token in &inner_tokens[1..inner_tokens.len() - 1] {
-            match inner_token {
-                Token::Resource(_) => {
-                    storage.add_resource(Resource::try_from(inner_token)?);
-                }
-                Token::AttributedResource(_) => {
-                    storage.add_attributed_resource(AttributedResource::try_from(inner_token)?);
-                }
-                _ => {}
-            }
+        let mut storage = Storage {
+            version: inner_tokens[0].inner_version().ok_or(Error::MissingToken)?,
+            ..Default::default()
+        };
+        for (index, inner_token) in inner_tokens
+            .iter()
+            .enumerate()
+            .take(inner_tokens.len() - 1)
+            .skip(1)
+        {
+            storage.add_token(inner_token).map_err(|err| locate(index, err))?;
         }
+        if storage.version.without_macros() < FileVersion::V0_17 {
+            storage.environments_mut().for_each(Environment::without_patterns);
+        }
+        storage.check_hierarchies()?;
 
         Ok(storage)
     }
+
+    fn add_token(&mut self, token: &Token) -> MinosResult<()> {
+        match token {
+            Token::Resource(_) => self.add_resource(Resource::try_from(token)?)?,
+            Token::AttributedResource(_) => {
+                self.add_attributed_resource(AttributedResource::try_from(token)?)
+            }
+            Token::RolesHierarchy(chains) => self.roles_hierarchy.merge(Hierarchy::try_from(chains)?),
+            Token::GroupsHierarchy(chains) => self.groups_hierarchy.merge(Hierarchy::try_from(chains)?),
+            Token::Schema(declarations) => self.schema.merge(Schema::try_from(declarations)?),
+            _ => {}
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,252 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    engine::ContextRepr,
+    errors::{Error, MinosResult},
+    parser::tokens::{Operator, TimeAttribute, Token, Weekday},
+};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Instant written in RFC 3339 format, like `2024-03-01T09:30:00Z`
+/// or `2024-03-01T09:30:00.5-06:00`.
+#[derive(Debug, Clone)]
+pub struct Timestamp {
+    text: Arc<str>,
+    instant: SystemTime,
+}
+
+impl Timestamp {
+    pub fn parse(text: &Arc<str>) -> MinosResult<Self> {
+        let instant = parse_rfc3339(text).ok_or_else(|| Error::InvalidTimestamp(text.to_string()))?;
+
+        Ok(Self {
+            text: text.clone(),
+            instant,
+        })
+    }
+
+    pub fn text(&self) -> &Arc<str> {
+        &self.text
+    }
+
+    pub fn instant(&self) -> SystemTime {
+        self.instant
+    }
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.instant == other.instant
+    }
+}
+
+/// Requirement over the instant of the request. The weekdays and the hours are evaluated
+/// in UTC, so a local window must be written with the hours of UTC.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeCondition {
+    /// `context.time before "<timestamp>"`
+    Before(Timestamp),
+    /// `context.time after "<timestamp>"`
+    After(Timestamp),
+    /// `context.weekday in Mon..Fri`, both days included. The range can wrap around
+    /// the week, like `Fri..Mon`.
+    Weekdays(Weekday, Weekday),
+    /// `context.hour in 9..17`, from the first hour to the second hour, excluded. The range
+    /// can wrap around midnight, like `22..6` from 22:00 to 5:59. The hours can't be equal,
+    /// since the range would be empty.
+    Hours(u8, u8),
+}
+
+impl TimeCondition {
+    pub(crate) fn apply(&self, context: &ContextRepr) -> Option<bool> {
+        let now = context.time();
+        let result = match self {
+            TimeCondition::Before(timestamp) => now < timestamp.instant,
+            TimeCondition::After(timestamp) => now > timestamp.instant,
+            TimeCondition::Weekdays(start, end) => {
+                let weekday = weekday_of(now);
+                match start <= end {
+                    true => start <= &weekday && &weekday <= end,
+                    false => start <= &weekday || &weekday <= end,
+                }
+            }
+            TimeCondition::Hours(start, end) => {
+                let hour = hour_of(now);
+                match start <= end {
+                    true => *start <= hour && hour < *end,
+                    false => *start <= hour || hour < *end,
+                }
+            }
+        };
+
+        Some(result)
+    }
+}
+
+impl TryFrom<&Vec<Token>> for TimeCondition {
+    type Error = Error;
+    fn try_from(tokens: &Vec<Token>) -> Result<Self, Self::Error> {
+        let (attribute, operator, operands) = match tokens.as_slice() {
+            [Token::TimeAttribute(attribute), Token::Operator(operator), operands @ ..] => {
+                (attribute, operator, operands)
+            }
+            [token, ..] => Err(Error::InvalidToken {
+                expected: "TimeAttribute",
+                found: token.to_string(),
+            })?,
+            [] => Err(Error::MissingToken)?,
+        };
+
+        let condition = match (attribute, operator, operands) {
+            (TimeAttribute::Time, Operator::Before, [Token::String(text)]) => {
+                Self::Before(Timestamp::parse(text)?)
+            }
+            (TimeAttribute::Time, Operator::After, [Token::String(text)]) => {
+                Self::After(Timestamp::parse(text)?)
+            }
+            (TimeAttribute::Weekday, Operator::In, [Token::Weekday(start), Token::Weekday(end)]) => {
+                Self::Weekdays(*start, *end)
+            }
+            (TimeAttribute::Hour, Operator::In, [Token::Hour(start), Token::Hour(end)]) => {
+                if start == end {
+                    Err(Error::EmptyHourRange(*start))?
+                }
+                Self::Hours(*start, *end)
+            }
+            _ => Err(Error::InvalidToken {
+                expected: "Temporal operands",
+                found: format!("{attribute} {operator}"),
+            })?,
+        };
+
+        Ok(condition)
+    }
+}
+
+fn seconds_since_epoch(instant: SystemTime) -> i64 {
+    match instant.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(err) => -(err.duration().as_secs_f64().ceil() as i64),
+    }
+}
+
+fn weekday_of(instant: SystemTime) -> Weekday {
+    // 1970-01-01 was Thursday
+    let days = seconds_since_epoch(instant).div_euclid(SECONDS_PER_DAY);
+    match (days + 3).rem_euclid(7) {
+        0 => Weekday::Mon,
+        1 => Weekday::Tue,
+        2 => Weekday::Wed,
+        3 => Weekday::Thu,
+        4 => Weekday::Fri,
+        5 => Weekday::Sat,
+        _ => Weekday::Sun,
+    }
+}
+
+fn hour_of(instant: SystemTime) -> u8 {
+    (seconds_since_epoch(instant).rem_euclid(SECONDS_PER_DAY) / 3600) as u8
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn parse_number(text: &str) -> Option<i64> {
+    match text.bytes().all(|byte| byte.is_ascii_digit()) {
+        true => text.parse().ok(),
+        false => None,
+    }
+}
+
+fn parse_rfc3339(text: &str) -> Option<SystemTime> {
+    if !text.is_ascii() {
+        return None;
+    }
+
+    let (date, time) = text.split_once(['T', 't', ' '])?;
+    if date.len() != 10 || &date[4..5] != "-" || &date[7..8] != "-" {
+        return None;
+    }
+    let year = parse_number(&date[0..4])?;
+    let month = parse_number(&date[5..7])?;
+    let day = parse_number(&date[8..10])?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    if time.len() < 8 || &time[2..3] != ":" || &time[5..6] != ":" {
+        return None;
+    }
+    let hour = parse_number(&time[0..2])?;
+    let minute = parse_number(&time[3..5])?;
+    let second = parse_number(&time[6..8])?;
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let mut rest = &time[8..];
+    let mut nanos = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        let significant = &fraction[..digits.min(9)];
+        nanos = parse_number(significant)? as u32 * 10u32.pow(9 - significant.len() as u32);
+        rest = &fraction[digits..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        offset if offset.len() == 6 && &offset[3..4] == ":" => {
+            let sign = match &offset[0..1] {
+                "+" => 1,
+                "-" => -1,
+                _ => return None,
+            };
+            let hours = parse_number(&offset[1..3])?;
+            let minutes = parse_number(&offset[4..6])?;
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            sign * (hours * 3600 + minutes * 60)
+        }
+        _ => return None,
+    };
+
+    let seconds =
+        days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second
+            - offset;
+    let instant = match seconds >= 0 {
+        true => UNIX_EPOCH + Duration::from_secs(seconds as u64),
+        false => UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs()),
+    };
+
+    Some(instant + Duration::from_nanos(nanos as u64))
+}
@@ -4,7 +4,9 @@
 pub mod engine;
 pub mod errors;
 pub mod language;
+pub mod lint;
 pub mod parser;
+pub mod sql;
 pub mod text_repr;
 
 #[cfg(test)]
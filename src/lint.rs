@@ -0,0 +1,509 @@
+//! Static checks over a [Storage], for the mistakes that parse fine but are likely wrong,
+//! like a typo in a status value or a rule that can never be satisfied.
+//!
+//! The lints are located by resource, environment, policy and rule, and by file, line
+//! and column if the storage is parsed from files.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use getset::Getters;
+use parse_display::Display;
+
+use crate::{
+    errors::{render_source, SourcePosition},
+    language::{
+        environment::{Environment, DEFAULT_ENV_IDENTIFIER},
+        policy::Policy,
+        requirements::{Attribute, ComparableValue, Requirement, Value},
+        rule::Rule,
+        storage::Storage,
+    },
+    parser::tokens::{ActorAttribute, Identifier, ResourceAttribute},
+    text_repr::to_text_repr::ToTextRepr,
+};
+
+/// Maximum edit distance between a status value and a more used one to be reported
+/// as a possible typo.
+const TYPO_DISTANCE: usize = 1;
+
+/// Kind of mistake found by [lint].
+#[derive(Debug, Clone, Copy, Display, PartialEq, Eq, Hash)]
+pub enum LintCode {
+    /// A status value isn't one of the [known statuses](LintConfig::known_statuses) or,
+    /// without known statuses nor statuses declared in the schema, looks like a typo of
+    /// a more used value.
+    #[display("unknown-status")]
+    UnknownStatus,
+    /// The rule requires different values for the same attribute.
+    #[display("unsatisfiable-rule")]
+    UnsatisfiableRule,
+    /// The permission is repeated in the same policy.
+    #[display("duplicated-permission")]
+    DuplicatedPermission,
+    /// The rule contains all the requirements of a sibling rule, so it never grants
+    /// something that the sibling doesn't.
+    #[display("redundant-rule")]
+    RedundantRule,
+    /// The environment is named like `DEFAULT` with a different case, so it isn't
+    /// the default environment.
+    #[display("default-environment-case")]
+    DefaultEnvironmentCase,
+}
+
+impl LintCode {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LintCode::UnknownStatus => "L001",
+            LintCode::UnsatisfiableRule => "L002",
+            LintCode::DuplicatedPermission => "L003",
+            LintCode::RedundantRule => "L004",
+            LintCode::DefaultEnvironmentCase => "L005",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The lint isn't reported.
+    #[display("allow")]
+    Allow,
+    #[display("warning")]
+    Warning,
+    #[display("error")]
+    Error,
+}
+
+/// Severity of every [LintCode], [Severity::Warning] by default, and the values
+/// known by the application.
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    severities: HashMap<LintCode, Severity>,
+    known_statuses: Option<Vec<Arc<str>>>,
+}
+
+impl LintConfig {
+    pub fn severity(mut self, code: LintCode, severity: Severity) -> Self {
+        self.severities.insert(code, severity);
+        self
+    }
+
+    /// Declares the valid values of `actor.status` and `resource.status`. By default, the
+    /// statuses declared in the schema, if any.
+    pub fn known_statuses(mut self, statuses: impl IntoIterator<Item = impl Into<Arc<str>>>) -> Self {
+        self.known_statuses = Some(statuses.into_iter().map(Into::into).collect());
+        self
+    }
+
+    fn severity_of(&self, code: LintCode) -> Severity {
+        self.severities.get(&code).copied().unwrap_or(Severity::Warning)
+    }
+}
+
+/// Place of a lint in the [Storage]. The policies and the rules are numbered from 1,
+/// in declaration order.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct LintLocation {
+    resource: Identifier,
+    /// The id of an attributed resource.
+    resource_id: Option<Arc<str>>,
+    environment: Option<Identifier>,
+    policy: Option<usize>,
+    rule: Option<usize>,
+}
+
+impl Display for LintLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "resource {}", self.resource)?;
+        if let Some(id) = &self.resource_id {
+            write!(f, " (id = \"{id}\")")?;
+        }
+        if let Some(environment) = &self.environment {
+            write!(f, " > env {environment}")?;
+        }
+        if let Some(policy) = self.policy {
+            write!(f, " > policy #{policy}")?;
+        }
+        if let Some(rule) = self.rule {
+            write!(f, " > rule #{rule}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+#[getset(get = "pub")]
+pub struct Lint {
+    code: LintCode,
+    severity: Severity,
+    location: LintLocation,
+    /// The file of the located item, if the storage is parsed from files.
+    path: Option<PathBuf>,
+    /// Position of the located item in its file: the rule, the policy or the named
+    /// environment.
+    position: Option<SourcePosition>,
+    message: String,
+}
+
+/// Renders the lint with a snippet of the source, like a [located error](crate::errors::LocatedError::render),
+/// or with the [LintLocation] if the position is unknown.
+impl Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = format!("{}[{}]: {}", self.severity, self.code.code(), self.message);
+        match &self.position {
+            Some(position) => write!(
+                f,
+                "{}",
+                render_source(&header, self.path.as_deref(), Some(position))
+            ),
+            None => write!(f, "{header}\n --> {}", self.location),
+        }
+    }
+}
+
+/// Returns the lints of the storage, ordered by resource and environment name,
+/// without the [allowed](Severity::Allow) ones.
+pub fn lint(storage: &Storage, config: &LintConfig) -> Vec<Lint> {
+    let mut linter = Linter {
+        config,
+        lints: vec![],
+        status_typos: status_typos(storage, config),
+    };
+
+    let mut resources: Vec<_> = storage.resources().values().collect();
+    resources.sort_by(|a, b| a.identifier().0.cmp(&b.identifier().0));
+    for resource in resources {
+        let location = LintLocation {
+            resource: resource.identifier().clone(),
+            resource_id: None,
+            environment: None,
+            policy: None,
+            rule: None,
+        };
+        linter.lint_environments(resource.environments(), &location);
+    }
+
+    let mut attributed_resources: Vec<_> = storage.attributed_resources().values().collect();
+    attributed_resources.sort_by(|a, b| (&a.identifier().0, a.id()).cmp(&(&b.identifier().0, b.id())));
+    for resource in attributed_resources {
+        let location = LintLocation {
+            resource: resource.identifier().clone(),
+            resource_id: Some(resource.id().clone()),
+            environment: None,
+            policy: None,
+            rule: None,
+        };
+        linter.lint_environments(resource.environments(), &location);
+    }
+
+    linter.lints
+}
+
+struct Linter<'c> {
+    config: &'c LintConfig,
+    lints: Vec<Lint>,
+    /// The misspelled status values with the message to report.
+    status_typos: HashMap<Arc<str>, String>,
+}
+
+impl Linter<'_> {
+    fn report(
+        &mut self,
+        code: LintCode,
+        location: &LintLocation,
+        path: Option<&Path>,
+        position: Option<&SourcePosition>,
+        message: String,
+    ) {
+        let severity = self.config.severity_of(code);
+        if severity == Severity::Allow {
+            return;
+        }
+
+        self.lints.push(Lint {
+            code,
+            severity,
+            location: location.clone(),
+            path: path.map(Path::to_path_buf),
+            position: position.cloned(),
+            message,
+        });
+    }
+
+    fn lint_environments(
+        &mut self,
+        environments: &HashMap<Identifier, Environment>,
+        location: &LintLocation,
+    ) {
+        let mut environments: Vec<_> = environments.values().collect();
+        environments.sort_by(|a, b| a.identifier().0.cmp(&b.identifier().0));
+        for environment in environments {
+            let identifier = environment.identifier();
+            let location = LintLocation {
+                environment: Some(identifier.clone()),
+                ..location.clone()
+            };
+            if identifier.0.as_ref() != DEFAULT_ENV_IDENTIFIER
+                && identifier.0.eq_ignore_ascii_case(DEFAULT_ENV_IDENTIFIER)
+            {
+                self.report(
+                    LintCode::DefaultEnvironmentCase,
+                    &location,
+                    environment.path().as_deref(),
+                    environment.position().as_ref(),
+                    format!("the environment `{identifier}` isn't the default environment `{DEFAULT_ENV_IDENTIFIER}`"),
+                );
+            }
+
+            for (index, policy) in environment.policies().iter().enumerate() {
+                let location = LintLocation {
+                    policy: Some(index + 1),
+                    ..location.clone()
+                };
+                self.lint_policy(policy, &location);
+            }
+        }
+    }
+
+    fn lint_policy(&mut self, policy: &Policy, location: &LintLocation) {
+        let permissions = policy.permissions();
+        for (index, permission) in permissions.iter().enumerate() {
+            if permissions[..index].contains(permission)
+                && !permissions[index + 1..].contains(permission)
+            {
+                self.report(
+                    LintCode::DuplicatedPermission,
+                    location,
+                    policy.path().as_deref(),
+                    policy.position().as_ref(),
+                    format!("the permission \"{}\" is repeated", permission.0),
+                );
+            }
+        }
+
+        let rules = policy.rules();
+        for (index, rule) in rules.iter().enumerate() {
+            let location = LintLocation {
+                rule: Some(index + 1),
+                ..location.clone()
+            };
+            self.lint_rule(rule, policy.path().as_deref(), &location);
+
+            let covering_rule = rules.iter().enumerate().position(|(sibling_index, sibling)| {
+                sibling_index != index && covers(sibling, rule, sibling_index < index)
+            });
+            if let Some(sibling_index) = covering_rule {
+                self.report(
+                    LintCode::RedundantRule,
+                    &location,
+                    policy.path().as_deref(),
+                    rule.position().as_ref(),
+                    format!("the rule #{} already grants the permissions", sibling_index + 1),
+                );
+            }
+        }
+    }
+
+    /// The rule is in the file of its policy, at `path`.
+    fn lint_rule(&mut self, rule: &Rule, path: Option<&Path>, location: &LintLocation) {
+        let requirements = rule.requirements();
+        for (index, requirement) in requirements.iter().enumerate() {
+            let conflict = requirements[..index]
+                .iter()
+                .find(|previous| contradicts(previous, requirement));
+            if let Some(previous) = conflict {
+                self.report(
+                    LintCode::UnsatisfiableRule,
+                    location,
+                    path,
+                    rule.position().as_ref(),
+                    format!(
+                        "`{}` contradicts `{}`, so the rule is never satisfied",
+                        requirement.to_text_repr(),
+                        previous.to_text_repr()
+                    ),
+                );
+            }
+        }
+
+        let mut statuses = vec![];
+        for requirement in requirements {
+            collect_statuses(requirement, &mut statuses);
+        }
+        for status in statuses {
+            if let Some(message) = self.status_typos.get(&status).cloned() {
+                self.report(
+                    LintCode::UnknownStatus,
+                    location,
+                    path,
+                    rule.position().as_ref(),
+                    message,
+                );
+            }
+        }
+    }
+}
+
+/// Indicates if the sibling rule is satisfied whenever the rule is. Equal rules cover
+/// each other, so only the rule declared after is reported.
+fn covers(sibling: &Rule, rule: &Rule, declared_before: bool) -> bool {
+    let contained = sibling
+        .requirements()
+        .iter()
+        .all(|requirement| rule.requirements().contains(requirement));
+    let equal = rule
+        .requirements()
+        .iter()
+        .all(|requirement| sibling.requirements().contains(requirement));
+
+    contained && (!equal || declared_before)
+}
+
+/// Returns the attribute and the value of `attribute = value`, where the attribute
+/// has a single value.
+fn single_value_assertion(requirement: &Requirement) -> Option<(&Attribute, &Value, bool)> {
+    let (attribute, value, equal) = match requirement {
+        Requirement::Assertion(assertion) => (assertion.left(), assertion.right(), true),
+        Requirement::Negation(negation) => (negation.left(), negation.right(), false),
+        _ => return None,
+    };
+    let single_value = matches!(
+        attribute,
+        Attribute::Actor(ActorAttribute::Id | ActorAttribute::Type | ActorAttribute::Status)
+            | Attribute::Resource(
+                ResourceAttribute::Id
+                    | ResourceAttribute::Type
+                    | ResourceAttribute::Owner
+                    | ResourceAttribute::Status
+            )
+    );
+    match (single_value, value) {
+        (true, ComparableValue::Value(value)) => Some((attribute, value, equal)),
+        _ => None,
+    }
+}
+
+/// `x = a` contradicts `x = b` and `x != a`.
+fn contradicts(previous: &Requirement, requirement: &Requirement) -> bool {
+    let (Some(previous), Some(requirement)) = (
+        single_value_assertion(previous),
+        single_value_assertion(requirement),
+    ) else {
+        return false;
+    };
+
+    match (previous, requirement) {
+        ((left, a, true), (right, b, true)) => left == right && !same_value(a, b),
+        ((left, a, true), (right, b, false)) | ((left, a, false), (right, b, true)) => {
+            left == right && same_value(a, b)
+        }
+        _ => false,
+    }
+}
+
+/// The identifiers and the strings with the same text are the same value.
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a.as_text(), b.as_text()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn is_status(attribute: &Attribute) -> bool {
+    matches!(
+        attribute,
+        Attribute::Actor(ActorAttribute::Status) | Attribute::Resource(ResourceAttribute::Status)
+    )
+}
+
+/// Collects the values compared with `actor.status` or `resource.status`.
+fn collect_statuses(requirement: &Requirement, statuses: &mut Vec<Arc<str>>) {
+    requirement.visit_compared_texts(&mut |attribute, value| {
+        if is_status(attribute) {
+            statuses.push(value.clone());
+        }
+    });
+}
+
+/// Returns the status values that aren't known or, without known statuses, that are
+/// similar to a more used value. The statuses declared in the schema are known by default.
+fn status_typos(storage: &Storage, config: &LintConfig) -> HashMap<Arc<str>, String> {
+    let schema_statuses = storage.schema().statuses();
+    let known_statuses = match &config.known_statuses {
+        Some(known) => Some(known),
+        None if !schema_statuses.is_empty() => Some(schema_statuses),
+        None => None,
+    };
+
+    let mut statuses = vec![];
+    let environments = storage
+        .resources()
+        .values()
+        .flat_map(|resource| resource.environments().values())
+        .chain(
+            storage
+                .attributed_resources()
+                .values()
+                .flat_map(|resource| resource.environments().values()),
+        );
+    for rule in environments
+        .flat_map(|env| env.policies())
+        .flat_map(|policy| policy.rules())
+    {
+        for requirement in rule.requirements() {
+            collect_statuses(requirement, &mut statuses);
+        }
+    }
+
+    let mut counts: HashMap<Arc<str>, usize> = HashMap::new();
+    for status in statuses {
+        *counts.entry(status).or_default() += 1;
+    }
+
+    let mut typos = HashMap::new();
+    for (status, count) in &counts {
+        let message = match known_statuses {
+            Some(known) if !known.contains(status) => {
+                format!("the status `{status}` isn't a known status")
+            }
+            Some(_) => continue,
+            None => {
+                let similar = counts
+                    .iter()
+                    .filter(|(other, other_count)| {
+                        *other_count > count && edit_distance(status, other) <= TYPO_DISTANCE
+                    })
+                    .max_by_key(|(other, other_count)| (**other_count, other.to_string()));
+                match similar {
+                    Some((other, _)) => format!("the status `{status}` looks like a typo of `{other}`"),
+                    None => continue,
+                }
+            }
+        };
+        typos.insert(status.clone(), message);
+    }
+
+    typos
+}
+
+/// Levenshtein distance between the characters of both texts.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, LazyLock};
 use std::{fs, path::Path, str::FromStr};
 
@@ -8,15 +9,28 @@ use crate::errors::{Error, MinosResult};
 
 use crate::language::storage::Storage;
 
-use self::tokens::FileVersion;
+use self::{
+    imports::Imports, load_report::LoadReport, macros::Macros, recovery::PolicyRange,
+    tokens::FileVersion,
+};
 
+pub(crate) mod imports;
+pub mod load_report;
+mod macros;
+mod recovery;
 pub mod tokens;
 pub(crate) mod v0_16;
 pub(crate) mod v0_16_m;
+pub(crate) mod v0_17;
+pub(crate) mod v0_17_m;
 
 static VERSION_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"syntax\s*=\s*(?P<VERSION>\d+\.+\d+M*)").expect("regex syntax error"));
 
+/// Builds a [Storage] with the content of a file, see [`MinosParser::parse_content`].
+type ContentParser =
+    fn(FileVersion, &str, &mut HashMap<String, Arc<str>>, &mut Imports) -> MinosResult<Storage>;
+
 #[derive(Debug)]
 pub struct MinosParser;
 
@@ -31,55 +45,271 @@ impl MinosParser {
         None
     }
 
-    /// Build a [Storage] with the file.
+    /// Build a [Storage] with the file. The errors are [located](Error::Located) in the file.
     pub(crate) fn parse_file(
         path: &Path,
         values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
     ) -> MinosResult<Storage> {
-        let file_content = fs::read_to_string(path)?;
-        let version = Self::get_file_version(&file_content).ok_or(Error::SyntaxNotSupported)?;
+        let mut parse = || {
+            let file_content = fs::read_to_string(path)?;
+            let version = Self::get_file_version(&file_content).ok_or(Error::SyntaxNotSupported)?;
+
+            let mut storage = imports.in_file(path, |imports| {
+                Self::parse_file_content(version, &file_content, values_map, imports)
+            })?;
+            storage.in_file(path);
+
+            MinosResult::Ok(storage)
+        };
+
+        parse().map_err(|err| err.in_file(path))
+    }
+
+    /// Returns the macros of the file imported with `import "file";`, with the macros that
+    /// it imports. Only the files with a macro syntax, like `0.16M`, can be imported.
+    fn parse_macros(
+        path: &Path,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<Macros> {
+        let mut parse = || {
+            let file_content = fs::read_to_string(path)?;
+            let version = Self::get_file_version(&file_content).ok_or(Error::SyntaxNotSupported)?;
+
+            match version {
+                FileVersion::V0_16M => {
+                    v0_16_m::MinosParserV0_16M::parse_macros(&file_content, values_map, imports)
+                }
+                FileVersion::V0_17M => {
+                    v0_17_m::MinosParserV0_17M::parse_macros(&file_content, values_map, imports)
+                }
+                FileVersion::V0_16 | FileVersion::V0_17 => Err(Error::SyntaxNotSupported),
+            }
+        };
 
-        Self::optimized_parse_str(version, &file_content, values_map)
+        parse().map_err(|err| err.in_file(path))
     }
 
     /// Read the directory and parse the files recursively. Build a [Storage] with the files content.
     pub(crate) fn parse_dir(
         path: &Path,
         values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
     ) -> MinosResult<Storage> {
-        let dir = fs::read_dir(path)?;
         let mut storage = Storage::default();
+        for file in Self::dir_files(path)? {
+            let file_storage = Self::parse_file(&file, values_map, imports)?;
+            storage.merge(file_storage).map_err(|err| err.in_file(&file))?;
+        }
+        storage.check_hierarchies()?;
+
+        Ok(storage)
+    }
 
+    /// Returns the minos files of the directory and its subdirectories.
+    pub(crate) fn dir_files(path: &Path) -> MinosResult<Vec<PathBuf>> {
+        let mut files = vec![];
+        let dir = fs::read_dir(path).map_err(|err| Error::from(err).in_file(path))?;
         for entry in dir {
             let path = entry?.path();
-            if !path.is_file() && !path.is_dir() {
+            if path.is_dir() {
+                files.extend(Self::dir_files(&path)?);
                 continue;
             }
 
-            if path.is_dir() {
-                let dir_storage = Self::parse_dir(&path, values_map)?;
-                storage.merge(dir_storage);
+            let is_minos_file = path.extension().map(|p| p == "minos").unwrap_or_default();
+            if path.is_file() && is_minos_file {
+                files.push(path);
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Like [`MinosParser::parse_file`], but the errors are added to `errors`, see [LoadReport].
+    pub(crate) fn parse_file_with_report(
+        path: &Path,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+        errors: &mut Vec<Error>,
+    ) -> Option<Storage> {
+        let file_content = match fs::read_to_string(path) {
+            Ok(file_content) => file_content,
+            Err(err) => {
+                errors.push(Error::from(err).in_file(path));
+                return None;
             }
+        };
 
-            let is_minos_file = path.extension().map(|p| p == "minos").unwrap_or_default();
-            if is_minos_file {
-                let file_storage = Self::parse_file(&path, values_map)?;
-                storage.merge(file_storage);
+        let mut file_errors = vec![];
+        let storage = imports.in_file(path, |imports| {
+            Self::recovering_parse_str(
+                Self::parse_file_content,
+                &file_content,
+                values_map,
+                imports,
+                &mut file_errors,
+            )
+        });
+        errors.extend(file_errors.into_iter().map(|err| err.in_file(path)));
+
+        storage.map(|mut storage| {
+            storage.in_file(path);
+            storage
+        })
+    }
+
+    /// Parses the file content skipping the policies and the top-level items with errors,
+    /// which are added to `errors` sorted by position. Returns `None` if no item is valid or an error is
+    /// out of the items.
+    fn recovering_parse_str(
+        parse: ContentParser,
+        file_content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+        errors: &mut Vec<Error>,
+    ) -> Option<Storage> {
+        let mut file_errors = vec![];
+        let storage =
+            Self::skip_invalid_items(parse, file_content, values_map, imports, &mut file_errors);
+        file_errors.sort_by_key(|err| match err {
+            Error::Located(located) => located.position().as_ref().map(|p| *p.offset()),
+            _ => None,
+        });
+        errors.extend(file_errors);
+
+        storage
+    }
+
+    fn skip_invalid_items(
+        parse: ContentParser,
+        file_content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+        errors: &mut Vec<Error>,
+    ) -> Option<Storage> {
+        let Some(version) = Self::get_file_version(file_content) else {
+            errors.push(Error::SyntaxNotSupported);
+            return None;
+        };
+        let items = recovery::top_level_items(file_content);
+        let policies: Vec<Vec<PolicyRange>> = items
+            .iter()
+            .map(|item| recovery::policies(file_content, item))
+            .collect();
+        let mut skipped = vec![false; items.len()];
+        let mut skipped_policies = vec![];
+        let mut content = file_content.to_string();
+
+        loop {
+            let err = match parse(version, &content, values_map, imports) {
+                Ok(storage) => return Some(storage),
+                Err(err) => err,
+            };
+            let offset = match &err {
+                Error::Located(located) => located.position().as_ref().map(|p| *p.offset()),
+                _ => None,
+            };
+            let item = offset.and_then(|offset| {
+                items
+                    .iter()
+                    .position(|item| item.contains(&offset))
+                    .filter(|index| !skipped[*index])
+            });
+
+            match item {
+                Some(index) => {
+                    errors.push(err);
+                    let policy = offset.and_then(|offset| {
+                        recovery::skippable_policy(&policies[index], &skipped_policies, offset)
+                    });
+                    match policy {
+                        Some(policy) => {
+                            content = recovery::blank(&content, &policy.range);
+                            skipped_policies.push(policy.range.clone());
+                        }
+                        None => {
+                            skipped[index] = true;
+                            content = recovery::blank(&content, &items[index]);
+                        }
+                    }
+                }
+                // all the resources were skipped
+                None if skipped.contains(&true) && offset == Some(content.len()) => return None,
+                None => {
+                    errors.push(err);
+                    return None;
+                }
             }
         }
+    }
 
-        Ok(storage)
+    /// Resolves the inheritance and checks the hierarchies of the storage built with
+    /// the valid parts of the files, see [LoadReport].
+    pub(crate) fn finish_report(
+        mut storage: Storage,
+        mut errors: Vec<Error>,
+    ) -> Result<Storage, LoadReport> {
+        if let Err(err) = storage.resolve_inheritance() {
+            errors.push(err);
+        }
+        if let Err(err) = storage.check_hierarchies() {
+            errors.push(err);
+        }
+        if let Err(err) = storage.check_schema() {
+            errors.push(err);
+        }
+
+        match errors.is_empty() {
+            true => Ok(storage),
+            false => Err(LoadReport::new(errors, storage)),
+        }
     }
 
     fn optimized_parse_str(
         version: FileVersion,
         file_content: &str,
         values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Storage> {
+        Self::parse_content(version, file_content, values_map, &mut Imports::default())
+    }
+
+    fn parse_content(
+        version: FileVersion,
+        file_content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
     ) -> MinosResult<Storage> {
         match version {
             FileVersion::V0_16 => v0_16::MinosParserV0_16::parse_file_content(file_content, values_map),
             FileVersion::V0_16M => {
-                v0_16_m::MinosParserV0_16M::parse_file_content(file_content, values_map)
+                v0_16_m::MinosParserV0_16M::parse_file_content(file_content, values_map, imports)
+            }
+            FileVersion::V0_17 => v0_17::MinosParserV0_17::parse_file_content(file_content, values_map),
+            FileVersion::V0_17M => {
+                v0_17_m::MinosParserV0_17M::parse_file_content(file_content, values_map, imports)
+            }
+        }
+    }
+
+    /// Like [`MinosParser::parse_content`], but a file with a macro syntax can be a library,
+    /// imported by other files, with only macros. A library adds no resources to the [Storage].
+    fn parse_file_content(
+        version: FileVersion,
+        file_content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<Storage> {
+        match version {
+            FileVersion::V0_16M => {
+                v0_16_m::MinosParserV0_16M::parse_library_content(file_content, values_map, imports)
+            }
+            FileVersion::V0_17M => {
+                v0_17_m::MinosParserV0_17M::parse_library_content(file_content, values_map, imports)
+            }
+            FileVersion::V0_16 | FileVersion::V0_17 => {
+                Self::parse_content(version, file_content, values_map, imports)
             }
         }
     }
@@ -97,7 +327,29 @@ impl MinosParser {
     #[deprecated(since = "0.12.0", note = "Use `MinosParser::easy_parse_str` method instead")]
     pub fn parse_str(version: FileVersion, file_content: &str) -> MinosResult<Storage> {
         let mut values_map = HashMap::new();
-        Self::optimized_parse_str(version, file_content, &mut values_map)
+        let mut storage = Self::optimized_parse_str(version, file_content, &mut values_map)
+            .map_err(Error::without_location)?;
+        storage.resolve_inheritance()?;
+        storage.check_schema()?;
+
+        Ok(storage)
+    }
+
+    /// Like [`MinosParser::easy_parse_str`], but doesn't stop at the first error:
+    /// the resources with errors are skipped and all the errors are collected into
+    /// a [LoadReport], with the [Storage] built from the valid resources.
+    pub fn parse_str_with_report(file_content: &str) -> Result<Storage, LoadReport> {
+        let mut values_map = HashMap::new();
+        let mut errors = vec![];
+        let storage = Self::recovering_parse_str(
+            Self::parse_content,
+            file_content,
+            &mut values_map,
+            &mut Imports::default(),
+            &mut errors,
+        );
+
+        Self::finish_report(storage.unwrap_or_default(), errors)
     }
 
     /// Read and parse a valid minos file content, returns an [Storage]
@@ -108,9 +360,17 @@ impl MinosParser {
     /// * Is partial minos file.
     /// * File contains syntax errors.
     /// * File uses unsupported syntax version.
+    ///
+    /// The errors have no location, the files loaded with a [Container](crate::Container)
+    /// return [`Error::Located`] instead.
     pub fn easy_parse_str(file_content: &str) -> MinosResult<Storage> {
         let mut values_map = HashMap::new();
-        let version = Self::get_file_version(&file_content).ok_or(Error::SyntaxNotSupported)?;
-        Self::optimized_parse_str(version, file_content, &mut values_map)
+        let version = Self::get_file_version(file_content).ok_or(Error::SyntaxNotSupported)?;
+        let mut storage = Self::optimized_parse_str(version, file_content, &mut values_map)
+            .map_err(Error::without_location)?;
+        storage.resolve_inheritance()?;
+        storage.check_schema()?;
+
+        Ok(storage)
     }
 }
@@ -0,0 +1,95 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::errors::{Error, MinosResult};
+
+use super::{macros::Macros, MinosParser};
+
+/// Resolves the `import "file";` directives of the files of a load and the files they import.
+///
+/// The paths are relative to the importing file, or to the current directory if the content
+/// isn't read from a file. Every file of the import graph is parsed once per load, and
+/// an import of a file that is still being parsed is a cycle.
+#[derive(Debug, Default)]
+pub(crate) struct Imports {
+    /// The files being parsed, the last one is the importing file.
+    stack: Vec<PathBuf>,
+    /// The macros of the parsed files, with the macros they import.
+    loaded: HashMap<PathBuf, Macros>,
+    /// All the imported files, including the ones that fail.
+    files: HashSet<PathBuf>,
+}
+
+impl Imports {
+    /// Runs the parse of the file at the path, so its imports are relative to the file.
+    pub(crate) fn in_file<T>(&mut self, path: &Path, parse: impl FnOnce(&mut Self) -> T) -> T {
+        self.stack.push(canonical(path));
+        let result = parse(self);
+        self.stack.pop();
+
+        result
+    }
+
+    /// Returns the imported files, sorted by path.
+    pub(crate) fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.files.iter().cloned().collect();
+        files.sort();
+
+        files
+    }
+
+    /// Returns the macros of the imported file, with the macros that it imports. Fails with
+    /// [`Error::ImportCycle`] if the file is being parsed, or with [`Error::Import`] if the file
+    /// can't be read or parsed.
+    pub(crate) fn import(
+        &mut self,
+        path: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Macros> {
+        let relative_path = match self.stack.last().and_then(|file| file.parent()) {
+            Some(dir) => dir.join(path),
+            None => PathBuf::from(path),
+        };
+        let imported = canonical(&relative_path);
+        self.files.insert(imported.clone());
+
+        if let Some(start) = self.stack.iter().position(|file| file == &imported) {
+            let cycle: Vec<String> = self.stack[start..]
+                .iter()
+                .chain([&imported])
+                .map(|file| file_name(file))
+                .collect();
+            return Err(Error::ImportCycle(cycle.join(" imports ")));
+        }
+        if let Some(macros) = self.loaded.get(&imported) {
+            return Ok(macros.clone());
+        }
+
+        let macros = self.in_file(&imported.clone(), |imports| {
+            MinosParser::parse_macros(&imported, values_map, imports)
+        });
+        let macros = macros.map_err(|err| Error::Import {
+            path: path.to_string(),
+            error: Box::new(err),
+        })?;
+        self.loaded.insert(imported, macros.clone());
+
+        Ok(macros)
+    }
+}
+
+/// The canonical path if the file exists, so the same file is always found by the same path.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string()
+}
@@ -0,0 +1,48 @@
+use getset::Getters;
+
+use crate::{errors::Error, language::storage::Storage};
+
+/// Errors found by [`MinosParser::parse_str_with_report`](super::MinosParser::parse_str_with_report)
+/// and [`Container::load_with_report`](crate::Container::load_with_report), which don't stop
+/// at the first error.
+///
+/// A policy with errors is skipped, and every skipped policy has its error. If its environment
+/// has no other policy, the resource is skipped instead, like a hierarchy or a macro definition
+/// with errors. The rest of the file is still parsed. The errors of the inheritance and the hierarchies are reported, but
+/// the resources are kept.
+#[derive(Debug, Clone, Getters)]
+pub struct LoadReport {
+    #[getset(get = "pub")]
+    errors: Vec<Error>,
+    storage: Box<Storage>,
+}
+
+impl LoadReport {
+    pub(crate) fn new(errors: Vec<Error>, storage: Storage) -> Self {
+        Self {
+            errors,
+            storage: Box::new(storage),
+        }
+    }
+
+    /// Returns the [Storage] built with the valid parts of the files.
+    pub fn partial_storage(&self) -> &Storage {
+        &self.storage
+    }
+
+    pub fn into_partial_storage(self) -> Storage {
+        *self.storage
+    }
+
+    /// Renders every error, with a snippet of the source if it's located.
+    pub fn render(&self) -> String {
+        self.errors
+            .iter()
+            .map(|error| match error {
+                Error::Located(located) => located.render(),
+                error => format!("error: {error}"),
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use crate::{
+    errors::{Error, MinosResult},
+    language::requirements::Requirement,
+};
+
+use super::tokens::{Identifier, ParameterKind, Token};
+
+/// Macros by name.
+pub(crate) type Macros = HashMap<Identifier, Macro>;
+
+/// Adds the imported macros to the macros of the file. Fails with [`Error::DuplicatedMacro`]
+/// if a macro is already imported with another definition. The same definition can be imported
+/// through several files.
+pub(crate) fn add_imported_macros(macros: &mut Macros, imported: Macros) -> MinosResult<()> {
+    for (name, imported_macro) in imported {
+        if macros
+            .get(&name)
+            .is_some_and(|existing| existing != &imported_macro)
+        {
+            return Err(Error::DuplicatedMacro(name.to_string()));
+        }
+        macros.insert(name, imported_macro);
+    }
+
+    Ok(())
+}
+
+/// A macro definition, like `#HAS_ROLE(role) { actor.roles *= $role; }`. The parameters
+/// are replaced by the arguments of the call, like `#[HAS_ROLE("billing")]`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Macro {
+    parameters: Vec<Identifier>,
+    tokens: Vec<Token>,
+}
+
+impl Macro {
+    /// Fails with [`Error::MacroParameterNotExist`] if the content uses an undeclared parameter.
+    pub(crate) fn new(
+        name: &Identifier,
+        parameters: Vec<Identifier>,
+        tokens: Vec<Token>,
+    ) -> MinosResult<Self> {
+        let mut undeclared = None;
+        for token in &tokens {
+            visit_parameters(token, &mut |parameter| {
+                if undeclared.is_none() && !parameters.contains(parameter) {
+                    undeclared = Some(parameter.clone());
+                }
+            });
+        }
+        if let Some(parameter) = undeclared {
+            return Err(Error::MacroParameterNotExist {
+                name: name.to_string(),
+                parameter: parameter.to_string(),
+            });
+        }
+
+        Ok(Self { parameters, tokens })
+    }
+
+    /// Returns the content with the arguments in place of the parameters. Fails with
+    /// [`Error::MacroArity`] if the number of arguments isn't the number of parameters,
+    /// with [`Error::InvalidToken`] if an argument isn't of the [ParameterKind] of its
+    /// parameter, or with the error of a requirement built with the arguments.
+    pub(crate) fn expand(&self, name: &Identifier, arguments: Vec<Token>) -> MinosResult<Vec<Token>> {
+        if arguments.len() != self.parameters.len() {
+            return Err(Error::MacroArity {
+                name: name.to_string(),
+                expected: self.parameters.len(),
+                found: arguments.len(),
+            });
+        }
+
+        let mut tokens = self.tokens.clone();
+        if !arguments.is_empty() {
+            for token in &mut tokens {
+                replace_parameters(token, &self.parameters, &arguments)?;
+                check_requirement(token)?;
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+/// Builds the requirement of the token, so the errors of the model are located at the token.
+/// The tokens that aren't requirements, and the requirements with macro parameters, which
+/// are built when the macro is expanded, are skipped.
+pub(crate) fn check_requirement(token: &Token) -> MinosResult<()> {
+    let is_requirement = matches!(
+        token,
+        Token::Requirement(_) | Token::AnyBlock(_) | Token::AllBlock(_) | Token::NotBlock(_)
+    );
+    let mut has_parameters = false;
+    visit_parameters(token, &mut |_| has_parameters = true);
+    if is_requirement && !has_parameters {
+        Requirement::try_from(token)?;
+    }
+
+    Ok(())
+}
+
+/// Fails with [`Error::MacroParameterOutsideMacro`] if the token, or its inner tokens,
+/// is a macro parameter.
+pub(crate) fn check_no_parameters(token: &Token) -> MinosResult<()> {
+    let mut parameter = None;
+    visit_parameters(token, &mut |found| {
+        parameter.get_or_insert_with(|| found.clone());
+    });
+
+    match parameter {
+        Some(parameter) => Err(Error::MacroParameterOutsideMacro(parameter.to_string())),
+        None => Ok(()),
+    }
+}
+
+fn visit_parameters(token: &Token, visit: &mut impl FnMut(&Identifier)) {
+    match token {
+        Token::MacroParameter(parameter, _) => visit(parameter),
+        token => {
+            for inner_token in token.inner_tokens().into_iter().flatten() {
+                visit_parameters(inner_token, visit);
+            }
+        }
+    }
+}
+
+fn replace_parameters(
+    token: &mut Token,
+    parameters: &[Identifier],
+    arguments: &[Token],
+) -> MinosResult<()> {
+    if let Token::MacroParameter(parameter, kind) = token {
+        if let Some(index) = parameters.iter().position(|name| name == parameter) {
+            let argument = &arguments[index];
+            check_argument(*kind, argument)?;
+            *token = argument.clone();
+        }
+        return Ok(());
+    }
+
+    if let Some(inner_tokens) = token.inner_tokens_mut() {
+        for inner_token in inner_tokens {
+            replace_parameters(inner_token, parameters, arguments)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fails with [`Error::InvalidToken`] if the argument isn't of the kind.
+fn check_argument(kind: ParameterKind, argument: &Token) -> MinosResult<()> {
+    let (is_valid, expected) = match kind {
+        ParameterKind::String => (matches!(argument, Token::String(_)), "String"),
+        ParameterKind::Identifier => (matches!(argument, Token::Identifier(_)), "Identifier"),
+        ParameterKind::Number => (matches!(argument, Token::Number(_)), "Number"),
+        ParameterKind::Any => return Ok(()),
+    };
+
+    match is_valid {
+        true => Ok(()),
+        false => Err(Error::InvalidToken {
+            expected,
+            found: argument.to_string(),
+        }),
+    }
+}
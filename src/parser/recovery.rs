@@ -0,0 +1,170 @@
+use std::ops::Range;
+
+/// Byte ranges of the top-level items of a minos file: the imports, the hierarchies, the macro
+/// definitions and the resources. The header `syntax = <version>;` isn't an item. An unclosed item extends
+/// past the end of the content, so it contains the errors found at the end.
+pub(crate) fn top_level_items(content: &str) -> Vec<Range<usize>> {
+    let bytes = content.as_bytes();
+    let mut items = vec![];
+    let mut start = None;
+    let mut depth = 0usize;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if let Some(literal_end) = literal_end(content, index) {
+            index = literal_end + 1;
+            continue;
+        }
+        match bytes[index] {
+            b';' if depth == 0 => {
+                if let Some(item_start) = start {
+                    items.push(item_start..index + 1);
+                }
+                start = Some(index + 1);
+            }
+            b'{' => depth += 1,
+            b'}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    let item_start = start.unwrap_or_default();
+                    items.push(item_start..index + 1);
+                    start = Some(index + 1);
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    if depth > 0 {
+        items.push(start.unwrap_or_default()..bytes.len() + 1);
+    }
+
+    items
+}
+
+/// Replaces the content of the range by spaces, keeping the line breaks, so the positions
+/// of the rest of the content don't change.
+pub(crate) fn blank(content: &str, range: &Range<usize>) -> String {
+    let end = range.end.min(content.len());
+    let blanked: String = content[range.start..end]
+        .chars()
+        .map(|c| match c {
+            '\n' => "\n".to_string(),
+            c => " ".repeat(c.len_utf8()),
+        })
+        .collect();
+
+    format!("{}{blanked}{}", &content[..range.start], &content[end..])
+}
+
+/// A `policy { ... }` block of a resource, see [policies].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PolicyRange {
+    /// Offset of the opening brace of the environment, or of the resource for the implicit
+    /// default environment.
+    pub(crate) block: usize,
+    pub(crate) range: Range<usize>,
+}
+
+/// Byte ranges of the policies of the resource in `item`. An unclosed policy extends until
+/// the end of the item.
+pub(crate) fn policies(content: &str, item: &Range<usize>) -> Vec<PolicyRange> {
+    let bytes = content.as_bytes();
+    let end = item.end.min(bytes.len());
+    let mut policies = vec![];
+    let mut blocks = vec![];
+    let mut current: Option<(usize, usize)> = None;
+    let mut index = item.start;
+
+    while index < end {
+        if let Some(literal_end) = literal_end(content, index) {
+            index = literal_end + 1;
+            continue;
+        }
+        match bytes[index] {
+            b'{' => blocks.push(index),
+            b'}' => {
+                blocks.pop();
+                if let Some((start, block)) = current.filter(|(_, block)| blocks.last() == Some(block)) {
+                    policies.push(PolicyRange {
+                        block,
+                        range: start..index + 1,
+                    });
+                    current = None;
+                }
+            }
+            b'p' if current.is_none()
+                && (1..=2).contains(&blocks.len())
+                && is_keyword(content, index, "policy") =>
+            {
+                current = blocks.last().map(|block| (index, *block));
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    if let Some((start, block)) = current {
+        policies.push(PolicyRange {
+            block,
+            range: start..item.end,
+        });
+    }
+
+    policies
+}
+
+/// Returns the policy to skip for an error at `offset`, the one that contains it if its
+/// environment keeps some other policy, since an environment can't be empty.
+pub(crate) fn skippable_policy<'p>(
+    policies: &'p [PolicyRange],
+    skipped: &[Range<usize>],
+    offset: usize,
+) -> Option<&'p PolicyRange> {
+    let is_kept = |policy: &&PolicyRange| !skipped.contains(&policy.range);
+    let policy = policies
+        .iter()
+        .filter(is_kept)
+        .find(|policy| policy.range.contains(&offset))?;
+
+    policies
+        .iter()
+        .filter(is_kept)
+        .any(|other| other.block == policy.block && other != policy)
+        .then_some(policy)
+}
+
+/// Returns the index of the last byte of the string or the comment that starts at `index`.
+fn literal_end(content: &str, index: usize) -> Option<usize> {
+    let bytes = content.as_bytes();
+
+    match bytes[index] {
+        b'"' => {
+            let mut end = index + 1;
+            while end < bytes.len() && bytes[end] != b'"' {
+                if bytes[end] == b'\\' {
+                    end += 1;
+                }
+                end += 1;
+            }
+            Some(end)
+        }
+        b'/' if bytes.get(index + 1) == Some(&b'*') => Some(
+            content[index + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |end| index + 2 + end + 1),
+        ),
+        _ => None,
+    }
+}
+
+fn is_keyword(content: &str, index: usize, keyword: &str) -> bool {
+    let is_identifier = |c: u8| c.is_ascii_alphanumeric() || c == b'_' || c == b'.';
+    let bytes = content.as_bytes();
+    let end = index + keyword.len();
+
+    content[index..].starts_with(keyword)
+        && (index == 0 || !is_identifier(bytes[index - 1]))
+        && bytes.get(end).is_none_or(|c| !is_identifier(*c))
+}
@@ -1,6 +1,9 @@
-use std::sync::Arc;
+use std::{cmp::Ordering, convert::Infallible, fmt, str, sync::Arc};
 
 use parse_display::{Display, FromStr};
+use pest::{iterators::Pair, RuleType};
+
+use crate::errors::SourcePosition;
 
 #[derive(Debug, Clone, PartialEq, Eq, Display)]
 pub enum Token {
@@ -16,9 +19,16 @@ pub enum Token {
     #[display("MacroCall")]
     MacroCall(Vec<Token>),
 
+    /// A parameter used in a macro definition, like `$role`, with the kind of its arguments.
+    #[display("MacroParameter")]
+    MacroParameter(Identifier, ParameterKind),
+
     #[display("Resource")]
     Resource(Vec<Token>),
 
+    #[display("Extends")]
+    Extends(Identifier),
+
     #[display("AttributedResource")]
     AttributedResource(Vec<Token>),
 
@@ -37,15 +47,51 @@ pub enum Token {
     #[display("Allow")]
     Allow(Vec<Token>),
 
+    #[display("Deny")]
+    Deny(Vec<Token>),
+
     #[display("Rule")]
     Rule(Vec<Token>),
 
     #[display("Array")]
     Array(Array),
 
+    #[display("RolesHierarchy")]
+    RolesHierarchy(Vec<Token>),
+
+    #[display("GroupsHierarchy")]
+    GroupsHierarchy(Vec<Token>),
+
+    #[display("HierarchyChain")]
+    HierarchyChain(Vec<Token>),
+
+    #[display("Schema")]
+    Schema(Vec<Token>),
+
+    #[display("ActorTypes")]
+    ActorTypes(Vec<Token>),
+
+    #[display("ResourceTypes")]
+    ResourceTypes(Vec<Token>),
+
+    #[display("Statuses")]
+    Statuses(Vec<Token>),
+
+    #[display("SchemaPermissions")]
+    SchemaPermissions(Vec<Token>),
+
     #[display("Requirement")]
     Requirement(Vec<Token>),
 
+    #[display("AnyBlock")]
+    AnyBlock(Vec<Token>),
+
+    #[display("AllBlock")]
+    AllBlock(Vec<Token>),
+
+    #[display("NotBlock")]
+    NotBlock(Vec<Token>),
+
     #[display("Assertion")]
     Assertion(Vec<Token>),
 
@@ -55,12 +101,39 @@ pub enum Token {
     #[display("Search")]
     Search(Vec<Token>),
 
+    #[display("Comparison")]
+    Comparison(Vec<Token>),
+
+    #[display("Match")]
+    Match(Vec<Token>),
+
+    #[display("Temporal")]
+    Temporal(Vec<Token>),
+
+    #[display("TimeAttribute")]
+    TimeAttribute(TimeAttribute),
+
+    #[display("Weekday")]
+    Weekday(Weekday),
+
+    #[display("Hour")]
+    Hour(u8),
+
+    #[display("GlobPattern")]
+    GlobPattern(Arc<str>),
+
+    #[display("RegexPattern")]
+    RegexPattern(Arc<str>),
+
     #[display("ActorAttribute")]
     ActorAttribute(ActorAttribute),
 
     #[display("ResourceAttribute")]
     ResourceAttribute(ResourceAttribute),
 
+    #[display("ContextAttribute")]
+    ContextAttribute(ContextAttribute),
+
     #[display("Operator")]
     Operator(Operator),
 
@@ -73,11 +146,32 @@ pub enum Token {
     #[display("String")]
     String(Arc<str>),
 
+    #[display("Number")]
+    Number(Number),
+
     #[display("Null")]
     Null,
+
+    /// Position in the file of the token that contains it, the last inner token
+    /// of the named environments, the policies and the rules.
+    #[display("Position")]
+    Position(SourcePosition),
 }
 
 impl Token {
+    /// Returns the [Token::Position] of the start of the pair.
+    pub(crate) fn position_of<R: RuleType>(pair: &Pair<R>) -> Token {
+        Token::Position(SourcePosition::new(pair.get_input(), pair.as_span().start()))
+    }
+
+    /// Splits the inner tokens from the [Token::Position] added by the parser, if any.
+    pub(crate) fn split_position(tokens: &[Token]) -> (&[Token], Option<&SourcePosition>) {
+        match tokens.split_last() {
+            Some((Token::Position(position), inner)) => (inner, Some(position)),
+            _ => (tokens, None),
+        }
+    }
+
     pub fn inner_file(&self) -> Option<&Vec<Token>> {
         if let Token::File(inner) = self {
             return Some(inner);
@@ -142,6 +236,14 @@ impl Token {
         None
     }
 
+    pub fn inner_deny(&self) -> Option<&Vec<Token>> {
+        if let Token::Deny(inner) = self {
+            return Some(inner);
+        }
+
+        None
+    }
+
     pub fn inner_array(&self) -> Option<&Array> {
         if let Token::Array(inner) = self {
             return Some(inner);
@@ -158,9 +260,9 @@ impl Token {
         None
     }
 
-    pub fn inner_resource_attribute(&self) -> Option<ResourceAttribute> {
+    pub fn inner_resource_attribute(&self) -> Option<&ResourceAttribute> {
         if let Token::ResourceAttribute(inner) = self {
-            return Some(*inner);
+            return Some(inner);
         }
 
         None
@@ -181,14 +283,102 @@ impl Token {
 
         None
     }
+
+    /// Returns the inner tokens of the tokens that contain other tokens.
+    pub(crate) fn inner_tokens(&self) -> Option<&Vec<Token>> {
+        match self {
+            Token::File(inner)
+            | Token::MacroCall(inner)
+            | Token::Resource(inner)
+            | Token::AttributedResource(inner)
+            | Token::NamedEnv(inner)
+            | Token::DefaultEnv(inner)
+            | Token::ImplicitDefaultEnv(inner)
+            | Token::Policy(inner)
+            | Token::Allow(inner)
+            | Token::Deny(inner)
+            | Token::Rule(inner)
+            | Token::RolesHierarchy(inner)
+            | Token::GroupsHierarchy(inner)
+            | Token::HierarchyChain(inner)
+            | Token::Schema(inner)
+            | Token::ActorTypes(inner)
+            | Token::ResourceTypes(inner)
+            | Token::Statuses(inner)
+            | Token::SchemaPermissions(inner)
+            | Token::Requirement(inner)
+            | Token::AnyBlock(inner)
+            | Token::AllBlock(inner)
+            | Token::NotBlock(inner)
+            | Token::Assertion(inner)
+            | Token::Negation(inner)
+            | Token::Search(inner)
+            | Token::Comparison(inner)
+            | Token::Match(inner)
+            | Token::Temporal(inner)
+            | Token::StringDefinition(inner) => Some(inner),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn inner_tokens_mut(&mut self) -> Option<&mut Vec<Token>> {
+        match self {
+            Token::File(inner)
+            | Token::MacroCall(inner)
+            | Token::Resource(inner)
+            | Token::AttributedResource(inner)
+            | Token::NamedEnv(inner)
+            | Token::DefaultEnv(inner)
+            | Token::ImplicitDefaultEnv(inner)
+            | Token::Policy(inner)
+            | Token::Allow(inner)
+            | Token::Deny(inner)
+            | Token::Rule(inner)
+            | Token::RolesHierarchy(inner)
+            | Token::GroupsHierarchy(inner)
+            | Token::HierarchyChain(inner)
+            | Token::Schema(inner)
+            | Token::ActorTypes(inner)
+            | Token::ResourceTypes(inner)
+            | Token::Statuses(inner)
+            | Token::SchemaPermissions(inner)
+            | Token::Requirement(inner)
+            | Token::AnyBlock(inner)
+            | Token::AllBlock(inner)
+            | Token::NotBlock(inner)
+            | Token::Assertion(inner)
+            | Token::Negation(inner)
+            | Token::Search(inner)
+            | Token::Comparison(inner)
+            | Token::Match(inner)
+            | Token::Temporal(inner)
+            | Token::StringDefinition(inner) => Some(inner),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, Display, FromStr, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Display, FromStr, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub enum FileVersion {
+    #[default]
     #[display("0.16")]
     V0_16,
     #[display("0.16M")]
     V0_16M,
+    #[display("0.17")]
+    V0_17,
+    #[display("0.17M")]
+    V0_17M,
+}
+
+impl FileVersion {
+    /// Returns the equivalent version without macros, useful when the macros are already expanded.
+    pub fn without_macros(self) -> Self {
+        match self {
+            FileVersion::V0_16 | FileVersion::V0_16M => FileVersion::V0_16,
+            FileVersion::V0_17 | FileVersion::V0_17M => FileVersion::V0_17,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -197,6 +387,11 @@ pub struct Array(pub Vec<Arc<str>>);
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Identifier(pub Arc<str>);
 
+impl Identifier {
+    /// Name of the resource declared as `resource * { ... }`.
+    pub const WILDCARD: &'static str = "*";
+}
+
 impl From<&str> for Identifier {
     fn from(value: &str) -> Self {
         Self(value.into())
@@ -215,7 +410,21 @@ impl From<String> for Identifier {
     }
 }
 
-#[derive(Debug, Clone, Copy, Display, FromStr, PartialEq, Eq)]
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl str::FromStr for Identifier {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+#[derive(Debug, Clone, Display, FromStr, PartialEq, Eq)]
 pub enum ActorAttribute {
     #[display("actor.type")]
     Type,
@@ -231,9 +440,13 @@ pub enum ActorAttribute {
 
     #[display("actor.status")]
     Status,
+
+    /// Custom attribute, declared as `actor.attrs.<name>`.
+    #[display("actor.attrs.{0}")]
+    Attrs(Identifier),
 }
 
-#[derive(Debug, Clone, Copy, Display, FromStr, PartialEq, Eq)]
+#[derive(Debug, Clone, Display, FromStr, PartialEq, Eq)]
 pub enum ResourceAttribute {
     #[display("resource.id")]
     Id,
@@ -246,8 +459,77 @@ pub enum ResourceAttribute {
 
     #[display("resource.status")]
     Status,
+
+    /// Custom attribute, declared as `resource.attrs.<name>`.
+    #[display("resource.attrs.{0}")]
+    Attrs(Identifier),
+}
+
+/// Integer or decimal number. The decimals are always finite.
+#[derive(Debug, Clone, Copy)]
+pub enum Number {
+    Integer(i64),
+    Decimal(f64),
 }
 
+impl Number {
+    /// Parses an integer or a finite decimal.
+    pub fn parse(s: &str) -> Option<Self> {
+        if let Ok(value) = s.parse::<i64>() {
+            return Some(Number::Integer(value));
+        }
+
+        s.parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .map(Number::Decimal)
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Number::Integer(value) => *value as f64,
+            Number::Decimal(value) => *value,
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Number::Integer(left), Number::Integer(right)) => left.cmp(right),
+            (left, right) => left.as_f64().total_cmp(&right.as_f64()),
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Integer(value) => write!(f, "{value}"),
+            Number::Decimal(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+/// Attribute of the request context, declared as `context.<name>`.
+#[derive(Debug, Clone, Display, FromStr, PartialEq, Eq)]
+#[display("context.{0}")]
+pub struct ContextAttribute(pub Identifier);
+
 #[derive(Debug, Clone, Copy, Display, FromStr, PartialEq, Eq)]
 pub enum Operator {
     #[display("=")]
@@ -258,4 +540,62 @@ pub enum Operator {
 
     #[display("*=")]
     Search,
+
+    #[display("<")]
+    LessThan,
+
+    #[display("<=")]
+    LessOrEqual,
+
+    #[display(">")]
+    GreaterThan,
+
+    #[display(">=")]
+    GreaterOrEqual,
+
+    #[display("~=")]
+    Match,
+
+    #[display("before")]
+    Before,
+
+    #[display("after")]
+    After,
+
+    #[display("in")]
+    In,
+}
+
+/// Kind of the arguments of a macro parameter, given by the value that the parameter replaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterKind {
+    String,
+    Identifier,
+    Number,
+    /// The value of a custom attribute, which can be any argument.
+    Any,
+}
+
+/// Attributes of the request's instant, used by the temporal requirements.
+#[derive(Debug, Clone, Copy, Display, FromStr, PartialEq, Eq)]
+pub enum TimeAttribute {
+    #[display("context.time")]
+    Time,
+
+    #[display("context.weekday")]
+    Weekday,
+
+    #[display("context.hour")]
+    Hour,
+}
+
+#[derive(Debug, Clone, Copy, Display, FromStr, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
 }
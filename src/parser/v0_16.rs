@@ -3,7 +3,11 @@ use std::{collections::HashMap, str::FromStr, sync::Arc};
 use pest::{iterators::Pair, Parser};
 use pest_derive::Parser;
 
-use crate::{language::storage::Storage, Error, MinosResult};
+use crate::{
+    errors::syntax_error,
+    language::{requirements::Requirement, storage::Storage},
+    Error, MinosResult,
+};
 
 use super::tokens::{
     ActorAttribute, Array, FileVersion, Identifier, Operator, ResourceAttribute, Token,
@@ -23,7 +27,7 @@ impl MinosParserV0_16 {
             .collect()
     }
 
-    fn extract_next_str(pair: Pair<Rule>) -> Option<&str> {
+    fn extract_next_str(pair: Pair<'_, Rule>) -> Option<&str> {
         pair.into_inner().next().map(|inner_pair| inner_pair.as_str())
     }
 
@@ -51,10 +55,16 @@ impl MinosParserV0_16 {
         }
     }
 
+    /// Builds the token of the pair. The errors are located at the start of the pair.
     pub(crate) fn parse_token(
         pair: Pair<Rule>,
         values_map: &mut HashMap<String, Arc<str>>,
     ) -> MinosResult<Token> {
+        let (input, offset) = (pair.get_input(), pair.as_span().start());
+        Self::build_token(pair, values_map).map_err(|err| err.at(input, offset))
+    }
+
+    fn build_token(pair: Pair<Rule>, values_map: &mut HashMap<String, Arc<str>>) -> MinosResult<Token> {
         let token = match pair.as_rule() {
             Rule::file => Token::File(Self::parse_tokens(pair, values_map)?),
             Rule::version => Token::Version(FileVersion::from_str(pair.as_str())?),
@@ -62,19 +72,39 @@ impl MinosParserV0_16 {
             Rule::attributed_resource => {
                 Token::AttributedResource(Self::parse_tokens(pair, values_map)?)
             }
-            Rule::named_env => Token::NamedEnv(Self::parse_tokens(pair, values_map)?),
+            Rule::named_env => {
+                let position = Token::position_of(&pair);
+                let mut tokens = Self::parse_tokens(pair, values_map)?;
+                tokens.push(position);
+                Token::NamedEnv(tokens)
+            }
             Rule::default_env => Token::DefaultEnv(Self::parse_tokens(pair, values_map)?),
             Rule::implicit_default_env => {
                 Token::ImplicitDefaultEnv(Self::parse_tokens(pair, values_map)?)
             }
-            Rule::policy => Token::Policy(Self::parse_tokens(pair, values_map)?),
+            Rule::policy => {
+                let position = Token::position_of(&pair);
+                let mut tokens = Self::parse_tokens(pair, values_map)?;
+                tokens.push(position);
+                Token::Policy(tokens)
+            }
             Rule::allow => Token::Allow(Self::parse_tokens(pair, values_map)?),
-            Rule::rule => Token::Rule(Self::parse_tokens(pair, values_map)?),
+            Rule::rule => {
+                let position = Token::position_of(&pair);
+                let mut tokens = Self::parse_tokens(pair, values_map)?;
+                tokens.push(position);
+                Token::Rule(tokens)
+            }
             Rule::array => {
                 let inner_values = Self::extract_next_array(pair, values_map);
                 Token::Array(Array(inner_values))
             }
-            Rule::requirement => Token::Requirement(Self::parse_tokens(pair, values_map)?),
+            Rule::requirement => {
+                let token = Token::Requirement(Self::parse_tokens(pair, values_map)?);
+                // built here to locate the errors of the model at the requirement
+                Requirement::try_from(&token)?;
+                token
+            }
             Rule::assertion => Token::Assertion(Self::parse_tokens(pair, values_map)?),
             Rule::negation => Token::Negation(Self::parse_tokens(pair, values_map)?),
             Rule::search => Token::Search(Self::parse_tokens(pair, values_map)?),
@@ -111,9 +141,18 @@ impl MinosParserV0_16 {
         content: &str,
         values_map: &mut HashMap<String, Arc<str>>,
     ) -> MinosResult<Storage> {
-        let file_rules = Self::parse(Rule::file, content)?.next().unwrap();
+        let file_rules = Self::parse(Rule::file, content)
+            .map_err(|err| syntax_error(err, content))?
+            .next()
+            .unwrap();
+        let offsets: Vec<usize> = file_rules
+            .clone()
+            .into_inner()
+            .map(|p| p.as_span().start())
+            .collect();
         let file_token = Self::parse_token(file_rules, values_map)?;
-        let storage = Storage::try_from(file_token)?;
+        let storage =
+            Storage::from_file_token(file_token, |index, err| err.at(content, offsets[index]))?;
 
         Ok(storage)
     }
@@ -3,10 +3,15 @@ use std::{collections::HashMap, str::FromStr, sync::Arc};
 use pest::{iterators::Pair, Parser};
 use pest_derive::Parser;
 
-use crate::{language::storage::Storage, Error, MinosResult};
+use crate::{errors::syntax_error, language::storage::Storage, Error, MinosResult};
+
+use super::{
+    imports::Imports,
+    macros::{add_imported_macros, check_no_parameters, check_requirement, Macro, Macros},
+};
 
 use super::tokens::{
-    ActorAttribute, Array, FileVersion, Identifier, Operator, ResourceAttribute, Token,
+    ActorAttribute, Array, FileVersion, Identifier, Operator, ParameterKind, ResourceAttribute, Token,
 };
 
 #[derive(Debug, Parser)]
@@ -16,7 +21,7 @@ pub(crate) struct MinosParserV0_16M;
 impl MinosParserV0_16M {
     fn parse_tokens(
         pair: Pair<Rule>,
-        macro_tokens: &mut HashMap<Identifier, Vec<Token>>,
+        macro_tokens: &mut Macros,
         values_map: &mut HashMap<String, Arc<str>>,
     ) -> MinosResult<Vec<Token>> {
         pair.into_inner()
@@ -36,7 +41,7 @@ impl MinosParserV0_16M {
         }
     }
 
-    fn extract_next_str(pair: Pair<Rule>) -> Option<&str> {
+    fn extract_next_str(pair: Pair<'_, Rule>) -> Option<&str> {
         pair.into_inner().next().map(|inner_pair| inner_pair.as_str())
     }
 
@@ -52,18 +57,25 @@ impl MinosParserV0_16M {
 
     fn parse_array(
         pair: Pair<Rule>,
-        macro_tokens: &mut HashMap<Identifier, Vec<Token>>,
+        macro_tokens: &mut Macros,
         values_map: &mut HashMap<String, Arc<str>>,
     ) -> MinosResult<Vec<Arc<str>>> {
         let mut permissions = vec![];
         for pair in pair.into_inner() {
+            let (input, offset) = (pair.get_input(), pair.as_span().start());
             let parsed_token = Self::parse_token(pair, macro_tokens, values_map)?;
             match parsed_token {
                 Token::String(permission) => permissions.push(permission),
                 Token::MacroCall(tokens) => {
                     for token in tokens {
-                        if let Token::String(permission) = token {
-                            permissions.push(permission);
+                        match token {
+                            Token::String(permission) => permissions.push(permission),
+                            // located at the call, instead of the array
+                            token => Err(Error::InvalidToken {
+                                expected: "String",
+                                found: token.to_string(),
+                            }
+                            .at(input, offset))?,
                         }
                     }
                 }
@@ -79,27 +91,30 @@ impl MinosParserV0_16M {
 
     fn extract_macro_tokens(
         pair: Pair<Rule>,
-        macro_tokens: &mut HashMap<Identifier, Vec<Token>>,
+        macro_tokens: &mut Macros,
         values_map: &mut HashMap<String, Arc<str>>,
     ) -> MinosResult<()> {
-        let mut inner_tokens = Self::parse_tokens(pair, macro_tokens, values_map)?;
-        let first_token = inner_tokens[0].clone();
-        let ident = match first_token {
-            Token::Identifier(ident) => ident,
-            _ => Err(Error::InvalidToken {
-                expected: "Identifier",
-                found: first_token.to_string(),
-            })?,
+        let mut inner_pairs = pair.into_inner().peekable();
+        let ident_pair = inner_pairs.next().ok_or(Error::MissingToken)?;
+        let ident = Identifier(Self::get_optimized_pointer(values_map, ident_pair.as_str()));
+        let parameters = match inner_pairs.next_if(|p| p.as_rule() == Rule::macro_parameters) {
+            Some(parameters) => parameters
+                .into_inner()
+                .map(|p| Identifier(Self::get_optimized_pointer(values_map, p.as_str())))
+                .collect(),
+            None => vec![],
         };
-        let _ = inner_tokens.remove(0);
-        macro_tokens.insert(ident, inner_tokens);
+        let inner_tokens = inner_pairs
+            .map(|p| Self::parse_token(p, macro_tokens, values_map))
+            .collect::<MinosResult<Vec<Token>>>()?;
+        macro_tokens.insert(ident.clone(), Macro::new(&ident, parameters, inner_tokens)?);
 
         Ok(())
     }
 
     fn extract_requirements(
         pair: Pair<Rule>,
-        macro_tokens: &mut HashMap<Identifier, Vec<Token>>,
+        macro_tokens: &mut Macros,
         values_map: &mut HashMap<String, Arc<str>>,
     ) -> MinosResult<Vec<Token>> {
         let mut requirements = vec![];
@@ -118,39 +133,85 @@ impl MinosParserV0_16M {
         Ok(requirements)
     }
 
+    /// Builds the token of the pair. The errors are located at the start of the pair.
     pub(crate) fn parse_token(
         pair: Pair<Rule>,
-        macro_tokens: &mut HashMap<Identifier, Vec<Token>>,
+        macro_tokens: &mut Macros,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Token> {
+        let (input, offset) = (pair.get_input(), pair.as_span().start());
+        Self::build_token(pair, macro_tokens, values_map).map_err(|err| err.at(input, offset))
+    }
+
+    fn build_token(
+        pair: Pair<Rule>,
+        macro_tokens: &mut Macros,
         values_map: &mut HashMap<String, Arc<str>>,
     ) -> MinosResult<Token> {
         let token = match pair.as_rule() {
-            Rule::file => Token::File(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::file | Rule::macros_file => {
+                Token::File(Self::parse_tokens(pair, macro_tokens, values_map)?)
+            }
             Rule::version => Token::Version(FileVersion::from_str(pair.as_str())?),
             Rule::macro_definition => {
                 Self::extract_macro_tokens(pair, macro_tokens, values_map)?;
                 Token::MacroDefinition
             }
             Rule::macro_call => {
+                let mut inner_pairs = pair.into_inner();
+                let ident_pair = inner_pairs.next().ok_or(Error::MissingToken)?;
                 let macro_ident =
-                    Self::extract_next_identifier(pair, values_map).ok_or(Error::MissingToken)?;
-                let macro_tokens = macro_tokens
+                    Identifier(Self::get_optimized_pointer(values_map, ident_pair.as_str()));
+                let arguments = match inner_pairs.next() {
+                    Some(arguments) => Self::parse_tokens(arguments, macro_tokens, values_map)?,
+                    None => vec![],
+                };
+                let macro_definition = macro_tokens
                     .get(&macro_ident)
                     .ok_or(Error::MacroNotExist(macro_ident.0.to_string()))?;
-                Token::MacroCall(macro_tokens.clone())
+                Token::MacroCall(macro_definition.expand(&macro_ident, arguments)?)
+            }
+            Rule::text_parameter | Rule::identifier_parameter => {
+                let kind = match pair.as_rule() {
+                    Rule::text_parameter => ParameterKind::String,
+                    _ => ParameterKind::Identifier,
+                };
+                let parameter =
+                    Self::extract_next_identifier(pair, values_map).ok_or(Error::MissingToken)?;
+                Token::MacroParameter(parameter, kind)
+            }
+            Rule::resource => {
+                let token = Token::Resource(Self::parse_tokens(pair, macro_tokens, values_map)?);
+                check_no_parameters(&token)?;
+                token
             }
-            Rule::resource => Token::Resource(Self::parse_tokens(pair, macro_tokens, values_map)?),
             Rule::attributed_resource => {
-                Token::AttributedResource(Self::parse_tokens(pair, macro_tokens, values_map)?)
+                let token =
+                    Token::AttributedResource(Self::parse_tokens(pair, macro_tokens, values_map)?);
+                check_no_parameters(&token)?;
+                token
+            }
+            Rule::named_env => {
+                let position = Token::position_of(&pair);
+                let mut tokens = Self::parse_tokens(pair, macro_tokens, values_map)?;
+                tokens.push(position);
+                Token::NamedEnv(tokens)
             }
-            Rule::named_env => Token::NamedEnv(Self::parse_tokens(pair, macro_tokens, values_map)?),
             Rule::default_env => Token::DefaultEnv(Self::parse_tokens(pair, macro_tokens, values_map)?),
             Rule::implicit_default_env => {
                 Token::ImplicitDefaultEnv(Self::parse_tokens(pair, macro_tokens, values_map)?)
             }
-            Rule::policy => Token::Policy(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::policy => {
+                let position = Token::position_of(&pair);
+                let mut tokens = Self::parse_tokens(pair, macro_tokens, values_map)?;
+                tokens.push(position);
+                Token::Policy(tokens)
+            }
             Rule::allow => Token::Allow(Self::parse_tokens(pair, macro_tokens, values_map)?),
             Rule::rule => {
-                let requirements = Self::extract_requirements(pair, macro_tokens, values_map)?;
+                let position = Token::position_of(&pair);
+                let mut requirements = Self::extract_requirements(pair, macro_tokens, values_map)?;
+                requirements.push(position);
 
                 Token::Rule(requirements)
             }
@@ -158,7 +219,11 @@ impl MinosParserV0_16M {
                 let permissions = Self::parse_array(pair, macro_tokens, values_map)?;
                 Token::Array(Array(permissions))
             }
-            Rule::requirement => Token::Requirement(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::requirement => {
+                let token = Token::Requirement(Self::parse_tokens(pair, macro_tokens, values_map)?);
+                check_requirement(&token)?;
+                token
+            }
             Rule::assertion => Token::Assertion(Self::parse_tokens(pair, macro_tokens, values_map)?),
             Rule::negation => Token::Negation(Self::parse_tokens(pair, macro_tokens, values_map)?),
             Rule::search => Token::Search(Self::parse_tokens(pair, macro_tokens, values_map)?),
@@ -183,23 +248,103 @@ impl MinosParserV0_16M {
                 let arc_val = Self::get_optimized_pointer(values_map, inner_str);
                 Token::String(arc_val)
             }
-            Rule::inner_string | Rule::COMMENT | Rule::char | Rule::WHITESPACE | Rule::EOI => {
-                Token::Null
-            }
+            // the imports are resolved before building the file's token, and the macro
+            // parameters and arguments are read with the macro definitions and calls
+            Rule::import_directive
+            | Rule::macro_parameters
+            | Rule::macro_arguments
+            | Rule::macro_argument
+            | Rule::text_value
+            | Rule::identifier_value
+            | Rule::inner_string
+            | Rule::COMMENT
+            | Rule::char
+            | Rule::WHITESPACE
+            | Rule::file_content
+            | Rule::EOI => Token::Null,
         };
 
         Ok(token)
     }
 
-    pub fn parse_file_content(
+    pub(crate) fn parse_file_content(
         content: &str,
         values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
     ) -> MinosResult<Storage> {
-        let file_rules = Self::parse(Rule::file, content)?.next().unwrap();
-        let mut macro_tokens: HashMap<Identifier, Vec<Token>> = HashMap::new();
-        let file_token = Self::parse_token(file_rules, &mut macro_tokens, values_map)?;
-        let storage = Storage::try_from(file_token)?;
+        Self::parse_storage(Rule::file, content, values_map, imports)
+    }
+
+    /// Like [`Self::parse_file_content`], but the file can be a library with only macros,
+    /// see [`Rule::macros_file`].
+    pub(crate) fn parse_library_content(
+        content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<Storage> {
+        Self::parse_storage(Rule::macros_file, content, values_map, imports)
+    }
+
+    fn parse_storage(
+        file_rule: Rule,
+        content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<Storage> {
+        let (file_token, offsets, _) = Self::parse_file_token(file_rule, content, values_map, imports)?;
+        let storage =
+            Storage::from_file_token(file_token, |index, err| err.at(content, offsets[index]))?;
 
         Ok(storage)
     }
+
+    /// Returns the macros defined and imported by the file, which doesn't need resources.
+    pub(crate) fn parse_macros(
+        content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<Macros> {
+        let (_, _, macro_tokens) =
+            Self::parse_file_token(Rule::macros_file, content, values_map, imports)?;
+
+        Ok(macro_tokens)
+    }
+
+    /// Returns the token of the file parsed with `file_rule`, [`Rule::file`] or [`Rule::macros_file`],
+    /// the offsets of its inner tokens and the macros. The imported
+    /// macros are loaded before the file's macros, which can redefine them.
+    fn parse_file_token(
+        file_rule: Rule,
+        content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<(Token, Vec<usize>, Macros)> {
+        let file_rules = Self::parse(file_rule, content)
+            .map_err(|err| syntax_error(err, content))?
+            .next()
+            .unwrap();
+        let offsets: Vec<usize> = file_rules
+            .clone()
+            .into_inner()
+            .map(|p| p.as_span().start())
+            .collect();
+
+        let mut macro_tokens: Macros = HashMap::new();
+        let import_directives = file_rules
+            .clone()
+            .into_inner()
+            .filter(|pair| pair.as_rule() == Rule::import_directive);
+        for pair in import_directives {
+            let offset = pair.as_span().start();
+            let string = pair.into_inner().next().ok_or(Error::MissingToken)?;
+            let path = Self::extract_next_str(string).ok_or(Error::MissingToken)?;
+            imports
+                .import(path, values_map)
+                .and_then(|macros| add_imported_macros(&mut macro_tokens, macros))
+                .map_err(|err| err.at(content, offset))?;
+        }
+        let file_token = Self::parse_token(file_rules, &mut macro_tokens, values_map)?;
+
+        Ok((file_token, offsets, macro_tokens))
+    }
 }
@@ -0,0 +1,242 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use pest::{iterators::Pair, Parser};
+use pest_derive::Parser;
+
+use crate::{
+    errors::syntax_error,
+    language::{requirements::Requirement, storage::Storage},
+    Error, MinosResult,
+};
+
+use super::tokens::{
+    ActorAttribute, Array, ContextAttribute, FileVersion, Identifier, Number, Operator,
+    ResourceAttribute, TimeAttribute, Token, Weekday,
+};
+
+#[derive(Debug, Parser)]
+#[grammar = "../assets/minos-v0_17.pest"]
+pub(crate) struct MinosParserV0_17;
+
+impl MinosParserV0_17 {
+    fn parse_tokens(
+        pair: Pair<Rule>,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Vec<Token>> {
+        pair.into_inner()
+            .map(|p| Self::parse_token(p, values_map))
+            .collect()
+    }
+
+    fn extract_next_str(pair: Pair<'_, Rule>) -> Option<&str> {
+        pair.into_inner().next().map(|inner_pair| inner_pair.as_str())
+    }
+
+    fn extract_next_array(
+        pair: Pair<Rule>,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> Vec<Arc<str>> {
+        pair.into_inner()
+            .flat_map(|pair| match Self::parse_token(pair, values_map).unwrap() {
+                Token::String(value) => Some(value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn get_optimized_pointer(values_map: &mut HashMap<String, Arc<str>>, value: &str) -> Arc<str> {
+        match values_map.get(value) {
+            Some(val) => val.clone(),
+            None => {
+                let arc: Arc<str> = Arc::from(value);
+                values_map.insert(value.to_string(), arc.clone());
+
+                arc
+            }
+        }
+    }
+
+    /// Builds the token of the pair. The errors are located at the start of the pair.
+    pub(crate) fn parse_token(
+        pair: Pair<Rule>,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Token> {
+        let (input, offset) = (pair.get_input(), pair.as_span().start());
+        Self::build_token(pair, values_map).map_err(|err| err.at(input, offset))
+    }
+
+    fn build_token(pair: Pair<Rule>, values_map: &mut HashMap<String, Arc<str>>) -> MinosResult<Token> {
+        let token = match pair.as_rule() {
+            Rule::file => Token::File(Self::parse_tokens(pair, values_map)?),
+            Rule::version => Token::Version(FileVersion::from_str(pair.as_str())?),
+            Rule::resource => Token::Resource(Self::parse_tokens(pair, values_map)?),
+            Rule::attributed_resource => {
+                Token::AttributedResource(Self::parse_tokens(pair, values_map)?)
+            }
+            Rule::extends => {
+                let parent = Self::extract_next_str(pair).ok_or(Error::MissingToken)?;
+                Token::Extends(Identifier(Self::get_optimized_pointer(values_map, parent)))
+            }
+            Rule::named_env => {
+                let position = Token::position_of(&pair);
+                let mut tokens = Self::parse_tokens(pair, values_map)?;
+                tokens.push(position);
+                Token::NamedEnv(tokens)
+            }
+            Rule::default_env => Token::DefaultEnv(Self::parse_tokens(pair, values_map)?),
+            Rule::implicit_default_env => {
+                Token::ImplicitDefaultEnv(Self::parse_tokens(pair, values_map)?)
+            }
+            Rule::roles_hierarchy => Token::RolesHierarchy(Self::parse_tokens(pair, values_map)?),
+            Rule::groups_hierarchy => Token::GroupsHierarchy(Self::parse_tokens(pair, values_map)?),
+            Rule::hierarchy_chain => Token::HierarchyChain(Self::parse_tokens(pair, values_map)?),
+            Rule::schema => Token::Schema(Self::parse_tokens(pair, values_map)?),
+            Rule::actor_types => Token::ActorTypes(Self::parse_tokens(pair, values_map)?),
+            Rule::resource_types => Token::ResourceTypes(Self::parse_tokens(pair, values_map)?),
+            Rule::statuses => Token::Statuses(Self::parse_tokens(pair, values_map)?),
+            Rule::schema_permissions => Token::SchemaPermissions(Self::parse_tokens(pair, values_map)?),
+            Rule::policy => {
+                let position = Token::position_of(&pair);
+                let mut tokens = Self::parse_tokens(pair, values_map)?;
+                tokens.push(position);
+                Token::Policy(tokens)
+            }
+            Rule::allow => Token::Allow(Self::parse_tokens(pair, values_map)?),
+            Rule::deny => Token::Deny(Self::parse_tokens(pair, values_map)?),
+            Rule::rule => {
+                let position = Token::position_of(&pair);
+                let mut tokens = Self::parse_tokens(pair, values_map)?;
+                tokens.push(position);
+                Token::Rule(tokens)
+            }
+            Rule::array => {
+                let inner_values = Self::extract_next_array(pair, values_map);
+                Token::Array(Array(inner_values))
+            }
+            Rule::requirement => {
+                let token = Token::Requirement(Self::parse_tokens(pair, values_map)?);
+                // built here to locate the errors of the model at the requirement
+                Requirement::try_from(&token)?;
+                token
+            }
+            Rule::any_block => Token::AnyBlock(Self::parse_tokens(pair, values_map)?),
+            Rule::all_block => Token::AllBlock(Self::parse_tokens(pair, values_map)?),
+            Rule::not_block => Token::NotBlock(Self::parse_tokens(pair, values_map)?),
+            Rule::assertion => Token::Assertion(Self::parse_tokens(pair, values_map)?),
+            Rule::negation => Token::Negation(Self::parse_tokens(pair, values_map)?),
+            Rule::search => Token::Search(Self::parse_tokens(pair, values_map)?),
+            Rule::comparison => Token::Comparison(Self::parse_tokens(pair, values_map)?),
+            Rule::pattern_match => Token::Match(Self::parse_tokens(pair, values_map)?),
+            Rule::temporal => Token::Temporal(Self::parse_tokens(pair, values_map)?),
+            Rule::glob_pattern => {
+                let string = pair.into_inner().next().ok_or(Error::MissingToken)?;
+                let pattern = Self::extract_next_str(string).ok_or(Error::MissingToken)?;
+                Token::GlobPattern(Self::get_optimized_pointer(values_map, pattern))
+            }
+            Rule::regex_pattern => {
+                let string = pair.into_inner().next().ok_or(Error::MissingToken)?;
+                let pattern = Self::extract_next_str(string).ok_or(Error::MissingToken)?;
+                Token::RegexPattern(Self::get_optimized_pointer(values_map, pattern))
+            }
+            Rule::actor_id => Token::ActorAttribute(ActorAttribute::Id),
+            Rule::actor_type => Token::ActorAttribute(ActorAttribute::Type),
+            Rule::actor_groups => Token::ActorAttribute(ActorAttribute::Groups),
+            Rule::actor_roles => Token::ActorAttribute(ActorAttribute::Roles),
+            Rule::actor_status => Token::ActorAttribute(ActorAttribute::Status),
+            Rule::actor_attrs => {
+                let name = Self::extract_next_str(pair).ok_or(Error::MissingToken)?;
+                let name = Self::get_optimized_pointer(values_map, name);
+                Token::ActorAttribute(ActorAttribute::Attrs(Identifier(name)))
+            }
+            Rule::resource_id => Token::ResourceAttribute(ResourceAttribute::Id),
+            Rule::resource_type => Token::ResourceAttribute(ResourceAttribute::Type),
+            Rule::resource_owner => Token::ResourceAttribute(ResourceAttribute::Owner),
+            Rule::resource_status => Token::ResourceAttribute(ResourceAttribute::Status),
+            Rule::resource_attrs => {
+                let name = Self::extract_next_str(pair).ok_or(Error::MissingToken)?;
+                let name = Self::get_optimized_pointer(values_map, name);
+                Token::ResourceAttribute(ResourceAttribute::Attrs(Identifier(name)))
+            }
+            Rule::context_attr => {
+                let name = Self::extract_next_str(pair).ok_or(Error::MissingToken)?;
+                let name = Self::get_optimized_pointer(values_map, name);
+                Token::ContextAttribute(ContextAttribute(Identifier(name)))
+            }
+            Rule::assertion_operator => Token::Operator(Operator::Assertion),
+            Rule::negation_operator => Token::Operator(Operator::Negation),
+            Rule::search_operator => Token::Operator(Operator::Search),
+            Rule::match_operator => Token::Operator(Operator::Match),
+            Rule::context_time => Token::TimeAttribute(TimeAttribute::Time),
+            Rule::context_weekday => Token::TimeAttribute(TimeAttribute::Weekday),
+            Rule::context_hour => Token::TimeAttribute(TimeAttribute::Hour),
+            Rule::weekday => Token::Weekday(Weekday::from_str(pair.as_str())?),
+            Rule::hour => {
+                let hour = u8::from_str(pair.as_str())
+                    .ok()
+                    .filter(|hour| *hour <= 24)
+                    .ok_or(Error::InvalidToken {
+                        expected: "Hour",
+                        found: pair.as_str().to_string(),
+                    })?;
+                Token::Hour(hour)
+            }
+            Rule::before_operator => Token::Operator(Operator::Before),
+            Rule::after_operator => Token::Operator(Operator::After),
+            Rule::in_operator => Token::Operator(Operator::In),
+            Rule::less_than_operator => Token::Operator(Operator::LessThan),
+            Rule::less_or_equal_operator => Token::Operator(Operator::LessOrEqual),
+            Rule::greater_than_operator => Token::Operator(Operator::GreaterThan),
+            Rule::greater_or_equal_operator => Token::Operator(Operator::GreaterOrEqual),
+            Rule::number => {
+                let number = Number::parse(pair.as_str()).ok_or(Error::InvalidToken {
+                    expected: "Number",
+                    found: pair.as_str().to_string(),
+                })?;
+                Token::Number(number)
+            }
+            Rule::identifier | Rule::wildcard => {
+                let val = Self::get_optimized_pointer(values_map, pair.as_str());
+                Token::Identifier(Identifier(val))
+            }
+            Rule::string => {
+                let value = Self::extract_next_str(pair).ok_or(Error::MissingToken)?;
+                let val = Self::get_optimized_pointer(values_map, value);
+                Token::String(val)
+            }
+            Rule::attribute_name
+            | Rule::custom_attribute
+            | Rule::comparison_operator
+            | Rule::condition_block
+            | Rule::hierarchy
+            | Rule::schema_declaration
+            | Rule::attribute
+            | Rule::inner_string
+            | Rule::COMMENT
+            | Rule::char
+            | Rule::WHITESPACE
+            | Rule::EOI => Token::Null,
+        };
+
+        Ok(token)
+    }
+
+    pub fn parse_file_content(
+        content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Storage> {
+        let file_rules = Self::parse(Rule::file, content)
+            .map_err(|err| syntax_error(err, content))?
+            .next()
+            .unwrap();
+        let offsets: Vec<usize> = file_rules
+            .clone()
+            .into_inner()
+            .map(|p| p.as_span().start())
+            .collect();
+        let file_token = Self::parse_token(file_rules, values_map)?;
+        let storage =
+            Storage::from_file_token(file_token, |index, err| err.at(content, offsets[index]))?;
+
+        Ok(storage)
+    }
+}
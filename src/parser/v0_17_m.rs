@@ -0,0 +1,454 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use pest::{iterators::Pair, Parser};
+use pest_derive::Parser;
+
+use crate::{errors::syntax_error, language::storage::Storage, Error, MinosResult};
+
+use super::{
+    imports::Imports,
+    macros::{add_imported_macros, check_no_parameters, check_requirement, Macro, Macros},
+};
+
+use super::tokens::{
+    ActorAttribute, Array, ContextAttribute, FileVersion, Identifier, Number, Operator, ParameterKind,
+    ResourceAttribute, TimeAttribute, Token, Weekday,
+};
+
+#[derive(Debug, Parser)]
+#[grammar = "../assets/minos-v0_17M.pest"]
+pub(crate) struct MinosParserV0_17M;
+
+impl MinosParserV0_17M {
+    fn parse_tokens(
+        pair: Pair<Rule>,
+        macro_tokens: &mut Macros,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Vec<Token>> {
+        pair.into_inner()
+            .map(|p| Self::parse_token(p, macro_tokens, values_map))
+            .collect()
+    }
+
+    fn get_optimized_pointer(values_map: &mut HashMap<String, Arc<str>>, value: &str) -> Arc<str> {
+        match values_map.get(value) {
+            Some(val) => val.clone(),
+            None => {
+                let arc: Arc<str> = Arc::from(value);
+                values_map.insert(value.to_string(), arc.clone());
+
+                arc
+            }
+        }
+    }
+
+    fn extract_next_str(pair: Pair<'_, Rule>) -> Option<&str> {
+        pair.into_inner().next().map(|inner_pair| inner_pair.as_str())
+    }
+
+    fn extract_next_identifier(
+        pair: Pair<Rule>,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> Option<Identifier> {
+        pair.into_inner().next().map(|rule| {
+            let arc_val = Self::get_optimized_pointer(values_map, rule.as_str());
+            Identifier(arc_val)
+        })
+    }
+
+    fn parse_array(
+        pair: Pair<Rule>,
+        macro_tokens: &mut Macros,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Vec<Arc<str>>> {
+        let mut permissions = vec![];
+        for pair in pair.into_inner() {
+            let (input, offset) = (pair.get_input(), pair.as_span().start());
+            let parsed_token = Self::parse_token(pair, macro_tokens, values_map)?;
+            match parsed_token {
+                Token::String(permission) => permissions.push(permission),
+                Token::MacroCall(tokens) => {
+                    for token in tokens {
+                        match token {
+                            Token::String(permission) => permissions.push(permission),
+                            // located at the call, instead of the array
+                            token => Err(Error::InvalidToken {
+                                expected: "String",
+                                found: token.to_string(),
+                            }
+                            .at(input, offset))?,
+                        }
+                    }
+                }
+                _ => Err(Error::InvalidToken {
+                    expected: "String",
+                    found: parsed_token.to_string(),
+                })?,
+            }
+        }
+
+        Ok(permissions)
+    }
+
+    fn extract_macro_tokens(
+        pair: Pair<Rule>,
+        macro_tokens: &mut Macros,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<()> {
+        let mut inner_pairs = pair.into_inner().peekable();
+        let ident_pair = inner_pairs.next().ok_or(Error::MissingToken)?;
+        let ident = Identifier(Self::get_optimized_pointer(values_map, ident_pair.as_str()));
+        let parameters = match inner_pairs.next_if(|p| p.as_rule() == Rule::macro_parameters) {
+            Some(parameters) => parameters
+                .into_inner()
+                .map(|p| Identifier(Self::get_optimized_pointer(values_map, p.as_str())))
+                .collect(),
+            None => vec![],
+        };
+        let inner_tokens = inner_pairs
+            .map(|p| Self::parse_token(p, macro_tokens, values_map))
+            .collect::<MinosResult<Vec<Token>>>()?;
+        macro_tokens.insert(ident.clone(), Macro::new(&ident, parameters, inner_tokens)?);
+
+        Ok(())
+    }
+
+    fn extract_requirements(
+        pair: Pair<Rule>,
+        macro_tokens: &mut Macros,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Vec<Token>> {
+        let mut requirements = vec![];
+        for p in pair.into_inner() {
+            let parsed_token = Self::parse_token(p, macro_tokens, values_map)?;
+            match parsed_token {
+                Token::MacroCall(mut tokens) => requirements.append(&mut tokens),
+                Token::Requirement(_) | Token::AnyBlock(_) | Token::AllBlock(_) | Token::NotBlock(_) => {
+                    requirements.push(parsed_token)
+                }
+                _ => Err(Error::InvalidToken {
+                    expected: "MacroCall, Requirement or condition block",
+                    found: parsed_token.to_string(),
+                })?,
+            }
+        }
+
+        Ok(requirements)
+    }
+
+    /// Builds the token of the pair. The errors are located at the start of the pair.
+    pub(crate) fn parse_token(
+        pair: Pair<Rule>,
+        macro_tokens: &mut Macros,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Token> {
+        let (input, offset) = (pair.get_input(), pair.as_span().start());
+        Self::build_token(pair, macro_tokens, values_map).map_err(|err| err.at(input, offset))
+    }
+
+    fn build_token(
+        pair: Pair<Rule>,
+        macro_tokens: &mut Macros,
+        values_map: &mut HashMap<String, Arc<str>>,
+    ) -> MinosResult<Token> {
+        let token = match pair.as_rule() {
+            Rule::file | Rule::macros_file => {
+                Token::File(Self::parse_tokens(pair, macro_tokens, values_map)?)
+            }
+            Rule::version => Token::Version(FileVersion::from_str(pair.as_str())?),
+            Rule::macro_definition => {
+                Self::extract_macro_tokens(pair, macro_tokens, values_map)?;
+                Token::MacroDefinition
+            }
+            Rule::macro_call => {
+                let mut inner_pairs = pair.into_inner();
+                let ident_pair = inner_pairs.next().ok_or(Error::MissingToken)?;
+                let macro_ident =
+                    Identifier(Self::get_optimized_pointer(values_map, ident_pair.as_str()));
+                let arguments = match inner_pairs.next() {
+                    Some(arguments) => Self::parse_tokens(arguments, macro_tokens, values_map)?,
+                    None => vec![],
+                };
+                let macro_definition = macro_tokens
+                    .get(&macro_ident)
+                    .ok_or(Error::MacroNotExist(macro_ident.0.to_string()))?;
+                Token::MacroCall(macro_definition.expand(&macro_ident, arguments)?)
+            }
+            Rule::macro_parameter
+            | Rule::text_parameter
+            | Rule::identifier_parameter
+            | Rule::number_parameter => {
+                let kind = match pair.as_rule() {
+                    Rule::text_parameter => ParameterKind::String,
+                    Rule::identifier_parameter => ParameterKind::Identifier,
+                    Rule::number_parameter => ParameterKind::Number,
+                    _ => ParameterKind::Any,
+                };
+                let parameter =
+                    Self::extract_next_identifier(pair, values_map).ok_or(Error::MissingToken)?;
+                Token::MacroParameter(parameter, kind)
+            }
+            Rule::resource => {
+                let token = Token::Resource(Self::parse_tokens(pair, macro_tokens, values_map)?);
+                check_no_parameters(&token)?;
+                token
+            }
+            Rule::attributed_resource => {
+                let token =
+                    Token::AttributedResource(Self::parse_tokens(pair, macro_tokens, values_map)?);
+                check_no_parameters(&token)?;
+                token
+            }
+            Rule::extends => {
+                let parent = Self::extract_next_str(pair).ok_or(Error::MissingToken)?;
+                Token::Extends(Identifier(Self::get_optimized_pointer(values_map, parent)))
+            }
+            Rule::named_env => {
+                let position = Token::position_of(&pair);
+                let mut tokens = Self::parse_tokens(pair, macro_tokens, values_map)?;
+                tokens.push(position);
+                Token::NamedEnv(tokens)
+            }
+            Rule::default_env => Token::DefaultEnv(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::implicit_default_env => {
+                Token::ImplicitDefaultEnv(Self::parse_tokens(pair, macro_tokens, values_map)?)
+            }
+            Rule::roles_hierarchy => {
+                Token::RolesHierarchy(Self::parse_tokens(pair, macro_tokens, values_map)?)
+            }
+            Rule::groups_hierarchy => {
+                Token::GroupsHierarchy(Self::parse_tokens(pair, macro_tokens, values_map)?)
+            }
+            Rule::hierarchy_chain => {
+                Token::HierarchyChain(Self::parse_tokens(pair, macro_tokens, values_map)?)
+            }
+            Rule::schema => Token::Schema(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::actor_types => Token::ActorTypes(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::resource_types => {
+                Token::ResourceTypes(Self::parse_tokens(pair, macro_tokens, values_map)?)
+            }
+            Rule::statuses => Token::Statuses(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::schema_permissions => {
+                Token::SchemaPermissions(Self::parse_tokens(pair, macro_tokens, values_map)?)
+            }
+            Rule::policy => {
+                let position = Token::position_of(&pair);
+                let mut tokens = Self::parse_tokens(pair, macro_tokens, values_map)?;
+                tokens.push(position);
+                Token::Policy(tokens)
+            }
+            Rule::allow => Token::Allow(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::deny => Token::Deny(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::rule => {
+                let position = Token::position_of(&pair);
+                let mut requirements = Self::extract_requirements(pair, macro_tokens, values_map)?;
+                requirements.push(position);
+
+                Token::Rule(requirements)
+            }
+            Rule::any_block => {
+                Token::AnyBlock(Self::extract_requirements(pair, macro_tokens, values_map)?)
+            }
+            Rule::all_block => {
+                Token::AllBlock(Self::extract_requirements(pair, macro_tokens, values_map)?)
+            }
+            Rule::not_block => {
+                Token::NotBlock(Self::extract_requirements(pair, macro_tokens, values_map)?)
+            }
+            Rule::array => {
+                let permissions = Self::parse_array(pair, macro_tokens, values_map)?;
+                Token::Array(Array(permissions))
+            }
+            Rule::requirement => {
+                let token = Token::Requirement(Self::parse_tokens(pair, macro_tokens, values_map)?);
+                check_requirement(&token)?;
+                token
+            }
+            Rule::assertion => Token::Assertion(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::negation => Token::Negation(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::search => Token::Search(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::comparison => Token::Comparison(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::pattern_match => Token::Match(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::temporal => Token::Temporal(Self::parse_tokens(pair, macro_tokens, values_map)?),
+            Rule::glob_pattern => {
+                let string = pair.into_inner().next().ok_or(Error::MissingToken)?;
+                let pattern = Self::extract_next_str(string).ok_or(Error::MissingToken)?;
+                Token::GlobPattern(Self::get_optimized_pointer(values_map, pattern))
+            }
+            Rule::regex_pattern => {
+                let string = pair.into_inner().next().ok_or(Error::MissingToken)?;
+                let pattern = Self::extract_next_str(string).ok_or(Error::MissingToken)?;
+                Token::RegexPattern(Self::get_optimized_pointer(values_map, pattern))
+            }
+            Rule::actor_id => Token::ActorAttribute(ActorAttribute::Id),
+            Rule::actor_type => Token::ActorAttribute(ActorAttribute::Type),
+            Rule::actor_groups => Token::ActorAttribute(ActorAttribute::Groups),
+            Rule::actor_roles => Token::ActorAttribute(ActorAttribute::Roles),
+            Rule::actor_status => Token::ActorAttribute(ActorAttribute::Status),
+            Rule::actor_attrs => {
+                let name = Self::extract_next_str(pair).ok_or(Error::MissingToken)?;
+                let name = Self::get_optimized_pointer(values_map, name);
+                Token::ActorAttribute(ActorAttribute::Attrs(Identifier(name)))
+            }
+            Rule::resource_id => Token::ResourceAttribute(ResourceAttribute::Id),
+            Rule::resource_type => Token::ResourceAttribute(ResourceAttribute::Type),
+            Rule::resource_owner => Token::ResourceAttribute(ResourceAttribute::Owner),
+            Rule::resource_status => Token::ResourceAttribute(ResourceAttribute::Status),
+            Rule::resource_attrs => {
+                let name = Self::extract_next_str(pair).ok_or(Error::MissingToken)?;
+                let name = Self::get_optimized_pointer(values_map, name);
+                Token::ResourceAttribute(ResourceAttribute::Attrs(Identifier(name)))
+            }
+            Rule::context_attr => {
+                let name = Self::extract_next_str(pair).ok_or(Error::MissingToken)?;
+                let name = Self::get_optimized_pointer(values_map, name);
+                Token::ContextAttribute(ContextAttribute(Identifier(name)))
+            }
+            Rule::assertion_operator => Token::Operator(Operator::Assertion),
+            Rule::negation_operator => Token::Operator(Operator::Negation),
+            Rule::search_operator => Token::Operator(Operator::Search),
+            Rule::match_operator => Token::Operator(Operator::Match),
+            Rule::context_time => Token::TimeAttribute(TimeAttribute::Time),
+            Rule::context_weekday => Token::TimeAttribute(TimeAttribute::Weekday),
+            Rule::context_hour => Token::TimeAttribute(TimeAttribute::Hour),
+            Rule::weekday => Token::Weekday(Weekday::from_str(pair.as_str())?),
+            Rule::hour => {
+                let hour = u8::from_str(pair.as_str())
+                    .ok()
+                    .filter(|hour| *hour <= 24)
+                    .ok_or(Error::InvalidToken {
+                        expected: "Hour",
+                        found: pair.as_str().to_string(),
+                    })?;
+                Token::Hour(hour)
+            }
+            Rule::before_operator => Token::Operator(Operator::Before),
+            Rule::after_operator => Token::Operator(Operator::After),
+            Rule::in_operator => Token::Operator(Operator::In),
+            Rule::less_than_operator => Token::Operator(Operator::LessThan),
+            Rule::less_or_equal_operator => Token::Operator(Operator::LessOrEqual),
+            Rule::greater_than_operator => Token::Operator(Operator::GreaterThan),
+            Rule::greater_or_equal_operator => Token::Operator(Operator::GreaterOrEqual),
+            Rule::number => {
+                let number = Number::parse(pair.as_str()).ok_or(Error::InvalidToken {
+                    expected: "Number",
+                    found: pair.as_str().to_string(),
+                })?;
+                Token::Number(number)
+            }
+            Rule::identifier | Rule::wildcard => {
+                let arc_val = Self::get_optimized_pointer(values_map, pair.as_str());
+                Token::Identifier(Identifier(arc_val))
+            }
+            Rule::string => {
+                let inner_str = Self::extract_next_str(pair).ok_or(Error::MissingToken)?;
+                let arc_val = Self::get_optimized_pointer(values_map, inner_str);
+                Token::String(arc_val)
+            }
+            // the imports are resolved before building the file's token, and the macro
+            // parameters and arguments are read with the macro definitions and calls
+            Rule::import_directive
+            | Rule::macro_parameters
+            | Rule::macro_arguments
+            | Rule::macro_argument
+            | Rule::text_value
+            | Rule::identifier_value
+            | Rule::attribute_name
+            | Rule::custom_attribute
+            | Rule::comparison_operator
+            | Rule::condition_block
+            | Rule::hierarchy
+            | Rule::schema_declaration
+            | Rule::attribute
+            | Rule::inner_string
+            | Rule::COMMENT
+            | Rule::char
+            | Rule::WHITESPACE
+            | Rule::file_content
+            | Rule::EOI => Token::Null,
+        };
+
+        Ok(token)
+    }
+
+    pub(crate) fn parse_file_content(
+        content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<Storage> {
+        Self::parse_storage(Rule::file, content, values_map, imports)
+    }
+
+    /// Like [`Self::parse_file_content`], but the file can be a library with only macros,
+    /// see [`Rule::macros_file`].
+    pub(crate) fn parse_library_content(
+        content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<Storage> {
+        Self::parse_storage(Rule::macros_file, content, values_map, imports)
+    }
+
+    fn parse_storage(
+        file_rule: Rule,
+        content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<Storage> {
+        let (file_token, offsets, _) = Self::parse_file_token(file_rule, content, values_map, imports)?;
+        let storage =
+            Storage::from_file_token(file_token, |index, err| err.at(content, offsets[index]))?;
+
+        Ok(storage)
+    }
+
+    /// Returns the macros defined and imported by the file, which doesn't need resources.
+    pub(crate) fn parse_macros(
+        content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<Macros> {
+        let (_, _, macro_tokens) =
+            Self::parse_file_token(Rule::macros_file, content, values_map, imports)?;
+
+        Ok(macro_tokens)
+    }
+
+    /// Returns the token of the file parsed with `file_rule`, [`Rule::file`] or [`Rule::macros_file`],
+    /// the offsets of its inner tokens and the macros. The imported
+    /// macros are loaded before the file's macros, which can redefine them.
+    fn parse_file_token(
+        file_rule: Rule,
+        content: &str,
+        values_map: &mut HashMap<String, Arc<str>>,
+        imports: &mut Imports,
+    ) -> MinosResult<(Token, Vec<usize>, Macros)> {
+        let file_rules = Self::parse(file_rule, content)
+            .map_err(|err| syntax_error(err, content))?
+            .next()
+            .unwrap();
+        let offsets: Vec<usize> = file_rules
+            .clone()
+            .into_inner()
+            .map(|p| p.as_span().start())
+            .collect();
+
+        let mut macro_tokens: Macros = HashMap::new();
+        let import_directives = file_rules
+            .clone()
+            .into_inner()
+            .filter(|pair| pair.as_rule() == Rule::import_directive);
+        for pair in import_directives {
+            let offset = pair.as_span().start();
+            let string = pair.into_inner().next().ok_or(Error::MissingToken)?;
+            let path = Self::extract_next_str(string).ok_or(Error::MissingToken)?;
+            imports
+                .import(path, values_map)
+                .and_then(|macros| add_imported_macros(&mut macro_tokens, macros))
+                .map_err(|err| err.at(content, offset))?;
+        }
+        let file_token = Self::parse_token(file_rules, &mut macro_tokens, values_map)?;
+
+        Ok((file_token, offsets, macro_tokens))
+    }
+}
@@ -0,0 +1,206 @@
+//! Conversion of the [Condition] over the resource attributes, returned by
+//! [`Engine::resource_condition`](crate::Engine::resource_condition), into a parameterised
+//! predicate for the `WHERE` clause of a SQL query.
+
+use std::sync::Arc;
+
+use getset::Getters;
+
+use crate::{
+    errors::{Error, MinosResult},
+    language::{
+        condition::Condition,
+        requirements::{Attribute, ComparableValue, Requirement, Value},
+    },
+    parser::tokens::{Number, ResourceAttribute},
+    text_repr::to_text_repr::ToTextRepr,
+};
+
+/// Maps the resource attributes to the columns of the table. The columns are written
+/// in the SQL as they are returned, so a name that isn't a plain identifier must be
+/// quoted, see [quoted_identifier].
+pub trait ColumnMapping {
+    /// Returns `None` if the attribute isn't stored in the table.
+    fn column(&self, attribute: &ResourceAttribute) -> Option<String>;
+}
+
+impl<F> ColumnMapping for F
+where
+    F: Fn(&ResourceAttribute) -> Option<String>,
+{
+    fn column(&self, attribute: &ResourceAttribute) -> Option<String> {
+        self(attribute)
+    }
+}
+
+/// Maps `resource.id`, `resource.type`, `resource.owner` and `resource.status` to the
+/// columns `id`, `type`, `owner` and `status`, and `resource.attrs.<name>` to the quoted
+/// column `"<name>"`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultColumns;
+
+impl ColumnMapping for DefaultColumns {
+    fn column(&self, attribute: &ResourceAttribute) -> Option<String> {
+        let column = match attribute {
+            ResourceAttribute::Id => "id",
+            ResourceAttribute::Type => "type",
+            ResourceAttribute::Owner => "owner",
+            ResourceAttribute::Status => "status",
+            ResourceAttribute::Attrs(name) => return Some(quoted_identifier(&name.0)),
+        };
+
+        Some(column.to_string())
+    }
+}
+
+/// Writes the name as a SQL identifier between double quotes, doubling the embedded quotes,
+/// so it can't be a keyword nor change the statement.
+pub fn quoted_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Text(Arc<str>),
+    Integer(i64),
+    Real(f64),
+}
+
+/// Predicate with `?` placeholders, bound in order to the [params](SqlPredicate::params).
+///
+/// A missing attribute is stored as `NULL`: like in the engine, the equalities and the
+/// comparisons aren't satisfied, and the negations are satisfied unless the attribute is custom.
+/// [`Condition::Missing`] is written as `IS NULL`.
+#[derive(Debug, Clone, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct SqlPredicate {
+    sql: String,
+    params: Vec<SqlValue>,
+}
+
+impl SqlPredicate {
+    /// Fails with [`Error::UnsupportedSqlRequirement`] if the condition contains requirements
+    /// over the actor or the context, searches into resource lists or pattern matches,
+    /// and with [`Error::MissingColumn`] if some attribute has no column.
+    pub fn from_condition(condition: &Condition, columns: &impl ColumnMapping) -> MinosResult<Self> {
+        let mut writer = SqlWriter {
+            columns,
+            params: vec![],
+        };
+        let sql = writer.condition_sql(condition)?;
+
+        Ok(Self {
+            sql,
+            params: writer.params,
+        })
+    }
+}
+
+struct SqlWriter<'c, C> {
+    columns: &'c C,
+    params: Vec<SqlValue>,
+}
+
+impl<C: ColumnMapping> SqlWriter<'_, C> {
+    fn condition_sql(&mut self, condition: &Condition) -> MinosResult<String> {
+        let sql = match condition {
+            Condition::True => "1 = 1".to_string(),
+            Condition::False => "1 = 0".to_string(),
+            Condition::Requirement(requirement) => self.requirement_sql(requirement)?,
+            Condition::Missing(attribute) => {
+                let unsupported = || Error::UnsupportedSqlRequirement(condition.to_text_repr());
+                format!("{} IS NULL", self.column(attribute, unsupported)?)
+            }
+            Condition::And(conditions) => format!("({})", self.conditions_sql(conditions, " AND ")?),
+            Condition::Or(conditions) => format!("({})", self.conditions_sql(conditions, " OR ")?),
+            Condition::Not(condition) => format!("NOT {}", self.condition_sql(condition)?),
+        };
+
+        Ok(sql)
+    }
+
+    fn conditions_sql(&mut self, conditions: &[Condition], separator: &str) -> MinosResult<String> {
+        let conditions = conditions
+            .iter()
+            .map(|condition| self.condition_sql(condition))
+            .collect::<MinosResult<Vec<String>>>()?;
+
+        Ok(conditions.join(separator))
+    }
+
+    fn requirement_sql(&mut self, requirement: &Requirement) -> MinosResult<String> {
+        let (left, operator, right, missing_satisfies) = match requirement {
+            Requirement::Assertion(assertion) => {
+                (assertion.left(), "=".to_string(), assertion.right(), false)
+            }
+            Requirement::Negation(negation) => (
+                negation.left(),
+                "<>".to_string(),
+                negation.right(),
+                !negation.left().is_custom() && !negation.right().is_custom_attribute(),
+            ),
+            Requirement::Comparison(comparison) => (
+                comparison.left(),
+                comparison.operator().to_string(),
+                comparison.right(),
+                false,
+            ),
+            _ => return Err(unsupported(requirement)),
+        };
+
+        let left = self.column(left, || unsupported(requirement))?;
+        let mut columns = vec![left.clone()];
+        let right = match right {
+            ComparableValue::Attribute(attribute) => {
+                let column = self.column(attribute, || unsupported(requirement))?;
+                columns.push(column.clone());
+                column
+            }
+            ComparableValue::Value(value) => {
+                let param = sql_value(value).ok_or_else(|| unsupported(requirement))?;
+                self.params.push(param);
+                "?".to_string()
+            }
+        };
+
+        let sql = match missing_satisfies {
+            true => format!("({left} IS NULL OR {left} {operator} {right})"),
+            false => {
+                let present: Vec<String> = columns
+                    .iter()
+                    .map(|column| format!("{column} IS NOT NULL"))
+                    .collect();
+                format!("({} AND {left} {operator} {right})", present.join(" AND "))
+            }
+        };
+
+        Ok(sql)
+    }
+
+    /// Fails with the `unsupported` error if the attribute isn't of the resource.
+    fn column(&self, attribute: &Attribute, unsupported: impl FnOnce() -> Error) -> MinosResult<String> {
+        let Attribute::Resource(attribute) = attribute else {
+            return Err(unsupported());
+        };
+
+        self.columns
+            .column(attribute)
+            .ok_or_else(|| Error::MissingColumn(attribute.to_string()))
+    }
+}
+
+fn sql_value(value: &Value) -> Option<SqlValue> {
+    let value = match value {
+        Value::String(text) => SqlValue::Text(text.clone()),
+        Value::Identifier(identifier) => SqlValue::Text(identifier.0.clone()),
+        Value::Number(Number::Integer(number)) => SqlValue::Integer(*number),
+        Value::Number(Number::Decimal(number)) => SqlValue::Real(*number),
+        Value::Array(_) => return None,
+    };
+
+    Some(value)
+}
+
+fn unsupported(requirement: &Requirement) -> Error {
+    Error::UnsupportedSqlRequirement(Condition::Requirement(requirement.clone()).to_text_repr())
+}
@@ -3,3 +3,6 @@ mod v0_16;
 
 #[cfg(test)]
 mod v0_16_m;
+
+#[cfg(test)]
+mod v0_17;
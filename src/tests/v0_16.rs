@@ -66,7 +66,7 @@ fn build_container_works() -> MinosResult<()> {
     path.push("assets");
     let container = Container::new("1".to_string(), "Test Container".to_string(), vec![path]).load()?;
 
-    assert!(container.storage().resources().len() > 0);
+    assert!(!container.storage().resources().is_empty());
 
     Ok(())
 }
@@ -105,18 +105,21 @@ fn simple_authorize_works() -> MinosResult<()> {
         groups: vec![],
         roles: vec![],
         status: None,
+        ..Default::default()
     };
     let resource = Resource {
         id: Some("Example.user.id".into()),
         type_: "User".into(),
         owner: None,
         status: None,
+        ..Default::default()
     };
     let engine = Engine::new(&storage);
     let permissions = engine.authorize(AuthorizeRequest {
         env_name: None,
         resource: &resource,
         actor: &user,
+        context: None,
     })?;
 
     assert_eq!(
@@ -141,12 +144,14 @@ fn simple_find_permission_works() {
         groups: vec![],
         roles: vec![],
         status: None,
+        ..Default::default()
     };
     let resource = Resource {
         id: Some("Example.user.id".into()),
         type_: "User".into(),
         owner: None,
         status: None,
+        ..Default::default()
     };
     let engine = Engine::new(&storage);
     let result = engine.actor_has_permission(FindPermissionRequest {
@@ -154,6 +159,7 @@ fn simple_find_permission_works() {
         resource: &resource,
         actor: &user,
         permission: SimplePermissions::Create.to_string(),
+        context: None,
     });
 
     assert!(result.is_ok());
@@ -168,12 +174,14 @@ fn simple_find_permissions_works() {
         groups: vec![],
         roles: vec![],
         status: None,
+        ..Default::default()
     };
     let resource = Resource {
         id: Some("Example.user.id".into()),
         type_: "User".into(),
         owner: None,
         status: None,
+        ..Default::default()
     };
     let engine = Engine::new(&storage);
     let result = engine.actor_has_permissions(FindPermissionsRequest {
@@ -184,11 +192,66 @@ fn simple_find_permissions_works() {
             SimplePermissions::Create.to_string(),
             SimplePermissions::Read.to_string(),
         ],
+        context: None,
     });
 
     assert!(result.is_ok());
 }
 
+const LITERAL_PERMISSIONS_CONTENT: &str = r#"syntax = 0.16;
+
+resource Document {
+    policy {
+        allow = ["document:*", "read"];
+        rule {
+            actor.type = User;
+        }
+    }
+}
+"#;
+
+/// The permission patterns are from the syntax 0.17, so `*` isn't a wildcard before it.
+#[test]
+fn permissions_are_literal_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(LITERAL_PERMISSIONS_CONTENT)?;
+    let user = Actor {
+        id: "Example.user.id".into(),
+        type_: "User".into(),
+        ..Default::default()
+    };
+    let resource = Resource {
+        id: Some("Example.document.id".into()),
+        type_: "Document".into(),
+        ..Default::default()
+    };
+    let engine = Engine::new(&storage);
+    let permissions = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        resource: &resource,
+        actor: &user,
+        context: None,
+    })?;
+
+    assert_eq!(permissions[0], "document:*");
+    assert_eq!(permissions.len(), permissions.iter().count());
+    assert!(permissions.has("document:*"));
+    assert!(!permissions.has("document:edit"));
+
+    let has_permission = |permission: &str| {
+        engine.actor_has_permission(FindPermissionRequest {
+            env_name: None,
+            resource: &resource,
+            actor: &user,
+            permission: permission.to_string(),
+            context: None,
+        })
+    };
+    assert_eq!(has_permission("document:*"), Ok(true));
+    assert_eq!(has_permission("document:edit"), Ok(false));
+
+    Ok(())
+}
+
 const ADVANCED_MINOS_V_0_16_TEXT: &str = include_str!("../../assets/simulation/simulation_v0_16.minos");
 
 static STORAGE_V_0_16: LazyLock<Storage> =
@@ -203,12 +266,14 @@ fn file_simulation_works() -> MinosResult<()> {
         groups: vec!["File".into()],
         roles: vec![],
         status: None,
+        ..Default::default()
     };
     let config_file = Resource {
         id: Some("app.conf".into()),
         type_: "File".into(),
         owner: Some("user1".into()),
         status: None,
+        ..Default::default()
     };
 
     let operation_result = ENGINE_V0_16.actor_has_permission(FindPermissionRequest {
@@ -216,6 +281,7 @@ fn file_simulation_works() -> MinosResult<()> {
         actor: &user2,
         resource: &config_file,
         permission: SimplePermissions::Read.to_string(),
+        context: None,
     });
     assert_eq!(operation_result, Ok(true));
 
@@ -225,12 +291,14 @@ fn file_simulation_works() -> MinosResult<()> {
         groups: vec![],
         roles: vec!["admin".into()],
         status: None,
+        ..Default::default()
     };
     let operation_result = ENGINE_V0_16.actor_has_permission(FindPermissionRequest {
         env_name: None,
         actor: &user1,
         resource: &config_file,
         permission: SimplePermissions::Delete.to_string(),
+        context: None,
     });
     assert_eq!(operation_result, Ok(true));
 
@@ -240,12 +308,14 @@ fn file_simulation_works() -> MinosResult<()> {
         groups: vec![],
         roles: vec!["guest".into()],
         status: None,
+        ..Default::default()
     };
     let operation_result = ENGINE_V0_16.actor_has_permission(FindPermissionRequest {
         env_name: None,
         actor: &guest_user,
         resource: &config_file,
         permission: SimplePermissions::Read.to_string(),
+        context: None,
     });
     assert_eq!(operation_result, Ok(false));
 
@@ -254,6 +324,7 @@ fn file_simulation_works() -> MinosResult<()> {
         actor: &guest_user,
         resource: &config_file,
         permission: SimplePermissions::Delete.to_string(),
+        context: None,
     });
     assert_eq!(operation_result, Ok(true));
 
@@ -261,6 +332,7 @@ fn file_simulation_works() -> MinosResult<()> {
         env_name: Some("TEST"),
         actor: &guest_user,
         resource: &config_file,
+        context: None,
     })?;
     assert_eq!(permissions.len(), 4);
 
@@ -294,6 +366,7 @@ impl AsActor for User {
             groups: vec![],
             roles: self.roles.clone(),
             status: None,
+            ..Default::default()
         }
     }
 }
@@ -316,6 +389,7 @@ impl TryIntoActor for SuperUser {
             groups: vec![],
             roles: vec![],
             status: None,
+            ..Default::default()
         })
     }
 }
@@ -335,6 +409,7 @@ impl Application {
             actor,
             resource: &self.as_resource(),
             permission: SimplePermissions::Install.to_string(),
+            context: None,
         })? {
             Err(anyhow!(
                 "the actor does not have the permission to install {}",
@@ -353,6 +428,7 @@ impl Application {
             actor,
             resource: &self.as_resource(),
             permission: SimplePermissions::Execute.to_string(),
+            context: None,
         })? {
             Err(anyhow!(
                 "the actor does not have permission to execute {}",
@@ -370,6 +446,7 @@ impl Application {
             actor,
             resource: &self.as_resource(),
             permission: SimplePermissions::Uninstall.to_string(),
+            context: None,
         })? {
             Err(anyhow!(
                 "the actor does not have permission to uninstall {}",
@@ -388,6 +465,7 @@ impl Application {
             actor,
             resource: &self.as_resource(),
             permission: SimplePermissions::Update.to_string(),
+            context: None,
         })? {
             Err(anyhow!(
                 "the actor does not have permission to update {}",
@@ -410,6 +488,7 @@ impl AsResource for Application {
                 true => Some("installed".into()),
                 false => Some("no-installed".into()),
             },
+            ..Default::default()
         }
     }
 }
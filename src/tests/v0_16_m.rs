@@ -1,11 +1,11 @@
-use std::sync::LazyLock;
+use std::{fs, sync::LazyLock};
 
 use anyhow::anyhow;
 
 use crate::{
-    engine::{AsActor, AsResource, FindPermissionRequest},
+    engine::{AsActor, AsResource, AuthorizeRequest, FindPermissionRequest},
     language::storage::Storage,
-    Actor, Engine, MinosParser,
+    Actor, Container, Engine, Error, MinosParser, Resource,
 };
 
 const ADVANCED_MINOS_FILE_CONTENT: &str =
@@ -31,6 +31,7 @@ impl<'a> AsActor for User<'a> {
             status: Some(self.status.into()),
             groups: vec![],
             roles: self.roles.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
         }
     }
 }
@@ -42,6 +43,7 @@ impl<'a> AsResource for User<'a> {
             type_: "User".into(),
             owner: None,
             status: Some(self.status.into()),
+            ..Default::default()
         }
     }
 }
@@ -62,7 +64,7 @@ impl<'a> User<'a> {
         let user = User {
             id: values.id,
             name: values.name,
-            status: "active".into(),
+            status: "active",
             roles: values.roles,
         };
 
@@ -71,6 +73,7 @@ impl<'a> User<'a> {
             actor,
             resource: &user.as_resource(),
             permission: "create".into(),
+            context: None,
         })? {
             Err(anyhow!("actor does not have create permission"))?
         }
@@ -84,11 +87,12 @@ impl<'a> User<'a> {
             actor,
             resource: &self.as_resource(),
             permission: "read_status".into(),
+            context: None,
         })? {
             Err(anyhow!("actor does not have read_status permission"))?
         }
 
-        Ok(&self.status)
+        Ok(self.status)
     }
 
     fn update_status(
@@ -103,6 +107,7 @@ impl<'a> User<'a> {
             actor,
             resource: &self.as_resource(),
             permission: "update_status".into(),
+            context: None,
         })? {
             Err(anyhow!("actor does not have update_status permission"))?
         }
@@ -118,6 +123,7 @@ impl<'a> User<'a> {
             actor,
             resource: &self.as_resource(),
             permission: "delete".into(),
+            context: None,
         })? {
             Err(anyhow!("actor does not have delete permission"))?
         }
@@ -130,12 +136,13 @@ impl<'a> User<'a> {
         Ok(())
     }
 
-    fn sudo(&self, engine: &Engine, env: &str) -> anyhow::Result<SuperUser> {
+    fn sudo(&self, engine: &Engine, env: &str) -> anyhow::Result<SuperUser<'_>> {
         if !engine.actor_has_permission(FindPermissionRequest {
             env_name: Some(env),
             actor: &self.as_actor(),
             resource: &self.as_resource(),
             permission: "sudo".into(),
+            context: None,
         })? {
             Err(anyhow!("actor cannot be superuser"))?
         }
@@ -156,6 +163,7 @@ impl<'a> AsActor for SuperUser<'a> {
             status: None,
             groups: vec![],
             roles: vec![],
+            ..Default::default()
         }
     }
 }
@@ -205,3 +213,321 @@ fn user_simulation_works() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+const IMPORTING_CONTENT: &str = r#"syntax = 0.16M;
+
+import "shared/roles.minos";
+import "shared/permissions.minos";
+
+resource Document {
+    policy {
+        allow = [#[READ_WRITE]];
+        rule {
+            #[IS_EDITOR]
+        }
+    }
+
+    policy {
+        allow = ["audit"];
+        rule {
+            #[IS_AUDITOR]
+        }
+    }
+}
+"#;
+
+#[test]
+fn imports_work() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("minos-imports-{}", std::process::id()));
+    fs::create_dir_all(dir.join("shared"))?;
+    fs::write(
+        dir.join("shared/roles.minos"),
+        "syntax = 0.17M;\n\n#IS_EDITOR {\n    actor.roles *= \"editor\";\n}\n",
+    )?;
+    // imports the roles again: the file is parsed once
+    fs::write(
+        dir.join("shared/permissions.minos"),
+        "syntax = 0.16M;\n\nimport \"roles.minos\";\n\n#READ_WRITE { \"read\", \"write\" }\n\n#IS_AUDITOR {\n    actor.roles *= \"auditor\";\n}\n",
+    )?;
+    fs::write(dir.join("documents.minos"), IMPORTING_CONTENT)?;
+
+    // the shared files only have macros, so they aren't in the paths of the container
+    let paths = vec![dir.join("documents.minos")];
+    let container = Container::new("1".to_string(), "Imports".to_string(), paths).load()?;
+    let engine = Engine::new(container.storage());
+    let editor = Actor {
+        roles: vec!["editor".into()],
+        ..Default::default()
+    };
+    let document = Resource {
+        type_: "Document".into(),
+        ..Default::default()
+    };
+    let permissions = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        actor: &editor,
+        resource: &document,
+        context: None,
+    })?;
+    assert!(permissions.has("read") && permissions.has("write") && !permissions.has("audit"));
+
+    let load = |content: &str| {
+        let file = dir.join("documents.minos");
+        fs::write(&file, content)?;
+        Container::new("1".to_string(), "Imports".to_string(), vec![file]).load()
+    };
+    let shared = fs::canonicalize(dir.join("shared"))?;
+
+    fs::write(
+        dir.join("shared/roles.minos"),
+        "syntax = 0.17M;\n\nimport \"permissions.minos\";\n\n#IS_EDITOR {\n    actor.roles *= \"editor\";\n}\n",
+    )?;
+    let Err(Error::Located(located)) = load(IMPORTING_CONTENT) else {
+        panic!("expected located error");
+    };
+    assert_eq!(
+        located.to_string(),
+        format!(
+            "{}:3:1: error in the imported file 'shared/roles.minos': {}:3:1: error in the imported file 'permissions.minos': {}:3:1: cycle found in the imports: roles.minos imports permissions.minos imports roles.minos",
+            dir.join("documents.minos").display(),
+            shared.join("roles.minos").display(),
+            shared.join("permissions.minos").display(),
+        )
+    );
+
+    let content = IMPORTING_CONTENT.replace("shared/roles.minos", "shared/missing.minos");
+    let Err(Error::Located(located)) = load(&content) else {
+        panic!("expected located error");
+    };
+    let position = located.position().as_ref().unwrap();
+    assert_eq!((*position.line(), *position.column()), (3, 1));
+    assert!(matches!(
+        located.error(),
+        Error::Import { path, .. } if path == "shared/missing.minos"
+    ));
+
+    // the same definition is imported through several files, but not another one
+    fs::write(
+        dir.join("shared/roles.minos"),
+        "syntax = 0.17M;\n\n#IS_EDITOR {\n    actor.roles *= \"editor\";\n}\n",
+    )?;
+    fs::write(
+        dir.join("shared/auditors.minos"),
+        "syntax = 0.17M;\n\n#IS_AUDITOR {\n    actor.roles *= \"admin\";\n}\n",
+    )?;
+    let content = IMPORTING_CONTENT.replace(
+        "import \"shared/permissions.minos\";",
+        "import \"shared/permissions.minos\";\nimport \"shared/auditors.minos\";",
+    );
+    let Err(Error::Located(located)) = load(&content) else {
+        panic!("expected located error");
+    };
+    let position = located.position().as_ref().unwrap();
+    assert_eq!((*position.line(), *position.column()), (5, 1));
+    assert_eq!(located.error(), &Error::DuplicatedMacro("IS_AUDITOR".to_string()));
+
+    // without imports, the files need macros and resources
+    for syntax in ["0.16M", "0.17M"] {
+        let content =
+            format!("syntax = {syntax};\n\n#IS_EDITOR {{\n    actor.roles *= \"editor\";\n}}\n");
+        assert!(MinosParser::easy_parse_str(&content).is_err());
+    }
+
+    fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+const AUTH_LIBRARY_CONTENT: &str = r#"syntax = 0.16M;
+
+#BY_ADMIN_AUTH {
+    actor.roles *= "admin";
+    actor.status = Active;
+}
+"#;
+
+const AUTH_IMPORTING_CONTENT: &str = r#"syntax = 0.16M;
+
+import "shared/auth.minos";
+
+resource Document {
+    policy {
+        allow = ["read"];
+        rule {
+            #[BY_ADMIN_AUTH]
+        }
+    }
+}
+"#;
+
+#[test]
+fn imported_library_in_dir_works() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("minos-library-{}", std::process::id()));
+    fs::create_dir_all(dir.join("shared"))?;
+    fs::write(dir.join("shared/auth.minos"), AUTH_LIBRARY_CONTENT)?;
+    fs::write(dir.join("documents.minos"), AUTH_IMPORTING_CONTENT)?;
+
+    let admin = Actor {
+        roles: vec!["admin".into()],
+        status: Some("Active".into()),
+        ..Default::default()
+    };
+    let document = Resource {
+        type_: "Document".into(),
+        ..Default::default()
+    };
+    let is_authorized = |storage: &Storage| {
+        Engine::new(storage).authorize(AuthorizeRequest {
+            env_name: None,
+            actor: &admin,
+            resource: &document,
+            context: None,
+        })
+    };
+    let container = || Container::new("1".to_string(), "Library".to_string(), vec![dir.clone()]);
+
+    let storage = container().load()?.storage().clone();
+    assert_eq!(storage.resources().len(), 1);
+    assert!(is_authorized(&storage)?.has("read"));
+
+    let Ok(container_with_report) = container().load_with_report() else {
+        panic!("expected no errors");
+    };
+    let storage = container_with_report.storage().clone();
+    assert!(is_authorized(&storage)?.has("read"));
+
+    let storage = container().load_reloadable()?.storage();
+    assert!(is_authorized(&storage)?.has("read"));
+
+    fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+const PARAMETERISED_MACROS_CONTENT: &str = r#"syntax = 0.16M;
+
+#HAS_ROLE(role) {
+    actor.roles *= $role;
+}
+
+#STATUS_AND_TYPE(status, type) {
+    actor.status = $status;
+    actor.type = $type;
+}
+
+#PERMISSIONS(extra) {
+    "read",
+    $extra
+}
+
+resource Invoice {
+    policy {
+        allow = [#[PERMISSIONS("pay")], "export"];
+        rule {
+            #[HAS_ROLE("billing")]
+            #[STATUS_AND_TYPE(Active, User)]
+        }
+    }
+}
+"#;
+
+#[test]
+fn parameterised_macros_work() -> anyhow::Result<()> {
+    let storage = MinosParser::easy_parse_str(PARAMETERISED_MACROS_CONTENT)?;
+    let expected = MinosParser::easy_parse_str(
+        r#"syntax = 0.16;
+
+resource Invoice {
+    policy {
+        allow = ["read", "pay", "export"];
+        rule {
+            actor.roles *= "billing";
+            actor.status = Active;
+            actor.type = User;
+        }
+    }
+}
+"#,
+    )?;
+    assert_eq!(storage.resources(), expected.resources());
+
+    let located_error = |from: &str, to: &str| {
+        let content = PARAMETERISED_MACROS_CONTENT.replacen(from, to, 1);
+        let Err(report) = MinosParser::parse_str_with_report(&content) else {
+            panic!("expected errors");
+        };
+        match &report.errors()[0] {
+            Error::Located(located) => {
+                let position = located.position().as_ref().unwrap();
+                ((*position.line(), *position.column()), located.error().clone())
+            }
+            err => panic!("expected located error, found {err:?}"),
+        }
+    };
+
+    assert_eq!(
+        located_error(
+            r#"#[HAS_ROLE("billing")]"#,
+            r#"#[HAS_ROLE("billing", "support")]"#
+        ),
+        (
+            (21, 13),
+            Error::MacroArity {
+                name: "HAS_ROLE".to_string(),
+                expected: 1,
+                found: 2,
+            }
+        )
+    );
+    assert_eq!(
+        located_error("actor.roles *= $role;", "actor.roles *= $rol;"),
+        (
+            (3, 1),
+            Error::MacroParameterNotExist {
+                name: "HAS_ROLE".to_string(),
+                parameter: "rol".to_string(),
+            }
+        )
+    );
+    assert_eq!(
+        located_error(r#"#[HAS_ROLE("billing")]"#, "actor.roles *= $role;"),
+        ((17, 1), Error::MacroParameterOutsideMacro("role".to_string()))
+    );
+    // the arguments are of the kind of the value that their parameter replaces
+    assert_eq!(
+        located_error("(Active, User)", r#"("Active", User)"#),
+        (
+            (22, 13),
+            Error::InvalidToken {
+                expected: "Identifier",
+                found: "String".to_string(),
+            }
+        )
+    );
+    // the numbers aren't arguments in 0.16M
+    let (_, error) = located_error(r#"#[HAS_ROLE("billing")]"#, "#[HAS_ROLE(3)]");
+    assert!(matches!(error, Error::RuleV0_16M(_)));
+    // only the macros of strings can be called in the arrays
+    assert_eq!(
+        located_error(r#"#[PERMISSIONS("pay")]"#, r#"#[HAS_ROLE("pay")]"#),
+        (
+            (19, 18),
+            Error::InvalidToken {
+                expected: "String",
+                found: "Requirement".to_string(),
+            }
+        )
+    );
+    assert_eq!(
+        located_error(r#"#[PERMISSIONS("pay")]"#, "#[PERMISSIONS(pay)]"),
+        (
+            (19, 18),
+            Error::InvalidToken {
+                expected: "String",
+                found: "Identifier".to_string(),
+            }
+        )
+    );
+
+    Ok(())
+}
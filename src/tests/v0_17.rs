@@ -0,0 +1,2993 @@
+use std::{
+    collections::HashMap,
+    fs,
+    sync::{Arc, LazyLock},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    engine::{
+        AttributedResourcesRequest, AuthorizeRequest, BatchAuthorizeRequest, BatchItem, Context,
+        Criteria, Decision, FilterResourcesRequest, FindPermissionRequest, RequirementTrace,
+        ResourceConditionRequest, SharedEngine, UnknownEnvironmentFallback, UnknownResourceFallback,
+        WhoCanAccessRequest,
+    },
+    language::{
+        environment::{Environment, DEFAULT_ENV_IDENTIFIER},
+        policy::{Effect, Permission, Policy},
+        requirements::{Assertion, Attribute, ComparableValue, Negation, Requirement},
+        rule::Rule,
+        storage::Storage,
+        time::Timestamp,
+    },
+    lint::{lint, LintCode, LintConfig, Severity},
+    parser::tokens::{ActorAttribute, Identifier, ResourceAttribute},
+    sql::{quoted_identifier, ColumnMapping, DefaultColumns, SqlPredicate, SqlValue},
+    text_repr::to_text_repr::ToTextRepr,
+    Actor, Container, Engine, Error, LocatedError, MinosParser, MinosResult, Resource,
+};
+
+const SIMULATION_V0_17M_CONTENT: &str = include_str!("../../assets/simulation/simulation_v0_17M.minos");
+
+static STORAGE: LazyLock<Storage> =
+    LazyLock::new(|| MinosParser::easy_parse_str(SIMULATION_V0_17M_CONTENT).unwrap());
+static ENGINE: LazyLock<Engine<'static>> = LazyLock::new(|| Engine::new(&STORAGE));
+
+fn user_actor(id: &str, status: &str, roles: Vec<String>) -> Actor {
+    Actor {
+        id: id.into(),
+        type_: "User".into(),
+        status: Some(status.into()),
+        groups: vec![],
+        roles,
+        attrs: HashMap::new(),
+    }
+}
+
+fn user_resource(id: &str, status: &str) -> Resource {
+    Resource {
+        id: Some(id.into()),
+        type_: "User".into(),
+        owner: None,
+        status: Some(status.into()),
+        attrs: HashMap::new(),
+    }
+}
+
+#[test]
+fn parse_deny_policy_works() {
+    let user = STORAGE.resources().get(&"User".into()).unwrap();
+    let default_env = user.default_environment().unwrap();
+    let policy = default_env.policies().first().unwrap();
+
+    assert_eq!(policy.effect(), &Effect::Deny);
+    assert_eq!(policy.permissions().len(), 4);
+}
+
+#[test]
+fn deny_policy_overrides_allow_policies() -> MinosResult<()> {
+    let admin = user_actor("1", "Active", vec!["admin".into()]);
+    let banned_admin = user_actor("2", "Banned", vec!["admin".into()]);
+    let resource = user_resource("3", "Active");
+
+    let permissions = ENGINE.authorize(AuthorizeRequest {
+        env_name: Some("STD"),
+        actor: &admin,
+        resource: &resource,
+        context: None,
+    })?;
+    assert_eq!(permissions.len(), 5);
+
+    let permissions = ENGINE.authorize(AuthorizeRequest {
+        env_name: Some("STD"),
+        actor: &banned_admin,
+        resource: &resource,
+        context: None,
+    })?;
+    assert_eq!(permissions.as_ref(), &["read_status".to_string()]);
+
+    let operation_result = ENGINE.actor_has_permission(FindPermissionRequest {
+        env_name: Some("STD"),
+        actor: &banned_admin,
+        resource: &resource,
+        permission: "delete".into(),
+        context: None,
+    });
+    assert_eq!(operation_result, Ok(false));
+
+    let banned_user = user_actor("4", "Banned", vec![]);
+    let operation_result = ENGINE.authorize(AuthorizeRequest {
+        env_name: Some("STD"),
+        actor: &banned_user,
+        resource: &user_resource("5", "Active"),
+        context: None,
+    });
+    assert_eq!(operation_result, Err(Error::ActorNotAuthorized("4".into())));
+
+    Ok(())
+}
+
+#[test]
+fn deny_policy_in_named_env_works() -> MinosResult<()> {
+    let super_user = Actor {
+        id: "1".into(),
+        type_: "SuperUser".into(),
+        status: None,
+        groups: vec![],
+        roles: vec![],
+        attrs: HashMap::new(),
+    };
+
+    let operation_result = ENGINE.actor_has_permission(FindPermissionRequest {
+        env_name: Some("ROOT"),
+        actor: &super_user,
+        resource: &user_resource("2", "Active"),
+        permission: "delete".into(),
+        context: None,
+    });
+    assert_eq!(operation_result, Ok(true));
+
+    let protected_user = user_resource("3", "Protected");
+    let operation_result = ENGINE.actor_has_permission(FindPermissionRequest {
+        env_name: Some("ROOT"),
+        actor: &super_user,
+        resource: &protected_user,
+        permission: "delete".into(),
+        context: None,
+    });
+    assert_eq!(operation_result, Ok(false));
+
+    let permissions = ENGINE.authorize(AuthorizeRequest {
+        env_name: Some("ROOT"),
+        actor: &super_user,
+        resource: &protected_user,
+        context: None,
+    })?;
+    assert!(!permissions.has("delete"));
+    assert!(permissions.has("sudo"));
+
+    Ok(())
+}
+
+const FORMATTED_MINOS_V0_17_CONTENT: &str = r#"syntax = 0.17;
+
+
+resource User {
+    env DEFAULT {
+        policy {
+            deny = ["delete"];
+
+            rule {
+                actor.status = Banned;
+            }
+        }
+    }
+}
+"#;
+
+#[test]
+fn deny_policy_text_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(FORMATTED_MINOS_V0_17_CONTENT)?;
+
+    assert_eq!(FORMATTED_MINOS_V0_17_CONTENT, &storage.to_text_repr());
+
+    Ok(())
+}
+
+#[test]
+fn explain_reports_granting_and_denying_rules() -> MinosResult<()> {
+    let banned_admin = user_actor("1", "Banned", vec!["admin".into()]);
+    let explanation = ENGINE.explain(AuthorizeRequest {
+        env_name: Some("STD"),
+        actor: &banned_admin,
+        resource: &user_resource("2", "Active"),
+        context: None,
+    })?;
+
+    assert!(explanation.is_authorized());
+    assert_eq!(explanation.environments().len(), 2);
+    assert_eq!(explanation.permissions().as_ref(), &["read_status".to_string()]);
+    assert_eq!(explanation.granting_rules("delete").len(), 1);
+    assert_eq!(explanation.denying_rules("delete").len(), 1);
+    assert!(explanation.denying_rules("read_status").is_empty());
+
+    Ok(())
+}
+
+const EXPLAINED_MINOS_V0_17_CONTENT: &str = r#"syntax = 0.17;
+
+resource File {
+    policy {
+        allow = ["read"];
+
+        rule {
+            actor.groups *= resource.owner;
+        }
+
+        rule {
+            actor.type = User;
+            actor.status != Banned;
+        }
+    }
+
+    policy {
+        deny = ["read"];
+
+        rule {
+            actor.status = Banned;
+        }
+    }
+}
+"#;
+
+const FILE_EXPLANATION: &str = r#"/* actor "1": granted ["read"] */
+resource File {
+    env DEFAULT {
+        policy { /* applied */
+            allow = ["read"];
+
+            rule { /* not satisfied */
+                actor.groups *= resource.owner; /* operation not permitted */
+            }
+
+            rule { /* satisfied */
+                actor.type = User; /* true */
+                actor.status != Banned; /* true */
+            }
+        }
+
+        policy { /* not applied */
+            deny = ["read"];
+
+            rule { /* not satisfied */
+                actor.status = Banned; /* false */
+            }
+        }
+    }
+}
+"#;
+
+#[test]
+fn explanation_text_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(EXPLAINED_MINOS_V0_17_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let actor = Actor {
+        id: "1".into(),
+        type_: "User".into(),
+        status: Some("Active".into()),
+        groups: vec![],
+        roles: vec![],
+        attrs: HashMap::new(),
+    };
+    let resource = Resource {
+        id: None,
+        type_: "File".into(),
+        owner: None,
+        status: None,
+        attrs: HashMap::new(),
+    };
+    let explanation = engine.explain(AuthorizeRequest {
+        env_name: None,
+        actor: &actor,
+        resource: &resource,
+        context: None,
+    })?;
+
+    assert_eq!(explanation.to_text_repr(), FILE_EXPLANATION);
+
+    Ok(())
+}
+
+const CUSTOM_ATTRIBUTES_CONTENT: &str = r#"syntax = 0.17;
+
+
+resource Document {
+    env DEFAULT {
+        policy {
+            allow = ["read"];
+
+            rule {
+                actor.attrs.tenant = resource.attrs.tenant;
+                actor.attrs.departments *= resource.attrs.department;
+            }
+        }
+
+        policy {
+            allow = ["delete"];
+
+            rule {
+                resource.attrs.classification != Secret;
+                actor.attrs.level = "manager";
+                actor.roles *= resource.attrs.department;
+            }
+        }
+    }
+}
+"#;
+
+#[test]
+fn custom_attributes_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(CUSTOM_ATTRIBUTES_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let actor = Actor {
+        id: "1".into(),
+        type_: "User".into(),
+        status: None,
+        groups: vec![],
+        roles: vec!["sales".into()],
+        attrs: HashMap::from([
+            ("tenant".into(), "tenant-1".into()),
+            ("level".into(), "manager".into()),
+            (
+                "departments".into(),
+                vec!["sales".to_string(), "support".to_string()].into(),
+            ),
+        ]),
+    };
+    let mut document = Resource {
+        id: Some("2".into()),
+        type_: "Document".into(),
+        owner: None,
+        status: None,
+        attrs: HashMap::from([
+            ("tenant".into(), "tenant-1".into()),
+            ("department".into(), "sales".into()),
+            ("classification".into(), "Public".into()),
+        ]),
+    };
+
+    let permissions = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        actor: &actor,
+        resource: &document,
+        context: None,
+    })?;
+    assert_eq!(permissions.as_ref(), &["read".to_string(), "delete".to_string()]);
+
+    document.attrs.insert("classification".into(), "Secret".into());
+    document.attrs.remove("tenant");
+    let operation_result = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        actor: &actor,
+        resource: &document,
+        context: None,
+    });
+    assert_eq!(operation_result, Err(Error::ActorNotAuthorized("1".into())));
+
+    Ok(())
+}
+
+#[test]
+fn custom_attributes_text_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(CUSTOM_ATTRIBUTES_CONTENT)?;
+
+    assert_eq!(CUSTOM_ATTRIBUTES_CONTENT, &storage.to_text_repr());
+
+    Ok(())
+}
+
+const CONTEXT_ATTRIBUTES_CONTENT: &str = r#"syntax = 0.17;
+
+resource Invoice {
+    policy {
+        allow = ["read"];
+
+        rule {
+            actor.type = Accountant;
+            context.network *= ["internal"];
+        }
+    }
+
+    policy {
+        allow = ["delete"];
+
+        rule {
+            actor.type = Accountant;
+            context.mfa = "true";
+            context.ip != "10.0.0.13";
+        }
+    }
+}
+"#;
+
+#[test]
+fn context_attributes_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(CONTEXT_ATTRIBUTES_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let actor = Actor {
+        id: "1".into(),
+        type_: "Accountant".into(),
+        status: None,
+        groups: vec![],
+        roles: vec![],
+        attrs: HashMap::new(),
+    };
+    let invoice = Resource {
+        id: Some("2".into()),
+        type_: "Invoice".into(),
+        owner: None,
+        status: None,
+        attrs: HashMap::new(),
+    };
+    let context = Context::new()
+        .with_attr("mfa", "true")
+        .with_attr("ip", "10.0.0.12")
+        .with_attr("network", vec!["internal".to_string(), "vpn".to_string()]);
+
+    let permissions = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        actor: &actor,
+        resource: &invoice,
+        context: Some(&context),
+    })?;
+    assert_eq!(permissions.as_ref(), &["read".to_string(), "delete".to_string()]);
+
+    let operation_result = engine.actor_has_permission(FindPermissionRequest {
+        env_name: None,
+        actor: &actor,
+        resource: &invoice,
+        context: Some(&Context::new().with_attr("ip", "10.0.0.12")),
+        permission: "delete".into(),
+    });
+    assert_eq!(operation_result, Ok(false));
+
+    let operation_result = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        actor: &actor,
+        resource: &invoice,
+        context: None,
+    });
+    assert_eq!(operation_result, Err(Error::ActorNotAuthorized("1".into())));
+
+    Ok(())
+}
+
+const COMPARISON_CONTENT: &str = r#"syntax = 0.17;
+
+
+resource Transfer {
+    env DEFAULT {
+        policy {
+            allow = ["create"];
+
+            rule {
+                actor.attrs.level >= 3;
+                resource.attrs.amount <= actor.attrs.limit;
+                context.risk < 0.5;
+            }
+        }
+
+        policy {
+            allow = ["review"];
+
+            rule {
+                resource.attrs.amount > 10000;
+                actor.attrs.level = 5;
+            }
+        }
+    }
+}
+"#;
+
+fn transfer(amount: i64) -> Resource {
+    Resource {
+        id: Some("2".into()),
+        type_: "Transfer".into(),
+        owner: None,
+        status: None,
+        attrs: HashMap::from([("amount".to_string(), amount.into())]),
+    }
+}
+
+#[test]
+fn comparison_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(COMPARISON_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let actor = Actor {
+        id: "1".into(),
+        type_: "Teller".into(),
+        status: None,
+        groups: vec![],
+        roles: vec![],
+        attrs: HashMap::from([
+            ("level".to_string(), "5".into()),
+            ("limit".to_string(), 50000.into()),
+        ]),
+    };
+    let context = Context::new().with_attr("risk", 0.25);
+
+    let permissions = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        actor: &actor,
+        resource: &transfer(20000),
+        context: Some(&context),
+    })?;
+    assert_eq!(
+        permissions.as_ref(),
+        &["create".to_string(), "review".to_string()]
+    );
+
+    let permissions = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        actor: &actor,
+        resource: &transfer(60000),
+        context: Some(&context),
+    })?;
+    assert_eq!(permissions.as_ref(), &["review".to_string()]);
+
+    let explanation = engine.explain(AuthorizeRequest {
+        env_name: None,
+        actor: &actor,
+        resource: &transfer(500),
+        context: Some(&Context::new().with_attr("risk", "high")),
+    })?;
+    let results: Vec<Option<bool>> = explanation.environments()[0].policies()[0].rules()[0]
+        .requirements()
+        .iter()
+        .map(|requirement| *requirement.result())
+        .collect();
+    assert_eq!(results, vec![Some(true), Some(true), None]);
+    assert!(!explanation.is_authorized());
+
+    Ok(())
+}
+
+#[test]
+fn comparison_text_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(COMPARISON_CONTENT)?;
+
+    assert_eq!(COMPARISON_CONTENT, &storage.to_text_repr());
+
+    Ok(())
+}
+
+const CONDITION_BLOCKS_CONTENT: &str = r#"syntax = 0.17;
+
+
+resource Document {
+    env DEFAULT {
+        policy {
+            allow = ["read"];
+
+            rule {
+                actor.type = User;
+                any {
+                    actor.roles *= "editor";
+                    all {
+                        actor.roles *= "viewer";
+                        resource.attrs.visibility = "public";
+                    }
+                }
+                not {
+                    actor.status = Banned;
+                }
+            }
+        }
+    }
+}
+"#;
+
+#[test]
+fn condition_blocks_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(CONDITION_BLOCKS_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let document = |visibility: &str| Resource {
+        id: Some("2".into()),
+        type_: "Document".into(),
+        owner: None,
+        status: None,
+        attrs: HashMap::from([("visibility".to_string(), visibility.into())]),
+    };
+    let has_permission = |actor: &Actor, resource: &Resource| {
+        engine.actor_has_permission(FindPermissionRequest {
+            env_name: None,
+            actor,
+            resource,
+            context: None,
+            permission: "read".into(),
+        })
+    };
+
+    let editor = user_actor("1", "Active", vec!["editor".into()]);
+    assert_eq!(has_permission(&editor, &document("private")), Ok(true));
+
+    let viewer = user_actor("1", "Active", vec!["viewer".into()]);
+    assert_eq!(has_permission(&viewer, &document("public")), Ok(true));
+    assert_eq!(has_permission(&viewer, &document("private")), Ok(false));
+
+    let banned_editor = user_actor("1", "Banned", vec!["editor".into()]);
+    assert_eq!(has_permission(&banned_editor, &document("public")), Ok(false));
+
+    Ok(())
+}
+
+#[test]
+fn condition_blocks_text_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(CONDITION_BLOCKS_CONTENT)?;
+
+    assert_eq!(CONDITION_BLOCKS_CONTENT, &storage.to_text_repr());
+
+    Ok(())
+}
+
+#[test]
+fn condition_blocks_explanation_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(CONDITION_BLOCKS_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let viewer = user_actor("1", "Active", vec!["viewer".into()]);
+    let document = Resource {
+        id: Some("2".into()),
+        type_: "Document".into(),
+        owner: None,
+        status: None,
+        attrs: HashMap::from([("visibility".to_string(), "private".into())]),
+    };
+
+    let explanation = engine.explain(AuthorizeRequest {
+        env_name: None,
+        actor: &viewer,
+        resource: &document,
+        context: None,
+    })?;
+    let requirements = explanation.environments()[0].policies()[0].rules()[0].requirements();
+    let results = |traces: &[RequirementTrace]| -> Vec<Option<bool>> {
+        traces.iter().map(|trace| *trace.result()).collect()
+    };
+    assert_eq!(results(requirements), vec![Some(true), Some(false), Some(true)]);
+    assert!(requirements[0].children().is_empty());
+    assert_eq!(
+        results(requirements[1].children()),
+        vec![Some(false), Some(false)]
+    );
+    assert_eq!(
+        results(requirements[1].children()[1].children()),
+        vec![Some(true), Some(false)]
+    );
+    assert_eq!(results(requirements[2].children()), vec![Some(false)]);
+
+    assert!(explanation.to_text_repr().contains(
+        r#"                any { /* false */
+                    actor.roles *= "editor"; /* false */
+                    all { /* false */
+                        actor.roles *= "viewer"; /* true */
+                        resource.attrs.visibility = "public"; /* false */
+                    }
+                }
+                not { /* true */
+                    actor.status = Banned; /* false */
+                }
+"#
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn condition_blocks_in_macros_works() -> MinosResult<()> {
+    let content = r#"syntax = 0.17M;
+
+#EDITORS {
+    any {
+        actor.roles *= "editor";
+        actor.roles *= "admin";
+    }
+}
+
+resource Document {
+    policy {
+        allow = ["write"];
+        rule {
+            #[EDITORS]
+            not {
+                actor.status = Banned;
+            }
+        }
+    }
+}
+"#;
+    let storage = MinosParser::easy_parse_str(content)?;
+    let engine = Engine::new(&storage);
+    let resource = user_resource("2", "Active");
+    let resource = Resource {
+        type_: "Document".into(),
+        ..resource
+    };
+
+    let permissions = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        actor: &user_actor("1", "Active", vec!["admin".into()]),
+        resource: &resource,
+        context: None,
+    })?;
+    assert_eq!(permissions.as_ref(), &["write".to_string()]);
+
+    let operation_result = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        actor: &user_actor("1", "Active", vec!["viewer".into()]),
+        resource: &resource,
+        context: None,
+    });
+    assert_eq!(operation_result, Err(Error::ActorNotAuthorized("1".into())));
+
+    Ok(())
+}
+
+const PATTERN_MATCH_CONTENT: &str = r#"syntax = 0.17;
+
+
+resource Repository {
+    env DEFAULT {
+        policy {
+            allow = ["read"];
+
+            rule {
+                actor.type ~= glob("Service*");
+                resource.id ~= glob("org-42/*");
+            }
+
+            rule {
+                actor.groups ~= regex("^team-[0-9]+$");
+            }
+        }
+    }
+}
+"#;
+
+#[test]
+fn pattern_match_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(PATTERN_MATCH_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let repository = |id: &str| Resource {
+        id: Some(id.into()),
+        type_: "Repository".into(),
+        owner: None,
+        status: None,
+        attrs: HashMap::new(),
+    };
+    let actor = |type_: &str, groups: Vec<String>| Actor {
+        id: "1".into(),
+        type_: type_.into(),
+        status: None,
+        groups,
+        roles: vec![],
+        attrs: HashMap::new(),
+    };
+    let has_permission = |actor: &Actor, resource: &Resource| {
+        engine.actor_has_permission(FindPermissionRequest {
+            env_name: None,
+            actor,
+            resource,
+            context: None,
+            permission: "read".into(),
+        })
+    };
+
+    let service = actor("ServiceAccount", vec![]);
+    assert_eq!(has_permission(&service, &repository("org-42/minos")), Ok(true));
+    assert_eq!(has_permission(&service, &repository("org-43/minos")), Ok(false));
+    assert_eq!(
+        has_permission(&actor("User", vec![]), &repository("org-42/minos")),
+        Ok(false)
+    );
+
+    let member = actor("User", vec!["guests".into(), "team-7".into()]);
+    assert_eq!(has_permission(&member, &repository("org-43/minos")), Ok(true));
+
+    Ok(())
+}
+
+#[test]
+fn pattern_match_text_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(PATTERN_MATCH_CONTENT)?;
+
+    assert_eq!(PATTERN_MATCH_CONTENT, &storage.to_text_repr());
+
+    Ok(())
+}
+
+#[test]
+fn invalid_regex_pattern_fails_at_parse_time() {
+    let content = r#"syntax = 0.17;
+
+resource Repository {
+    policy {
+        allow = ["read"];
+        rule {
+            actor.type ~= regex("Service(");
+        }
+    }
+}
+"#;
+    let result = MinosParser::easy_parse_str(content);
+
+    assert!(matches!(result, Err(Error::InvalidPattern { pattern, .. }) if pattern == "Service("));
+}
+
+const TEMPORAL_CONTENT: &str = r#"syntax = 0.17;
+
+
+resource Project {
+    env DEFAULT {
+        policy {
+            allow = ["read", "write"];
+
+            rule {
+                actor.type = Contractor;
+                context.time after "2026-03-01T00:00:00Z";
+                context.time before "2026-04-01T00:00:00+02:00";
+                context.weekday in Mon..Fri;
+                context.hour in 9..17;
+            }
+        }
+
+        policy {
+            allow = ["deploy"];
+
+            rule {
+                actor.type = Operator;
+                context.hour in 22..6;
+            }
+        }
+    }
+}
+"#;
+
+fn instant(rfc3339: &str) -> SystemTime {
+    chrono::DateTime::parse_from_rfc3339(rfc3339).unwrap().into()
+}
+
+#[test]
+fn temporal_requirements_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(TEMPORAL_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let project = Resource {
+        id: Some("2".into()),
+        type_: "Project".into(),
+        owner: None,
+        status: None,
+        attrs: HashMap::new(),
+    };
+    let actor = |type_: &str| Actor {
+        id: "1".into(),
+        type_: type_.into(),
+        status: None,
+        groups: vec![],
+        roles: vec![],
+        attrs: HashMap::new(),
+    };
+    let has_permission = |actor: &Actor, permission: &str, time: &str| {
+        engine.actor_has_permission(FindPermissionRequest {
+            env_name: None,
+            actor,
+            resource: &project,
+            context: Some(&Context::new().with_time(instant(time))),
+            permission: permission.into(),
+        })
+    };
+
+    let contractor = actor("Contractor");
+    // Monday
+    assert_eq!(
+        has_permission(&contractor, "read", "2026-03-02T10:00:00Z"),
+        Ok(true)
+    );
+    assert_eq!(
+        has_permission(&contractor, "read", "2026-03-02T08:59:59Z"),
+        Ok(false)
+    );
+    assert_eq!(
+        has_permission(&contractor, "read", "2026-03-02T17:00:00Z"),
+        Ok(false)
+    );
+    // Saturday
+    assert_eq!(
+        has_permission(&contractor, "read", "2026-03-07T10:00:00Z"),
+        Ok(false)
+    );
+    // before the access window
+    assert_eq!(
+        has_permission(&contractor, "read", "2026-02-27T10:00:00Z"),
+        Ok(false)
+    );
+    // Tuesday, after the access window (2026-03-31T22:00:00Z)
+    assert_eq!(
+        has_permission(&contractor, "read", "2026-03-31T12:00:00Z"),
+        Ok(true)
+    );
+    assert_eq!(
+        has_permission(&contractor, "read", "2026-04-01T12:00:00+02:00"),
+        Ok(false)
+    );
+
+    let operator = actor("Operator");
+    assert_eq!(
+        has_permission(&operator, "deploy", "2026-03-02T23:30:00Z"),
+        Ok(true)
+    );
+    assert_eq!(
+        has_permission(&operator, "deploy", "2026-03-03T05:59:00Z"),
+        Ok(true)
+    );
+    assert_eq!(
+        has_permission(&operator, "deploy", "2026-03-03T06:00:00Z"),
+        Ok(false)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn temporal_requirements_text_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(TEMPORAL_CONTENT)?;
+
+    assert_eq!(TEMPORAL_CONTENT, &storage.to_text_repr());
+
+    Ok(())
+}
+
+#[test]
+fn timestamp_parse_works() {
+    let timestamps = [
+        "2026-03-01T00:00:00Z",
+        "1969-07-20T20:17:40Z",
+        "2024-02-29T23:59:59.999-06:00",
+        "2000-01-01 12:00:00.5+05:30",
+        "1900-03-01T00:00:00.000000001z",
+    ];
+    for text in timestamps {
+        let timestamp = Timestamp::parse(&Arc::from(text)).unwrap();
+        assert_eq!(timestamp.instant(), instant(text), "{text}");
+    }
+
+    let invalid_timestamps = [
+        "2026-02-29T00:00:00Z",
+        "2026-03-01T24:00:00Z",
+        "2026-03-01T00:00:00",
+        "2026-03-01",
+    ];
+    for text in invalid_timestamps {
+        let result = Timestamp::parse(&Arc::from(text));
+        assert_eq!(result, Err(Error::InvalidTimestamp(text.into())));
+    }
+}
+
+#[test]
+fn empty_hour_range_fails() {
+    let content = TEMPORAL_CONTENT.replace("context.hour in 9..17;", "context.hour in 9..9;");
+    assert_eq!(
+        MinosParser::easy_parse_str(&content),
+        Err(Error::EmptyHourRange(9))
+    );
+
+    let content = TEMPORAL_CONTENT.replace("context.hour in 9..17;", "context.hour in 0..24;");
+    assert!(MinosParser::easy_parse_str(&content).is_ok());
+}
+
+const HIERARCHIES_CONTENT: &str = r#"syntax = 0.17;
+
+
+roles {
+    superadmin > admin;
+    admin > editor;
+    owner > editor;
+    editor > viewer;
+}
+
+groups {
+    staff > "external staff";
+}
+
+resource Article {
+    env DEFAULT {
+        policy {
+            allow = ["read"];
+
+            rule {
+                actor.roles *= "viewer";
+            }
+
+            rule {
+                actor.groups *= "external staff";
+            }
+        }
+
+        policy {
+            allow = ["publish"];
+
+            rule {
+                actor.roles *= ["admin", "viewer"];
+            }
+        }
+    }
+}
+"#;
+
+#[test]
+fn hierarchies_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(HIERARCHIES_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let article = Resource {
+        id: None,
+        type_: "Article".into(),
+        owner: None,
+        status: None,
+        attrs: HashMap::new(),
+    };
+    let authorize = |roles: Vec<String>, groups: Vec<String>| {
+        let actor = Actor {
+            id: "1".into(),
+            type_: "User".into(),
+            status: None,
+            groups,
+            roles,
+            attrs: HashMap::new(),
+        };
+        engine.authorize(AuthorizeRequest {
+            env_name: None,
+            actor: &actor,
+            resource: &article,
+            context: None,
+        })
+    };
+
+    let permissions = authorize(vec!["superadmin".into()], vec![])?;
+    assert_eq!(permissions.as_ref(), &["read".to_string(), "publish".to_string()]);
+
+    let permissions = authorize(vec!["owner".into()], vec![])?;
+    assert_eq!(permissions.as_ref(), &["read".to_string()]);
+
+    let permissions = authorize(vec![], vec!["staff".into()])?;
+    assert_eq!(permissions.as_ref(), &["read".to_string()]);
+
+    let operation_result = authorize(vec!["guest".into()], vec![]);
+    assert_eq!(operation_result, Err(Error::ActorNotAuthorized("1".into())));
+
+    Ok(())
+}
+
+#[test]
+fn hierarchies_text_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(HIERARCHIES_CONTENT)?;
+
+    assert_eq!(HIERARCHIES_CONTENT, &storage.to_text_repr());
+
+    Ok(())
+}
+
+#[test]
+fn hierarchy_cycles_fails() {
+    let content = r#"syntax = 0.17;
+
+roles {
+    admin > editor > viewer;
+    viewer > guest > editor;
+}
+
+resource Article {
+    policy {
+        allow = ["read"];
+        rule {
+            actor.roles *= "viewer";
+        }
+    }
+}
+"#;
+    let result = MinosParser::easy_parse_str(content);
+    assert_eq!(
+        result,
+        Err(Error::HierarchyCycle("editor > viewer > guest > editor".into()))
+    );
+}
+
+const INHERITANCE_CONTENT: &str = r#"syntax = 0.17;
+
+resource Document {
+    env DEFAULT {
+        policy {
+            allow = ["read"];
+            rule {
+                actor.roles *= "viewer";
+            }
+        }
+    }
+
+    env STD {
+        policy {
+            allow = ["write"];
+            rule {
+                actor.roles *= "editor";
+            }
+        }
+    }
+}
+
+resource Invoice extends Document {
+    env DEFAULT {
+        policy {
+            allow = ["pay"];
+            rule {
+                actor.roles *= "accountant";
+            }
+        }
+    }
+}
+
+resource Report extends Invoice {}
+"#;
+
+#[test]
+fn resource_inheritance_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(INHERITANCE_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let authorize = |type_: &str, roles: Vec<String>| {
+        let actor = Actor {
+            id: "1".into(),
+            type_: "User".into(),
+            status: None,
+            groups: vec![],
+            roles,
+            attrs: HashMap::new(),
+        };
+        let resource = Resource {
+            id: None,
+            type_: type_.into(),
+            owner: None,
+            status: None,
+            attrs: HashMap::new(),
+        };
+        engine.authorize(AuthorizeRequest {
+            env_name: Some("STD"),
+            actor: &actor,
+            resource: &resource,
+            context: None,
+        })
+    };
+
+    let roles: Vec<String> = vec!["viewer".into(), "editor".into(), "accountant".into()];
+    let permissions = authorize("Invoice", roles.clone())?;
+    assert_eq!(
+        permissions.as_ref(),
+        &["read".to_string(), "pay".to_string(), "write".to_string()]
+    );
+
+    let permissions = authorize("Report", roles.clone())?;
+    assert_eq!(permissions.len(), 3);
+
+    let permissions = authorize("Document", roles)?;
+    assert_eq!(permissions.as_ref(), &["read".to_string(), "write".to_string()]);
+
+    let info = engine.info();
+    assert_eq!(info.policies_len(None), 3);
+    assert_eq!(info.policies_len(Some(Criteria::ResourceType("Invoice"))), 3);
+    assert_eq!(info.policies_len(Some(Criteria::ResourceType("Report"))), 3);
+    assert_eq!(
+        info.environments(Criteria::ResourceType("Report")).unwrap().len(),
+        2
+    );
+
+    let invoice = storage.resources().get(&"Invoice".into()).unwrap();
+    assert_eq!(invoice.environments().len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn resource_inheritance_text_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(INHERITANCE_CONTENT)?;
+    let text_repr = storage.to_text_repr();
+    assert!(text_repr.contains("resource Invoice extends Document {"));
+    assert!(text_repr.contains("resource Report extends Invoice {\n}"));
+
+    assert_eq!(MinosParser::easy_parse_str(&text_repr)?, storage);
+
+    Ok(())
+}
+
+#[test]
+fn resource_inheritance_errors() {
+    let content = r#"syntax = 0.17;
+
+resource Invoice extends Missing {}
+"#;
+    let result = MinosParser::easy_parse_str(content);
+    assert_eq!(result, Err(Error::ResourceNotFound("Missing".into())));
+
+    let content = r#"syntax = 0.17;
+
+resource Invoice extends Report {}
+resource Report extends Invoice {}
+"#;
+    let result = MinosParser::easy_parse_str(content);
+    assert!(matches!(result, Err(Error::InheritanceCycle(_))));
+
+    let content = r#"syntax = 0.17;
+
+resource Document {}
+resource Report {}
+resource Invoice extends Document {}
+resource Invoice extends Report {}
+"#;
+    let result = MinosParser::easy_parse_str(content).map_err(Error::without_location);
+    assert_eq!(
+        result,
+        Err(Error::ConflictingParents {
+            resource: "Invoice".into(),
+            parent: "Document".into(),
+            other_parent: "Report".into(),
+        })
+    );
+}
+
+const LISTING_CONTENT: &str = r#"syntax = 0.17;
+
+resource File {
+    policy {
+        allow = ["read"];
+        rule {
+            actor.id = resource.owner;
+        }
+    }
+}
+
+resource File {
+    id = "public.file";
+
+    policy {
+        allow = ["read", "download"];
+        rule {
+            actor.type = User;
+        }
+    }
+}
+
+resource File {
+    id = "admin.file";
+
+    policy {
+        allow = ["read"];
+        rule {
+            actor.roles *= "admin";
+        }
+    }
+}
+"#;
+
+#[test]
+fn filter_resources_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(LISTING_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let actor = user_actor("1", "Active", vec![]);
+    let file = |id: &str, owner: &str| Resource {
+        id: Some(id.into()),
+        type_: "File".into(),
+        owner: Some(owner.into()),
+        status: None,
+        attrs: HashMap::new(),
+    };
+    let unknown = Resource {
+        type_: "Unknown".into(),
+        ..file("4", "1")
+    };
+    let candidates = vec![
+        file("1", "1"),
+        file("2", "2"),
+        file("public.file", "2"),
+        file("admin.file", "1"),
+        unknown,
+    ];
+
+    let readable = engine.filter_resources(FilterResourcesRequest {
+        env_name: None,
+        actor: &actor,
+        context: None,
+        resources: &candidates,
+        permission: "read",
+    });
+    assert_eq!(readable, vec![&candidates[0], &candidates[2]]);
+
+    Ok(())
+}
+
+#[test]
+fn attributed_resources_permissions_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(LISTING_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let request = |actor| AttributedResourcesRequest {
+        env_name: None,
+        actor,
+        context: None,
+    };
+
+    let user = user_actor("1", "Active", vec![]);
+    let resources = engine.attributed_resources_permissions(request(&user));
+    assert_eq!(resources.len(), 1);
+    let (type_, id, permissions) = &resources[0];
+    assert_eq!(type_, &Identifier::from("File"));
+    assert_eq!(id.as_ref(), "public.file");
+    assert_eq!(
+        permissions.as_ref(),
+        &["read".to_string(), "download".to_string()]
+    );
+
+    let admin = user_actor("2", "Active", vec!["admin".into()]);
+    let mut ids: Vec<String> = engine
+        .attributed_resources_permissions(request(&admin))
+        .into_iter()
+        .map(|(_, id, _)| id.to_string())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["admin.file", "public.file"]);
+
+    Ok(())
+}
+
+const WHO_CAN_ACCESS_CONTENT: &str = r#"syntax = 0.17;
+
+resource Document {
+    policy {
+        allow = ["read", "write"];
+        rule {
+            actor.type = SuperUser;
+        }
+        rule {
+            actor.roles *= "editor";
+            any {
+                actor.status = Active;
+                context.hour in 9..17;
+            }
+        }
+    }
+
+    policy {
+        allow = ["read"];
+        rule {
+            actor.id = resource.owner;
+        }
+    }
+
+    policy {
+        deny = ["write"];
+        rule {
+            resource.status = Archived;
+        }
+        rule {
+            actor.groups *= "suspended";
+        }
+    }
+}
+"#;
+
+#[test]
+fn who_can_access_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(WHO_CAN_ACCESS_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let document = |owner: Option<&str>, status: Option<&str>| Resource {
+        id: None,
+        type_: "Document".into(),
+        owner: owner.map(Into::into),
+        status: status.map(Into::into),
+        attrs: HashMap::new(),
+    };
+    let who_can = |resource: &Resource, permission| {
+        engine
+            .who_can_access(WhoCanAccessRequest {
+                env_name: None,
+                resource,
+                permission,
+            })
+            .map(|condition| condition.to_text_repr())
+    };
+
+    assert_eq!(
+        who_can(&document(Some("7"), None), "read")?,
+        r#"actor.type = SuperUser OR actor.roles *= "editor" AND (actor.status = Active OR context.hour in 9..17) OR actor.id = "7""#
+    );
+    // the owner requirement can't be satisfied without owner
+    assert_eq!(
+        who_can(&document(None, None), "read")?,
+        r#"actor.type = SuperUser OR actor.roles *= "editor" AND (actor.status = Active OR context.hour in 9..17)"#
+    );
+    assert_eq!(
+        who_can(&document(None, None), "write")?,
+        r#"(actor.type = SuperUser OR actor.roles *= "editor" AND (actor.status = Active OR context.hour in 9..17)) AND NOT actor.groups *= "suspended""#
+    );
+    // the deny policy over the resource status is resolved without actor
+    let condition = engine.who_can_access(WhoCanAccessRequest {
+        env_name: None,
+        resource: &document(None, Some("Archived")),
+        permission: "write",
+    })?;
+    assert!(condition.is_false());
+    assert!(engine
+        .who_can_access(WhoCanAccessRequest {
+            env_name: None,
+            resource: &document(None, None),
+            permission: "delete",
+        })?
+        .is_false());
+
+    let result = engine.who_can_access(WhoCanAccessRequest {
+        env_name: None,
+        resource: &Resource {
+            type_: "Unknown".into(),
+            ..document(None, None)
+        },
+        permission: "read",
+    });
+    assert!(matches!(result, Err(Error::ResourceNotFound(_))));
+
+    Ok(())
+}
+
+const WHO_CAN_ACCESS_HIERARCHY_CONTENT: &str = r#"syntax = 0.17;
+
+roles {
+    admin > editor > viewer;
+    owner > editor;
+}
+
+groups {
+    staff > employees;
+}
+
+resource Document {
+    policy {
+        allow = ["read"];
+        rule {
+            actor.roles *= "viewer";
+        }
+    }
+
+    policy {
+        allow = ["write"];
+        rule {
+            actor.roles *= "editor";
+            actor.groups *= ["employees", "writers"];
+        }
+    }
+}
+"#;
+
+#[test]
+fn who_can_access_expands_hierarchies() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(WHO_CAN_ACCESS_HIERARCHY_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let document = Resource {
+        type_: "Document".into(),
+        ..Default::default()
+    };
+    let who_can = |permission| {
+        engine
+            .who_can_access(WhoCanAccessRequest {
+                env_name: None,
+                resource: &document,
+                permission,
+            })
+            .map(|condition| condition.to_text_repr())
+    };
+
+    assert_eq!(
+        who_can("read")?,
+        r#"actor.roles *= "viewer" OR actor.roles *= "editor" OR actor.roles *= "admin" OR actor.roles *= "owner""#
+    );
+    assert_eq!(
+        who_can("write")?,
+        r#"(actor.roles *= "editor" OR actor.roles *= "admin" OR actor.roles *= "owner") AND (actor.groups *= "employees" OR actor.groups *= "staff") AND actor.groups *= "writers""#
+    );
+
+    Ok(())
+}
+
+const RESOURCE_CONDITION_CONTENT: &str = r#"syntax = 0.17;
+
+resource Document {
+    policy {
+        allow = ["read"];
+        rule {
+            actor.roles *= "admin";
+        }
+        rule {
+            actor.id = resource.owner;
+        }
+        rule {
+            actor.groups *= resource.attrs.team;
+            resource.attrs.priority <= 3;
+        }
+        rule {
+            actor.type = User;
+            any {
+                resource.status = Published;
+                actor.status = Reviewer;
+            }
+        }
+    }
+
+    policy {
+        deny = ["read"];
+        rule {
+            resource.status = Archived;
+            not {
+                actor.roles *= "auditor";
+            }
+        }
+        rule {
+            resource.attrs.team != "public";
+            resource.attrs.priority > 8;
+        }
+    }
+}
+
+resource Document {
+    id = "secret";
+
+    policy {
+        allow = ["read"];
+        rule {
+            actor.roles *= "security";
+        }
+    }
+}
+"#;
+
+/// (id, owner, status, team, priority)
+type DocumentRow = (
+    &'static str,
+    Option<&'static str>,
+    Option<&'static str>,
+    Option<&'static str>,
+    Option<i64>,
+);
+
+const DOCUMENT_ROWS: [DocumentRow; 8] = [
+    ("1", Some("alice"), Some("Draft"), None, None),
+    ("2", Some("bob"), Some("Published"), Some("public"), Some(9)),
+    ("3", None, Some("Archived"), Some("core"), Some(1)),
+    ("4", Some("bob"), None, Some("core"), Some(5)),
+    ("5", Some("alice"), Some("Published"), Some("core"), Some(10)),
+    ("6", None, None, None, Some(2)),
+    ("secret", Some("alice"), Some("Published"), Some("core"), Some(1)),
+    ("7", Some("carol"), Some("Archived"), Some("ops"), Some(3)),
+];
+
+fn rusqlite_value(value: &SqlValue) -> rusqlite::types::Value {
+    match value {
+        SqlValue::Text(text) => rusqlite::types::Value::Text(text.to_string()),
+        SqlValue::Integer(number) => rusqlite::types::Value::Integer(*number),
+        SqlValue::Real(number) => rusqlite::types::Value::Real(*number),
+    }
+}
+
+#[test]
+fn resource_condition_to_sql_works() -> anyhow::Result<()> {
+    let storage = MinosParser::easy_parse_str(RESOURCE_CONDITION_CONTENT)?;
+    let engine = Engine::new(&storage);
+
+    let connection = rusqlite::Connection::open_in_memory()?;
+    connection.execute(
+        "CREATE TABLE documents (id TEXT, owner_id TEXT, status TEXT, team TEXT, priority INTEGER)",
+        (),
+    )?;
+    for row in DOCUMENT_ROWS {
+        connection.execute("INSERT INTO documents VALUES (?, ?, ?, ?, ?)", row)?;
+    }
+    let resources: Vec<Resource> = DOCUMENT_ROWS
+        .iter()
+        .map(|(id, owner, status, team, priority)| {
+            let mut attrs = HashMap::new();
+            if let Some(team) = team {
+                attrs.insert("team".to_string(), (*team).into());
+            }
+            if let Some(priority) = priority {
+                attrs.insert("priority".to_string(), (*priority).into());
+            }
+            Resource {
+                id: Some((*id).into()),
+                type_: "Document".into(),
+                owner: owner.map(Into::into),
+                status: status.map(Into::into),
+                attrs,
+            }
+        })
+        .collect();
+    let columns = |attribute: &ResourceAttribute| match attribute {
+        ResourceAttribute::Owner => Some("owner_id".to_string()),
+        ResourceAttribute::Type => None,
+        attribute => DefaultColumns.column(attribute),
+    };
+
+    let mut reviewer = user_actor("dave", "Reviewer", vec![]);
+    reviewer.type_ = "User".into();
+    let mut auditor = user_actor("alice", "Active", vec!["auditor".into()]);
+    auditor.groups = vec!["core".into()];
+    let actors = [
+        user_actor("root", "Active", vec!["admin".into(), "security".into()]),
+        user_actor("bob", "Active", vec![]),
+        auditor,
+        reviewer,
+        Actor {
+            type_: "Service".into(),
+            groups: vec!["ops".into()],
+            ..user_actor("svc", "Active", vec![])
+        },
+    ];
+
+    for actor in &actors {
+        let condition = engine.resource_condition(ResourceConditionRequest {
+            env_name: None,
+            actor,
+            context: None,
+            resource_type: "Document",
+            permission: "read",
+        })?;
+        let predicate = SqlPredicate::from_condition(&condition, &columns)?;
+        let mut statement = connection.prepare(&format!(
+            "SELECT id FROM documents WHERE {} ORDER BY id",
+            predicate.sql()
+        ))?;
+        let params = predicate.params().iter().map(rusqlite_value);
+        let selected = statement
+            .query_map(rusqlite::params_from_iter(params), |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        let mut expected: Vec<String> = engine
+            .filter_resources(FilterResourcesRequest {
+                env_name: None,
+                actor,
+                context: None,
+                resources: &resources,
+                permission: "read",
+            })
+            .into_iter()
+            .map(|resource| resource.id.clone().unwrap_or_default())
+            .collect();
+        expected.sort();
+
+        assert_eq!(
+            selected,
+            expected,
+            "actor '{}', where {}",
+            actor.id,
+            predicate.sql()
+        );
+    }
+
+    Ok(())
+}
+
+/// Storage with the policies `allow = ["read"]` with `actor.status = resource.status`
+/// and `allow = ["write"]` with `actor.status != resource.status`, which are built without
+/// the parser.
+fn missing_attributes_storage() -> MinosResult<Storage> {
+    let status = |attribute| match attribute {
+        "actor" => Attribute::Actor(ActorAttribute::Status),
+        _ => Attribute::Resource(ResourceAttribute::Status),
+    };
+    let policy = |permission: &str, requirement| {
+        let rules = vec![Arc::new(Rule::new(vec![requirement]))];
+        let rules_map = HashMap::from([(Permission::from(permission), rules.clone())]);
+        Policy::new(
+            Effect::Allow,
+            vec![Permission::from(permission)],
+            rules,
+            rules_map,
+        )
+    };
+    let read = policy(
+        "read",
+        Requirement::Assertion(Assertion::new(
+            status("actor"),
+            ComparableValue::Attribute(status("resource")),
+        )),
+    );
+    let write = policy(
+        "write",
+        Requirement::Negation(Negation::new(
+            status("actor"),
+            ComparableValue::Attribute(status("resource")),
+        )),
+    );
+    let environment = Environment::new(DEFAULT_ENV_IDENTIFIER.into(), vec![read, write]);
+
+    let mut storage = Storage::default();
+    storage.add_resource(crate::language::resource::Resource::new(
+        "Document".into(),
+        HashMap::from([(environment.identifier().clone(), environment)]),
+    ))?;
+
+    Ok(storage)
+}
+
+/// The partial evaluation compares the missing attributes like the engine: two missing
+/// attributes are equal.
+#[test]
+fn partial_evaluation_missing_attributes_works() -> anyhow::Result<()> {
+    let storage = missing_attributes_storage()?;
+    let engine = Engine::new(&storage);
+    let connection = rusqlite::Connection::open_in_memory()?;
+    connection.execute("CREATE TABLE documents (id TEXT, status TEXT)", ())?;
+    let rows = [("1", Some("Active")), ("2", None), ("3", Some("Draft"))];
+    for row in rows {
+        connection.execute("INSERT INTO documents VALUES (?, ?)", row)?;
+    }
+    let resources: Vec<Resource> = rows
+        .iter()
+        .map(|(id, status)| Resource {
+            type_: "Document".into(),
+            status: status.map(Into::into),
+            ..user_resource(id, "")
+        })
+        .collect();
+    let actors = [
+        user_actor("1", "Active", vec![]),
+        Actor {
+            status: None,
+            ..user_actor("2", "", vec![])
+        },
+    ];
+
+    for actor in &actors {
+        for permission in ["read", "write"] {
+            let condition = engine.resource_condition(ResourceConditionRequest {
+                env_name: None,
+                actor,
+                context: None,
+                resource_type: "Document",
+                permission,
+            })?;
+            let predicate = SqlPredicate::from_condition(&condition, &DefaultColumns)?;
+            let mut statement = connection.prepare(&format!(
+                "SELECT id FROM documents WHERE {} ORDER BY id",
+                predicate.sql()
+            ))?;
+            let params = predicate.params().iter().map(rusqlite_value);
+            let selected = statement
+                .query_map(rusqlite::params_from_iter(params), |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<String>, _>>()?;
+
+            let expected: Vec<String> = resources
+                .iter()
+                .filter(|resource| {
+                    engine
+                        .actor_has_permission(FindPermissionRequest {
+                            env_name: None,
+                            actor,
+                            resource,
+                            context: None,
+                            permission: permission.to_string(),
+                        })
+                        .unwrap_or_default()
+                })
+                .map(|resource| resource.id.clone().unwrap_or_default())
+                .collect();
+
+            assert_eq!(selected, expected, "actor '{}', {permission}", actor.id);
+        }
+    }
+
+    let condition = engine.who_can_access(WhoCanAccessRequest {
+        env_name: None,
+        resource: &resources[1],
+        permission: "read",
+    })?;
+    assert_eq!(condition.to_text_repr(), "actor.status IS MISSING");
+
+    Ok(())
+}
+
+#[test]
+fn resource_condition_sql_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(RESOURCE_CONDITION_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let actor = user_actor("bob", "Active", vec![]);
+    let condition = engine.resource_condition(ResourceConditionRequest {
+        env_name: None,
+        actor: &actor,
+        context: None,
+        resource_type: "Document",
+        permission: "read",
+    })?;
+    let predicate = SqlPredicate::from_condition(&condition, &DefaultColumns)?;
+
+    assert_eq!(
+        predicate.sql(),
+        "(NOT (id IS NOT NULL AND id = ?) \
+        AND ((owner IS NOT NULL AND owner = ?) OR (status IS NOT NULL AND status = ?)) \
+        AND NOT ((status IS NOT NULL AND status = ?) \
+        OR ((\"team\" IS NOT NULL AND \"team\" <> ?) \
+        AND (\"priority\" IS NOT NULL AND \"priority\" > ?))))"
+    );
+    assert_eq!(
+        predicate.params(),
+        &vec![
+            SqlValue::Text("secret".into()),
+            SqlValue::Text("bob".into()),
+            SqlValue::Text("Published".into()),
+            SqlValue::Text("Archived".into()),
+            SqlValue::Text("public".into()),
+            SqlValue::Integer(8),
+        ]
+    );
+
+    assert_eq!(quoted_identifier(r#"a"b"#), r#""a""b""#);
+
+    let result = SqlPredicate::from_condition(&condition, &|_: &ResourceAttribute| None);
+    assert_eq!(result, Err(Error::MissingColumn("resource.id".to_string())));
+
+    let result = engine.resource_condition(ResourceConditionRequest {
+        env_name: None,
+        actor: &actor,
+        context: None,
+        resource_type: "Unknown",
+        permission: "read",
+    });
+    assert!(matches!(result, Err(Error::ResourceNotFound(_))));
+
+    Ok(())
+}
+
+#[test]
+fn authorize_batch_works() {
+    let admin = user_actor("1", "Active", vec!["admin".into()]);
+    let banned = user_actor("2", "Banned", vec!["admin".into()]);
+    let self_user = user_resource("2", "Active");
+    let other_user = user_resource("3", "Active");
+    let unknown = Resource {
+        type_: "Unknown".into(),
+        ..user_resource("4", "Active")
+    };
+    let other_user_copy = other_user.clone();
+    let active_without_id = Resource {
+        id: None,
+        ..user_resource("", "Active")
+    };
+    let banned_without_id = Resource {
+        id: None,
+        ..user_resource("", "Banned")
+    };
+    // the same ids with other attributes
+    let admin_without_roles = user_actor("1", "Active", vec![]);
+    let banned_other_user = user_resource("3", "Banned");
+    let item = |env_name, actor, resource, permission| BatchItem {
+        env_name,
+        actor,
+        resource,
+        permission,
+    };
+    let items = [
+        item(Some("STD"), &admin, &other_user, "delete"),
+        item(Some("STD"), &banned, &self_user, "read_status"),
+        item(Some("STD"), &banned, &other_user, "delete"),
+        item(Some("STD"), &banned, &other_user, "read_status"),
+        item(Some("ROOT"), &admin, &other_user, "delete"),
+        item(Some("MISSING"), &admin, &other_user, "delete"),
+        item(Some("STD"), &admin, &unknown, "delete"),
+        item(None, &admin, &other_user, "delete"),
+        item(Some("STD"), &banned, &other_user_copy, "read_status"),
+        item(Some("STD"), &admin, &active_without_id, "read_status"),
+        item(Some("STD"), &admin, &banned_without_id, "read_status"),
+        item(Some("STD"), &admin_without_roles, &other_user, "delete"),
+        item(Some("STD"), &banned, &banned_other_user, "read_status"),
+    ];
+
+    let results = ENGINE.authorize_batch(BatchAuthorizeRequest {
+        context: None,
+        items: &items,
+    });
+
+    assert_eq!(results.len(), items.len());
+    for (item, result) in items.iter().zip(results) {
+        let expected = ENGINE.actor_has_permission(FindPermissionRequest {
+            env_name: item.env_name,
+            actor: item.actor,
+            resource: item.resource,
+            context: None,
+            permission: item.permission.to_string(),
+        });
+        assert_eq!(result, expected, "{item:?}");
+    }
+    assert_eq!(
+        ENGINE
+            .authorize_batch(BatchAuthorizeRequest {
+                context: None,
+                items: &[
+                    item(Some("STD"), &admin, &other_user, "delete"),
+                    item(Some("STD"), &admin_without_roles, &other_user, "delete"),
+                ],
+            })
+            .into_iter()
+            .collect::<MinosResult<Vec<bool>>>(),
+        Ok(vec![true, false])
+    );
+    assert_eq!(
+        ENGINE
+            .authorize_batch(BatchAuthorizeRequest {
+                context: None,
+                items: &items[..4],
+            })
+            .into_iter()
+            .collect::<MinosResult<Vec<bool>>>(),
+        Ok(vec![true, true, false, true])
+    );
+}
+
+#[test]
+fn decision_works() -> MinosResult<()> {
+    let admin = user_actor("1", "Active", vec!["admin".into()]);
+    let banned_admin = user_actor("2", "Banned", vec!["admin".into()]);
+    let banned = user_actor("3", "Banned", vec![]);
+    let user = user_actor("4", "Active", vec![]);
+    let resource = user_resource("5", "Active");
+    let decide = |actor| {
+        ENGINE.decide(AuthorizeRequest {
+            env_name: Some("STD"),
+            actor,
+            resource: &resource,
+            context: None,
+        })
+    };
+    let decide_permission = |actor, permission: &str| {
+        ENGINE.decide_permission(FindPermissionRequest {
+            env_name: Some("STD"),
+            actor,
+            resource: &resource,
+            context: None,
+            permission: permission.to_string(),
+        })
+    };
+
+    let decision = decide(&admin)?;
+    assert!(decision.is_allowed());
+    assert_eq!(decision.permissions().map(|p| p.len()), Some(5));
+
+    let decision = decide(&banned_admin)?;
+    assert_eq!(
+        decision.permissions().map(|p| p.as_ref()),
+        Some(&["read_status".to_string()][..])
+    );
+
+    let Decision::Deny(reason) = decide(&banned)? else {
+        panic!("expected a deny decision");
+    };
+    assert_eq!(
+        reason.revoked().as_ref(),
+        &["create", "delete", "sudo", "update_status"].map(String::from)
+    );
+    assert_eq!(decide(&user)?, Decision::NotApplicable);
+
+    assert!(decide_permission(&admin, "delete")?.is_allowed());
+    assert!(matches!(
+        decide_permission(&banned_admin, "delete")?,
+        Decision::Deny(_)
+    ));
+    assert_eq!(decide_permission(&user, "delete")?, Decision::NotApplicable);
+
+    let result = ENGINE.decide(AuthorizeRequest {
+        env_name: Some("STD"),
+        actor: &admin,
+        resource: &Resource {
+            type_: "Unknown".into(),
+            ..user_resource("5", "Active")
+        },
+        context: None,
+    });
+    assert!(matches!(result, Err(Error::ResourceNotFound(_))));
+
+    Ok(())
+}
+
+const FALLBACK_CONTENT: &str = r#"syntax = 0.17;
+
+resource * {
+    policy {
+        allow = ["read"];
+        rule {
+            actor.roles *= "admin";
+        }
+    }
+}
+
+resource Document {
+    env DEFAULT {
+        policy {
+            allow = ["read"];
+            rule {
+                actor.id = resource.owner;
+            }
+        }
+    }
+
+    env Archive {
+        policy {
+            allow = ["restore"];
+            rule {
+                actor.roles *= "archivist";
+            }
+        }
+    }
+}
+"#;
+
+#[test]
+fn unknown_resource_fallback_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(FALLBACK_CONTENT)?;
+    let admin = user_actor("1", "Active", vec!["admin".into()]);
+    let invoice = Resource {
+        type_: "Invoice".into(),
+        ..user_resource("2", "Active")
+    };
+    let request = |engine: &Engine| {
+        engine.actor_has_permission(FindPermissionRequest {
+            env_name: None,
+            actor: &admin,
+            resource: &invoice,
+            context: None,
+            permission: "read".to_string(),
+        })
+    };
+
+    let engine = Engine::new(&storage);
+    assert_eq!(
+        request(&engine),
+        Err(Error::ResourceNotFound("Invoice".to_string()))
+    );
+
+    let engine = Engine::builder(&storage)
+        .unknown_resource(UnknownResourceFallback::Deny)
+        .build();
+    assert_eq!(request(&engine), Ok(false));
+    let decision = engine.decide(AuthorizeRequest {
+        env_name: None,
+        actor: &admin,
+        resource: &invoice,
+        context: None,
+    });
+    assert_eq!(decision, Ok(Decision::NotApplicable));
+
+    let engine = Engine::builder(&storage)
+        .unknown_resource(UnknownResourceFallback::WildcardResource)
+        .build();
+    assert_eq!(request(&engine), Ok(true));
+
+    let storage = MinosParser::easy_parse_str(LISTING_CONTENT)?;
+    let engine = Engine::builder(&storage)
+        .unknown_resource(UnknownResourceFallback::WildcardResource)
+        .build();
+    assert_eq!(
+        request(&engine),
+        Err(Error::ResourceNotFound("Invoice".to_string()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn unknown_environment_fallback_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(FALLBACK_CONTENT)?;
+    let owner = user_actor("1", "Active", vec!["archivist".into()]);
+    let document = Resource {
+        type_: "Document".into(),
+        owner: Some("1".into()),
+        ..user_resource("2", "Active")
+    };
+    let request = |engine: &Engine, env_name| {
+        engine.actor_has_permission(FindPermissionRequest {
+            env_name: Some(env_name),
+            actor: &owner,
+            resource: &document,
+            context: None,
+            permission: "read".to_string(),
+        })
+    };
+
+    let engine = Engine::new(&storage);
+    assert_eq!(request(&engine, "Archive"), Ok(true));
+    assert_eq!(request(&engine, "Drafts"), Ok(true));
+
+    let engine = Engine::builder(&storage)
+        .unknown_environment(UnknownEnvironmentFallback::Error)
+        .build();
+    assert_eq!(
+        request(&engine, "Drafts"),
+        Err(Error::EnvironmentNotFound("Drafts".to_string()))
+    );
+
+    let engine = Engine::builder(&storage)
+        .unknown_environment(UnknownEnvironmentFallback::Deny)
+        .build();
+    assert_eq!(request(&engine, "Drafts"), Ok(false));
+
+    Ok(())
+}
+
+#[test]
+fn wildcard_resource_text_repr_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(FALLBACK_CONTENT)?;
+    let text_repr = storage.to_text_repr();
+    assert!(text_repr.contains("resource * {"));
+
+    assert_eq!(MinosParser::easy_parse_str(&text_repr)?, storage);
+
+    Ok(())
+}
+
+const PATTERNS_CONTENT: &str = r#"syntax = 0.17;
+
+resource * {
+    env DEFAULT {
+        policy {
+            allow = ["*"];
+            rule {
+                actor.roles *= "admin";
+            }
+        }
+    }
+
+    env Audit {
+        policy {
+            allow = ["*:read"];
+            rule {
+                actor.roles *= "auditor";
+            }
+        }
+    }
+}
+
+resource Document {
+    env DEFAULT {
+        policy {
+            allow = ["document:*"];
+            rule {
+                actor.id = resource.owner;
+            }
+        }
+
+        policy {
+            deny = ["document:delete"];
+            rule {
+                resource.status = Archived;
+            }
+        }
+    }
+}
+"#;
+
+#[test]
+fn wildcard_resource_and_permission_patterns_work() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(PATTERNS_CONTENT)?;
+    let engine = Engine::new(&storage);
+    let owner = user_actor("1", "Active", vec![]);
+    let admin = user_actor("2", "Active", vec!["admin".into()]);
+    let auditor = user_actor("3", "Active", vec!["auditor".into()]);
+    let document = Resource {
+        type_: "Document".into(),
+        owner: Some("1".into()),
+        ..user_resource("4", "Active")
+    };
+    let archived = Resource {
+        status: Some("Archived".into()),
+        ..document.clone()
+    };
+    let has_permission = |actor, resource, env_name, permission: &str| {
+        engine.actor_has_permission(FindPermissionRequest {
+            env_name,
+            actor,
+            resource,
+            context: None,
+            permission: permission.to_string(),
+        })
+    };
+
+    assert_eq!(has_permission(&owner, &document, None, "document:edit"), Ok(true));
+    assert_eq!(has_permission(&owner, &document, None, "invoice:edit"), Ok(false));
+    assert_eq!(has_permission(&owner, &archived, None, "document:edit"), Ok(true));
+    assert_eq!(
+        has_permission(&owner, &archived, None, "document:delete"),
+        Ok(false)
+    );
+    assert_eq!(
+        has_permission(&admin, &document, None, "document:delete"),
+        Ok(true)
+    );
+    assert_eq!(
+        has_permission(&admin, &archived, None, "document:delete"),
+        Ok(false)
+    );
+    assert_eq!(
+        has_permission(&auditor, &document, Some("Audit"), "document:read"),
+        Ok(true)
+    );
+    assert_eq!(
+        has_permission(&auditor, &document, Some("Audit"), "document:edit"),
+        Ok(false)
+    );
+
+    let permissions = engine.authorize(AuthorizeRequest {
+        env_name: None,
+        actor: &owner,
+        resource: &archived,
+        context: None,
+    })?;
+    assert_eq!(permissions.as_ref(), &["document:*".to_string()]);
+    assert_eq!(permissions.revoked(), &[Permission::from("document:delete")]);
+    assert!(!permissions.contains(&"document:delete".to_string()));
+    assert!(permissions.has("document:edit"));
+    assert!(!permissions.has("document:delete"));
+    assert!(!permissions.has("invoice:edit"));
+
+    let permissions = engine.authorize(AuthorizeRequest {
+        env_name: Some("Audit"),
+        actor: &auditor,
+        resource: &document,
+        context: None,
+    })?;
+    assert!(permissions.has("document:read"));
+    assert!(permissions.has("invoice:read"));
+    assert!(!permissions.has("document:edit"));
+
+    Ok(())
+}
+
+const RELOAD_CONTENT: &str = r#"syntax = 0.17;
+
+resource Document {
+    policy {
+        allow = ["read"];
+        rule {
+            actor.roles *= "reader";
+        }
+    }
+}
+"#;
+
+const RELOAD_MACROS_CONTENT: &str = r#"syntax = 0.17M;
+
+#CAN_READ {
+    actor.roles *= "reader";
+}
+
+resource Shared {
+    policy {
+        allow = ["read"];
+        rule {
+            #[CAN_READ]
+        }
+    }
+}
+"#;
+
+const RELOAD_IMPORTING_CONTENT: &str = r#"syntax = 0.17M;
+
+import "{macros_file}";
+
+resource Document {
+    policy {
+        allow = ["read"];
+        rule {
+            #[CAN_READ]
+        }
+    }
+}
+"#;
+
+#[test]
+fn reloadable_container_works() -> MinosResult<()> {
+    let dir = std::env::temp_dir().join(format!("minos-reload-{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+    let file = dir.join("policies.minos");
+    fs::write(&file, RELOAD_CONTENT)?;
+
+    let container = Arc::new(
+        Container::new("1".to_string(), "Reload".to_string(), vec![dir.clone()]).load_reloadable()?,
+    );
+    let editor = user_actor("1", "Active", vec!["editor".into()]);
+    let document = Resource {
+        type_: "Document".into(),
+        ..user_resource("2", "Active")
+    };
+    let can_read = |storage: &Storage| {
+        Engine::new(storage).actor_has_permission(FindPermissionRequest {
+            env_name: None,
+            actor: &editor,
+            resource: &document,
+            context: None,
+            permission: "read".to_string(),
+        })
+    };
+
+    let snapshot = container.storage();
+    assert_eq!(can_read(&snapshot), Ok(false));
+
+    fs::write(&file, RELOAD_CONTENT.replace("reader", "editor"))?;
+    container.reload()?;
+    assert_eq!(container.generation(), 1);
+    assert_eq!(can_read(&container.storage()), Ok(true));
+    // the previous snapshot is still usable
+    assert_eq!(can_read(&snapshot), Ok(false));
+
+    fs::write(&file, "syntax = 0.17;\nresource Document {")?;
+    assert!(container.reload().is_err());
+    assert!(container.last_error().is_some());
+    assert_eq!(container.generation(), 1);
+    assert_eq!(can_read(&container.storage()), Ok(true));
+
+    let watcher = container.watch(Duration::from_millis(10));
+    thread::sleep(Duration::from_millis(50));
+    fs::write(&file, RELOAD_CONTENT)?;
+    let start = Instant::now();
+    while container.generation() < 2 && start.elapsed() < Duration::from_secs(5) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    drop(watcher);
+    assert_eq!(container.generation(), 2);
+    assert_eq!(container.last_error(), None);
+    assert_eq!(can_read(&container.storage()), Ok(false));
+
+    // the imported files are watched too, even if they are out of the paths
+    let macros_dir = dir.with_extension("macros");
+    fs::create_dir_all(&macros_dir)?;
+    let macros_file = macros_dir.join("roles.minos");
+    fs::write(&macros_file, RELOAD_MACROS_CONTENT)?;
+    let importing_content =
+        RELOAD_IMPORTING_CONTENT.replace("{macros_file}", &macros_file.to_string_lossy());
+    fs::write(&file, importing_content)?;
+    container.reload()?;
+    assert_eq!(container.generation(), 3);
+    assert_eq!(can_read(&container.storage()), Ok(false));
+
+    let watcher = container.watch(Duration::from_millis(10));
+    thread::sleep(Duration::from_millis(50));
+    fs::write(&macros_file, RELOAD_MACROS_CONTENT.replace("reader", "editor"))?;
+    let start = Instant::now();
+    while container.generation() < 4 && start.elapsed() < Duration::from_secs(5) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    drop(watcher);
+    assert_eq!(container.generation(), 4);
+    assert_eq!(can_read(&container.storage()), Ok(true));
+
+    fs::remove_dir_all(&dir)?;
+    fs::remove_dir_all(&macros_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn shared_engine_works() -> MinosResult<()> {
+    fn assert_shareable<T: Send + Sync + 'static>(_: &T) {}
+
+    let storage = Arc::new(MinosParser::easy_parse_str(RELOAD_CONTENT)?);
+    let engine: SharedEngine = Engine::shared_builder(storage.clone())
+        .unknown_resource(UnknownResourceFallback::Deny)
+        .build();
+    assert_shareable(&engine);
+    // the clones share the storage
+    let clone = engine.clone();
+    assert_eq!(Arc::strong_count(&storage), 3);
+    drop(clone);
+
+    let handles: Vec<_> = ["reader", "editor"]
+        .into_iter()
+        .map(|role| {
+            let engine = engine.clone();
+            thread::spawn(move || {
+                let actor = user_actor("1", "Active", vec![role.into()]);
+                let document = Resource {
+                    type_: "Document".into(),
+                    ..user_resource("2", "Active")
+                };
+                engine.actor_has_permission(FindPermissionRequest {
+                    env_name: None,
+                    actor: &actor,
+                    resource: &document,
+                    context: None,
+                    permission: "read".to_string(),
+                })
+            })
+        })
+        .collect();
+    let results: Vec<MinosResult<bool>> =
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+    assert_eq!(results, vec![Ok(true), Ok(false)]);
+
+    Ok(())
+}
+
+const LOCATED_SYNTAX_CONTENT: &str = r#"syntax = 0.17;
+
+resource Document {
+    policy {
+        allow = [read];
+    }
+}
+"#;
+
+const LOCATED_TIMESTAMP_CONTENT: &str = r#"syntax = 0.17;
+
+resource Document {
+    policy {
+        allow = ["read"];
+        rule {
+            context.time before "yesterday";
+        }
+    }
+}
+"#;
+
+const LOCATED_MACRO_CONTENT: &str = r#"syntax = 0.17M;
+
+#READERS {
+    actor.roles *= "reader";
+}
+
+resource Document {
+    policy {
+        allow = ["read"];
+        rule {
+            #[WRITERS]
+        }
+    }
+}
+"#;
+
+#[test]
+fn located_errors_work() -> MinosResult<()> {
+    let dir = std::env::temp_dir().join(format!("minos-located-{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+    let load = |file_name: &str, content: &str| -> MinosResult<LocatedError> {
+        let file = dir.join(file_name);
+        fs::write(&file, content)?;
+        let result = Container::new("1".to_string(), "Located".to_string(), vec![file.clone()]).load();
+        fs::remove_file(&file)?;
+        match result.map(|_| ()) {
+            Err(Error::Located(located)) => Ok(*located),
+            result => panic!("expected located error, found {result:?}"),
+        }
+    };
+
+    let located = load("syntax.minos", LOCATED_SYNTAX_CONTENT)?;
+    assert_eq!(
+        located.path().as_deref(),
+        Some(dir.join("syntax.minos").as_path())
+    );
+    let position = located.position().as_ref().unwrap();
+    assert_eq!((*position.line(), *position.column()), (5, 18));
+    assert_eq!(position.line_text(), "        allow = [read];");
+    assert!(matches!(located.error(), Error::RuleV0_17(_)));
+
+    let located = load("timestamp.minos", LOCATED_TIMESTAMP_CONTENT)?;
+    assert_eq!(located.error(), &Error::InvalidTimestamp("yesterday".into()));
+    let position = located.position().as_ref().unwrap();
+    assert_eq!((*position.line(), *position.column()), (7, 13));
+    let expected = format!(
+        "error: invalid timestamp 'yesterday', expected RFC 3339 format\n --> {}:7:13\n  |\n7 |             context.time before \"yesterday\";\n  |             ^",
+        dir.join("timestamp.minos").display()
+    );
+    assert_eq!(located.render(), expected);
+
+    let located = load("macro.minos", LOCATED_MACRO_CONTENT)?;
+    let expected = format!(
+        "{}:11:13: macro 'WRITERS' not found",
+        dir.join("macro.minos").display()
+    );
+    assert_eq!(located.to_string(), expected);
+
+    fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+const RECOVERY_CONTENT: &str = r#"syntax = 0.17;
+
+resource Document {
+    policy {
+        allow = [read];
+    }
+}
+
+resource Folder {
+    policy {
+        allow = ["read"];
+        rule {
+            actor.roles *= "reader";
+        }
+    }
+}
+
+resource Invoice {
+    policy {
+        allow = ["read"];
+        rule {
+            context.time before "yesterday";
+        }
+    }
+}
+
+resource Report {
+    policy {
+        allow = ["read"];
+        rule {
+"#;
+
+#[test]
+fn load_with_report_works() -> MinosResult<()> {
+    let report = MinosParser::parse_str_with_report(RECOVERY_CONTENT).unwrap_err();
+    let lines: Vec<usize> = report
+        .errors()
+        .iter()
+        .map(|error| match error {
+            Error::Located(located) => *located.position().as_ref().unwrap().line(),
+            error => panic!("expected located error, found {error:?}"),
+        })
+        .collect();
+    assert_eq!(lines, vec![5, 22, 31]);
+    let resources = report.partial_storage().resources();
+    assert_eq!(resources.len(), 1);
+    assert!(resources.contains_key(&Identifier::from("Folder")));
+
+    let dir = std::env::temp_dir().join(format!("minos-report-{}", std::process::id()));
+    fs::create_dir_all(dir.join("nested"))?;
+    fs::write(dir.join("invalid.minos"), RECOVERY_CONTENT)?;
+    fs::write(dir.join("nested").join("valid.minos"), RELOAD_CONTENT)?;
+    fs::write(dir.join("unsupported.minos"), "resource User {}")?;
+
+    let container = Container::new("1".to_string(), "Report".to_string(), vec![dir.clone()]);
+    let report = container.load_with_report().unwrap_err();
+    assert_eq!(report.errors().len(), 4);
+    let rendered = report.render();
+    assert!(rendered.contains(&format!("{}:22:13", dir.join("invalid.minos").display())));
+    assert!(rendered.contains("sintaxis not supported"));
+    let mut resources: Vec<&str> = report
+        .partial_storage()
+        .resources()
+        .keys()
+        .map(|identifier| identifier.0.as_ref())
+        .collect();
+    resources.sort();
+    assert_eq!(resources, vec!["Document", "Folder"]);
+
+    fs::remove_file(dir.join("invalid.minos"))?;
+    fs::remove_file(dir.join("unsupported.minos"))?;
+    let container = Container::new("1".to_string(), "Report".to_string(), vec![dir.clone()]);
+    assert!(container.load_with_report().is_ok());
+
+    fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+const POLICY_RECOVERY_CONTENT: &str = r#"syntax = 0.17;
+
+resource Document {
+    env DEFAULT {
+        policy {
+            allow = ["read"];
+            rule {
+                actor.roles *= "reader";
+            }
+        }
+
+        policy {
+            allow = [write];
+        }
+
+        policy {
+            allow = ["delete"];
+            rule {
+                context.time before "yesterday";
+            }
+        }
+
+        policy {
+            allow = ["share"];
+            rule {
+                actor.roles *= "owner";
+            }
+        }
+    }
+
+    env Audit {
+        policy {
+            allow = [audit];
+        }
+    }
+}
+
+resource Folder {
+    policy {
+        allow = ["read"];
+        rule {
+            actor.roles *= "reader";
+        }
+    }
+}
+"#;
+
+#[test]
+fn load_with_report_skips_invalid_policies() -> MinosResult<()> {
+    let report = MinosParser::parse_str_with_report(POLICY_RECOVERY_CONTENT).unwrap_err();
+    let lines: Vec<usize> = report
+        .errors()
+        .iter()
+        .map(|error| match error {
+            Error::Located(located) => *located.position().as_ref().unwrap().line(),
+            error => panic!("expected located error, found {error:?}"),
+        })
+        .collect();
+    assert_eq!(lines, vec![13, 33]);
+
+    // the only policy of the `Audit` environment can't be skipped, so the whole resource is
+    let resources = report.partial_storage().resources();
+    assert_eq!(resources.len(), 1);
+    assert!(resources.contains_key(&Identifier::from("Folder")));
+
+    let content = POLICY_RECOVERY_CONTENT.replace(
+        "    env Audit {\n        policy {\n            allow = [audit];\n        }\n    }\n",
+        "",
+    );
+    let report = MinosParser::parse_str_with_report(&content).unwrap_err();
+    let lines: Vec<usize> = report
+        .errors()
+        .iter()
+        .filter_map(|error| match error {
+            Error::Located(located) => Some(*located.position().as_ref()?.line()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(lines, vec![13, 19]);
+    let document = &report.partial_storage().resources()[&Identifier::from("Document")];
+    let permissions: Vec<&str> = document
+        .default_environment()
+        .unwrap()
+        .policies()
+        .iter()
+        .flat_map(|policy| {
+            policy
+                .permissions()
+                .iter()
+                .map(|permission| permission.0.as_ref())
+        })
+        .collect();
+    assert_eq!(permissions, vec!["read", "share"]);
+
+    Ok(())
+}
+
+const LINT_CONTENT: &str = r#"syntax = 0.17;
+
+resource Document {
+    env DEFAULT {
+        policy {
+            allow = ["read", "edit", "read"];
+            rule {
+                actor.status = Active;
+            }
+            rule {
+                actor.status = Active;
+                actor.roles *= "editor";
+            }
+        }
+    }
+
+    env Default {
+        policy {
+            allow = ["read"];
+            rule {
+                actor.status = Activ;
+            }
+            rule {
+                actor.type = User;
+                actor.type = Service;
+            }
+            rule {
+                resource.status = Active;
+            }
+        }
+    }
+}
+"#;
+
+#[test]
+fn lint_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(LINT_CONTENT)?;
+    let lints = lint(&storage, &LintConfig::default());
+    let summary: Vec<(LintCode, String, usize)> = lints
+        .iter()
+        .map(|lint| {
+            let line = *lint.position().as_ref().unwrap().line();
+            (*lint.code(), lint.location().to_string(), line)
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                LintCode::DuplicatedPermission,
+                "resource Document > env DEFAULT > policy #1".to_string(),
+                5
+            ),
+            (
+                LintCode::RedundantRule,
+                "resource Document > env DEFAULT > policy #1 > rule #2".to_string(),
+                10
+            ),
+            (
+                LintCode::DefaultEnvironmentCase,
+                "resource Document > env Default".to_string(),
+                17
+            ),
+            (
+                LintCode::UnknownStatus,
+                "resource Document > env Default > policy #1 > rule #1".to_string(),
+                20
+            ),
+            (
+                LintCode::UnsatisfiableRule,
+                "resource Document > env Default > policy #1 > rule #2".to_string(),
+                23
+            ),
+        ]
+    );
+    assert_eq!(
+        lints[3].to_string(),
+        "warning[L001]: the status `Activ` looks like a typo of `Active`\n  --> 20:13\n   |\n20 |             rule {\n   |             ^"
+    );
+
+    let config = LintConfig::default()
+        .severity(LintCode::RedundantRule, Severity::Allow)
+        .severity(LintCode::UnsatisfiableRule, Severity::Error)
+        .known_statuses(["Active", "Activ"]);
+    let lints = lint(&storage, &config);
+    let codes: Vec<(LintCode, Severity)> = lints
+        .iter()
+        .map(|lint| (*lint.code(), *lint.severity()))
+        .collect();
+    assert_eq!(
+        codes,
+        vec![
+            (LintCode::DuplicatedPermission, Severity::Warning),
+            (LintCode::DefaultEnvironmentCase, Severity::Warning),
+            (LintCode::UnsatisfiableRule, Severity::Error),
+        ]
+    );
+
+    let dir = std::env::temp_dir().join(format!("minos-lint-{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+    let file = dir.join("lint.minos");
+    fs::write(&file, LINT_CONTENT)?;
+    let container = Container::new("1".to_string(), "Lint".to_string(), vec![file.clone()]).load()?;
+    let lints = lint(container.storage(), &LintConfig::default());
+    assert_eq!(lints[2].path().as_deref(), Some(file.as_path()));
+    assert!(lints[2]
+        .to_string()
+        .contains(&format!("--> {}:17:5", file.display())));
+    fs::remove_dir_all(&dir)?;
+
+    let report = MinosParser::parse_str_with_report(LINT_SCHEMA_CONTENT).unwrap_err();
+    let lints = lint(report.partial_storage(), &LintConfig::default());
+    let messages: Vec<&str> = lints.iter().map(|lint| lint.message().as_str()).collect();
+    assert_eq!(messages, vec!["the status `Activ` isn't a known status"]);
+
+    Ok(())
+}
+
+const LINT_SCHEMA_CONTENT: &str = r#"syntax = 0.17;
+
+schema {
+    statuses Active, Archived;
+}
+
+resource Document {
+    policy {
+        allow = ["read"];
+        rule {
+            actor.status = Active;
+        }
+        rule {
+            actor.status = Activ;
+        }
+    }
+}
+"#;
+
+const SCHEMA_CONTENT: &str = r#"syntax = 0.17;
+
+schema {
+    actor types User, Service;
+    resource types Document, Invoice;
+    statuses Active, OnHold;
+    permissions for Document "read", "write";
+    permissions for * "audit";
+}
+
+resource Document {
+    policy {
+        allow = ["read", "audit"];
+        rule {
+            actor.type = User;
+            actor.status = Active;
+            resource.status != OnHold;
+        }
+    }
+}
+
+resource * {
+    policy {
+        allow = ["write"];
+        rule {
+            actor.type = Service;
+        }
+    }
+}
+"#;
+
+#[test]
+fn schema_works() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(SCHEMA_CONTENT)?;
+    assert_eq!(storage.schema().statuses().len(), 2);
+    let reparsed = MinosParser::easy_parse_str(&storage.to_text_repr())?;
+    assert_eq!(reparsed.schema(), storage.schema());
+
+    let undeclared = |from: &str, to: &str| {
+        MinosParser::easy_parse_str(&SCHEMA_CONTENT.replacen(from, to, 1)).map(|_| ())
+    };
+    assert_eq!(
+        undeclared("actor.status = Active", "actor.status = Activ"),
+        Err(Error::Undeclared {
+            kind: "status",
+            name: "Activ".to_string(),
+            place: "resource Document, environment DEFAULT".to_string(),
+        })
+    );
+    assert!(matches!(
+        undeclared("actor.type = User", "actor.type = Usr"),
+        Err(Error::Undeclared {
+            kind: "actor type",
+            ..
+        })
+    ));
+    assert!(matches!(
+        undeclared("resource Document {", "resource Documents {"),
+        Err(Error::Undeclared {
+            kind: "resource type",
+            ..
+        })
+    ));
+    assert!(matches!(
+        undeclared(r#"["read", "audit"]"#, r#"["raed"]"#),
+        Err(Error::Undeclared {
+            kind: "permission",
+            ..
+        })
+    ));
+    // the patterns and the permissions of the wildcard resource match the declared permissions
+    assert_eq!(undeclared(r#"["read", "audit"]"#, r#"["re*"]"#), Ok(()));
+    assert!(matches!(
+        undeclared(r#"["write"]"#, r#"["delete"]"#),
+        Err(Error::Undeclared {
+            kind: "permission",
+            ..
+        })
+    ));
+
+    let actor = user_actor("1", "Active", vec![]);
+    let document = Resource {
+        type_: "Document".into(),
+        ..user_resource("2", "Active")
+    };
+    fn request<'a>(
+        actor: &'a Actor,
+        resource: &'a Resource,
+        permission: &str,
+    ) -> FindPermissionRequest<'a> {
+        FindPermissionRequest {
+            env_name: None,
+            actor,
+            resource,
+            context: None,
+            permission: permission.to_string(),
+        }
+    }
+    let engine = Engine::builder(&storage).check_requests(true).build();
+    assert_eq!(
+        engine.actor_has_permission(request(&actor, &document, "read")),
+        Ok(true)
+    );
+    assert!(matches!(
+        engine.actor_has_permission(request(&actor, &document, "raed")),
+        Err(Error::Undeclared {
+            kind: "permission",
+            ..
+        })
+    ));
+    let inactive = user_actor("1", "Inactive", vec![]);
+    assert_eq!(
+        engine.actor_has_permission(request(&inactive, &document, "read")),
+        Err(Error::Undeclared {
+            kind: "status",
+            name: "Inactive".to_string(),
+            place: "the request".to_string(),
+        })
+    );
+    let report = Resource {
+        type_: "Report".into(),
+        ..user_resource("3", "Active")
+    };
+    let filter = |actor| FilterResourcesRequest {
+        env_name: None,
+        actor,
+        context: None,
+        resources: [&document, &report],
+        permission: "read",
+    };
+    assert_eq!(engine.filter_resources(filter(&actor)), vec![&document]);
+    assert!(engine.filter_resources(filter(&inactive)).is_empty());
+    // without the check, the undeclared names only deny
+    let engine = Engine::new(&storage);
+    assert_eq!(
+        engine.actor_has_permission(request(&inactive, &document, "read")),
+        Ok(false)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn macro_number_arguments_work() -> MinosResult<()> {
+    let storage = MinosParser::easy_parse_str(
+        r#"syntax = 0.17M;
+
+#MIN_LEVEL(level) {
+    actor.attrs.level >= $level;
+}
+
+resource Invoice {
+    policy {
+        allow = ["approve"];
+        rule {
+            #[MIN_LEVEL(3)]
+        }
+    }
+}
+"#,
+    )?;
+    let expected = MinosParser::easy_parse_str(
+        r#"syntax = 0.17;
+
+resource Invoice {
+    policy {
+        allow = ["approve"];
+        rule {
+            actor.attrs.level >= 3;
+        }
+    }
+}
+"#,
+    )?;
+    assert_eq!(storage.resources(), expected.resources());
+
+    let content = r#"syntax = 0.17M;
+
+#MIN_LEVEL(level) {
+    actor.attrs.level >= $level;
+}
+
+resource Invoice {
+    policy {
+        allow = ["approve"];
+        rule {
+            #[MIN_LEVEL("3")]
+        }
+    }
+}
+"#;
+    assert_eq!(
+        MinosParser::easy_parse_str(content).map_err(Error::without_location),
+        Err(Error::InvalidToken {
+            expected: "Number",
+            found: "String".to_string(),
+        })
+    );
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
+pub mod condition_text_repr;
 pub mod environment_text_repr;
+pub mod explanation_text_repr;
 pub mod policy_text_repr;
 pub mod requirements_text_repr;
 pub mod resources_text_repr;
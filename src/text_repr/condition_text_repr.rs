@@ -0,0 +1,56 @@
+use crate::language::{condition::Condition, requirements::Requirement};
+
+use super::to_text_repr::ToTextRepr;
+
+/// Single line representation, like `actor.type = Admin OR (actor.roles *= "editor" AND NOT actor.status = Blocked)`.
+/// A missing attribute is written like `resource.status IS MISSING`, and the blocks are
+/// written with the boolean operators.
+impl ToTextRepr for Condition {
+    const INDENTATION: &'static str = "";
+
+    fn to_text_repr(&self) -> String {
+        match unblock(self) {
+            Condition::True => "true".to_string(),
+            Condition::False => "false".to_string(),
+            Condition::Requirement(requirement) => requirement_text_repr(requirement),
+            Condition::Missing(attribute) => format!("{} IS MISSING", attribute.to_text_repr()),
+            Condition::And(conditions) => conditions
+                .iter()
+                .map(|condition| match unblock(condition) {
+                    Condition::Or(_) => format!("({})", condition.to_text_repr()),
+                    _ => condition.to_text_repr(),
+                })
+                .collect::<Vec<String>>()
+                .join(" AND "),
+            Condition::Or(conditions) => conditions
+                .iter()
+                .map(|condition| condition.to_text_repr())
+                .collect::<Vec<String>>()
+                .join(" OR "),
+            Condition::Not(condition) => match unblock(condition) {
+                Condition::And(_) | Condition::Or(_) => format!("NOT ({})", condition.to_text_repr()),
+                _ => format!("NOT {}", condition.to_text_repr()),
+            },
+        }
+    }
+}
+
+/// Returns the condition of the block, see [`Requirement::Block`].
+fn unblock(condition: &Condition) -> &Condition {
+    match condition {
+        Condition::Requirement(Requirement::Block(inner)) => unblock(inner),
+        condition => condition,
+    }
+}
+
+fn requirement_text_repr(requirement: &Requirement) -> String {
+    match requirement {
+        Requirement::Assertion(assertion) => assertion.to_text_repr(),
+        Requirement::Negation(negation) => negation.to_text_repr(),
+        Requirement::Search(search) => search.to_text_repr(),
+        Requirement::Comparison(comparison) => comparison.to_text_repr(),
+        Requirement::Match(match_) => match_.to_text_repr(),
+        Requirement::Temporal(condition) => condition.to_text_repr(),
+        Requirement::Block(condition) => condition.to_text_repr(),
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    engine::{EnvironmentTrace, Explanation, PolicyTrace, RequirementTrace, RuleTrace},
+    language::{environment::Environment, policy::Policy, requirements::Requirement, rule::Rule},
+    text_repr::policy_text_repr::permissions_text_repr,
+};
+
+use super::to_text_repr::ToTextRepr;
+
+impl ToTextRepr for RequirementTrace<'_> {
+    const INDENTATION: &'static str = Requirement::INDENTATION;
+
+    fn to_text_repr(&self) -> String {
+        requirement_trace_text_repr(self, Self::INDENTATION)
+    }
+}
+
+/// The blocks get their result after the opening brace, and the traces of their inner
+/// requirements with an extra level of indentation.
+fn requirement_trace_text_repr(trace: &RequirementTrace, ind: &str) -> String {
+    let result = match trace.result() {
+        Some(true) => "true",
+        Some(false) => "false",
+        None => "operation not permitted",
+    };
+
+    match trace.requirement() {
+        Requirement::Block(condition) => {
+            let keyword = condition.block_keyword();
+            let inner_ind = format!("{ind}    ");
+            let children: String = trace
+                .children()
+                .iter()
+                .map(|child| requirement_trace_text_repr(child, &inner_ind))
+                .collect();
+
+            format!("{ind}{keyword} {{ /* {result} */\n{children}{ind}}}\n")
+        }
+        requirement => {
+            let requirement = requirement.to_text_repr();
+            let requirement = requirement.trim_start().trim_end_matches('\n');
+
+            format!("{ind}{requirement} /* {result} */\n")
+        }
+    }
+}
+
+impl ToTextRepr for RuleTrace<'_> {
+    const INDENTATION: &'static str = Rule::INDENTATION;
+
+    fn to_text_repr(&self) -> String {
+        let ind = Self::INDENTATION;
+        let result = match self.is_satisfied() {
+            true => "satisfied",
+            false => "not satisfied",
+        };
+        let requirements: String = self.requirements().iter().map(|r| r.to_text_repr()).collect();
+
+        format!("{ind}rule {{ /* {result} */\n{requirements}{ind}}}\n")
+    }
+}
+
+impl ToTextRepr for PolicyTrace<'_> {
+    const INDENTATION: &'static str = Policy::INDENTATION;
+
+    fn to_text_repr(&self) -> String {
+        let ind = Self::INDENTATION;
+        let result = match self.is_applied() {
+            true => "applied",
+            false => "not applied",
+        };
+        let permissions = permissions_text_repr(self.policy());
+        let rules = self
+            .rules()
+            .iter()
+            .map(|rule| rule.to_text_repr())
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!("{ind}policy {{ /* {result} */\n{permissions}{rules}{ind}}}\n")
+    }
+}
+
+impl ToTextRepr for EnvironmentTrace<'_> {
+    const INDENTATION: &'static str = Environment::INDENTATION;
+
+    fn to_text_repr(&self) -> String {
+        let ind = Self::INDENTATION;
+        let identifier = &self.environment().identifier().0;
+        let policies = self
+            .policies()
+            .iter()
+            .map(|policy| policy.to_text_repr())
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!("{ind}env {identifier} {{\n{policies}{ind}}}\n")
+    }
+}
+
+impl ToTextRepr for Explanation<'_> {
+    const INDENTATION: &'static str = "";
+
+    fn to_text_repr(&self) -> String {
+        let actor_id = self.actor_id();
+        let result = match self.is_authorized() {
+            true => {
+                let permissions = self.permissions().iter().collect::<Vec<&String>>();
+                format!("granted {permissions:?}")
+            }
+            false => "not authorized".to_string(),
+        };
+        let identifier = &self.resource_identifier().0;
+        let resource_id = match self.resource_id() {
+            Some(id) => format!("{}id = {id:?};\n\n", Environment::INDENTATION),
+            None => String::new(),
+        };
+        let envs = self
+            .environments()
+            .iter()
+            .map(|env| env.to_text_repr())
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!("/* actor {actor_id:?}: {result} */\nresource {identifier} {{\n{resource_id}{envs}}}\n")
+    }
+}
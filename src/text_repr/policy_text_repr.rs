@@ -1,6 +1,6 @@
 use derived::Ctor;
 
-use crate::language::policy::{Permission, Policy};
+use crate::language::policy::{Effect, Permission, Policy};
 
 use super::to_text_repr::ToTextRepr;
 
@@ -37,10 +37,18 @@ impl ToTextRepr for Policy {
     const INDENTATION: &'static str = "        ";
     fn to_text_repr(&self) -> String {
         let ind = Self::INDENTATION;
-        let allow = self.permissions().to_text_repr();
+        let permissions = permissions_text_repr(self);
         let rules = self.rules().to_text_repr();
 
-        format!("{ind}policy {{\n{allow}{rules}{ind}}}\n")
+        format!("{ind}policy {{\n{permissions}{rules}{ind}}}\n")
+    }
+}
+
+/// Returns the `allow` or `deny` statement of the [Policy].
+pub(crate) fn permissions_text_repr(policy: &Policy) -> String {
+    match policy.effect() {
+        Effect::Allow => policy.permissions().to_text_repr(),
+        Effect::Deny => DeniedPermissions(policy.permissions()).to_text_repr(),
     }
 }
 
@@ -54,3 +62,18 @@ impl ToTextRepr for Vec<Permission> {
         format!("{}allow = {:?};\n\n", Self::INDENTATION, permissions_str)
     }
 }
+
+/// Wrapper to represent the permissions of a deny [Policy].
+#[derive(Debug, Clone, Copy)]
+pub struct DeniedPermissions<'a>(pub &'a [Permission]);
+
+impl ToTextRepr for DeniedPermissions<'_> {
+    /// 3 tabs of identation
+    const INDENTATION: &'static str = "            ";
+
+    fn to_text_repr(&self) -> String {
+        let permissions_str = self.0.iter().map(|p| p.0.as_ref()).collect::<Vec<&str>>();
+
+        format!("{}deny = {:?};\n\n", Self::INDENTATION, permissions_str)
+    }
+}
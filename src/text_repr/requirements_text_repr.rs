@@ -1,6 +1,7 @@
 use crate::language::requirements::{
-    Assertion, Attribute, ComparableValue, Negation, Requirement, Search, Value,
+    Assertion, Attribute, ComparableValue, Comparison, Match, Negation, Requirement, Search, Value,
 };
+use crate::language::time::TimeCondition;
 
 use super::to_text_repr::ToTextRepr;
 
@@ -23,16 +24,30 @@ impl ToTextRepr for Requirement {
     const INDENTATION: &'static str = "                ";
 
     fn to_text_repr(&self) -> String {
-        let requirement = match self {
-            Requirement::Assertion(assertion) => assertion.to_text_repr(),
-            Requirement::Negation(negation) => negation.to_text_repr(),
-            Requirement::Search(search) => search.to_text_repr(),
-        };
-
-        format!("{}{};\n", Self::INDENTATION, requirement)
+        requirement_text_repr(self, Self::INDENTATION)
     }
 }
 
+/// The inner requirements of the `any`, `all` and `not` blocks get an extra level of indentation.
+fn requirement_text_repr(requirement: &Requirement, ind: &str) -> String {
+    let (keyword, requirements) = match requirement {
+        Requirement::Assertion(assertion) => return format!("{ind}{};\n", assertion.to_text_repr()),
+        Requirement::Negation(negation) => return format!("{ind}{};\n", negation.to_text_repr()),
+        Requirement::Search(search) => return format!("{ind}{};\n", search.to_text_repr()),
+        Requirement::Comparison(comparison) => return format!("{ind}{};\n", comparison.to_text_repr()),
+        Requirement::Match(match_) => return format!("{ind}{};\n", match_.to_text_repr()),
+        Requirement::Temporal(condition) => return format!("{ind}{};\n", condition.to_text_repr()),
+        Requirement::Block(condition) => (condition.block_keyword(), condition.requirements()),
+    };
+    let inner_ind = format!("{ind}    ");
+    let requirements: String = requirements
+        .iter()
+        .map(|requirement| requirement_text_repr(requirement, &inner_ind))
+        .collect();
+
+    format!("{ind}{keyword} {{\n{requirements}{ind}}}\n")
+}
+
 impl ToTextRepr for Assertion {
     const INDENTATION: &'static str = "";
 
@@ -65,6 +80,46 @@ impl ToTextRepr for Search {
     }
 }
 
+impl ToTextRepr for Comparison {
+    const INDENTATION: &'static str = "";
+
+    fn to_text_repr(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.left().to_text_repr(),
+            self.operator(),
+            self.right().to_text_repr()
+        )
+    }
+}
+
+impl ToTextRepr for Match {
+    const INDENTATION: &'static str = "";
+
+    fn to_text_repr(&self) -> String {
+        let pattern = self.pattern();
+        format!(
+            "{} ~= {}(\"{}\")",
+            self.left().to_text_repr(),
+            pattern.flavour(),
+            pattern.source()
+        )
+    }
+}
+
+impl ToTextRepr for TimeCondition {
+    const INDENTATION: &'static str = "";
+
+    fn to_text_repr(&self) -> String {
+        match self {
+            TimeCondition::Before(timestamp) => format!("context.time before \"{}\"", timestamp.text()),
+            TimeCondition::After(timestamp) => format!("context.time after \"{}\"", timestamp.text()),
+            TimeCondition::Weekdays(start, end) => format!("context.weekday in {start}..{end}"),
+            TimeCondition::Hours(start, end) => format!("context.hour in {start}..{end}"),
+        }
+    }
+}
+
 impl ToTextRepr for Attribute {
     const INDENTATION: &'static str = "";
 
@@ -72,6 +127,7 @@ impl ToTextRepr for Attribute {
         match self {
             Attribute::Actor(attr) => attr.to_string(),
             Attribute::Resource(attr) => attr.to_string(),
+            Attribute::Context(attr) => attr.to_string(),
         }
     }
 }
@@ -84,6 +140,7 @@ impl ToTextRepr for Value {
             Value::String(v) => format!("\"{}\"", v),
             Value::Array(arr) => format!("{:?}", arr.0),
             Value::Identifier(v) => v.0.to_string(),
+            Value::Number(v) => v.to_string(),
         }
     }
 }
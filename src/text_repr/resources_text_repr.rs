@@ -33,10 +33,14 @@ impl ToTextRepr for Resource {
 
     fn to_text_repr(&self) -> String {
         let identifier = &self.identifier().0;
+        let extends = match self.parent() {
+            Some(parent) => format!(" extends {parent}"),
+            None => String::new(),
+        };
         let envs_list = self.environments().values();
         let envs = EnvironmentsFormatter::new(envs_list).to_text_repr();
 
-        format!("resource {identifier} {{\n{envs}}}\n")
+        format!("resource {identifier}{extends} {{\n{envs}}}\n")
     }
 }
 
@@ -1,4 +1,6 @@
-use crate::language::storage::Storage;
+use std::sync::Arc;
+
+use crate::language::{hierarchy::Hierarchy, schema::Schema, storage::Storage};
 
 use super::to_text_repr::ToTextRepr;
 
@@ -6,9 +8,84 @@ impl ToTextRepr for Storage {
     const INDENTATION: &'static str = "";
 
     fn to_text_repr(&self) -> String {
+        let schema = schema_text_repr(self.schema());
+        let roles = hierarchy_text_repr("roles", self.roles_hierarchy());
+        let groups = hierarchy_text_repr("groups", self.groups_hierarchy());
         let resources = self.resources().to_text_repr();
         let attr_resources = self.attributed_resources().to_text_repr();
+        let version = self.version().without_macros();
+
+        format!("syntax = {version};\n\n\n{schema}{roles}{groups}{resources}{attr_resources}")
+    }
+}
+
+/// Every inheritance is written in its own line, like `admin > editor;`.
+fn hierarchy_text_repr(keyword: &str, hierarchy: &Hierarchy) -> String {
+    if hierarchy.is_empty() {
+        return String::new();
+    }
+
+    let ind = "    ";
+    let mut repr = format!("{keyword} {{\n");
+    for (senior, junior) in hierarchy.inheritances() {
+        let [senior, junior] = [senior, junior].map(|name| name_text_repr(name));
+        repr.push_str(&format!("{ind}{senior} > {junior};\n"));
+    }
+    repr.push_str("}\n\n");
+
+    repr
+}
+
+/// Every declaration is written in its own line, and the permissions are sorted by type.
+fn schema_text_repr(schema: &Schema) -> String {
+    if schema.is_empty() {
+        return String::new();
+    }
+
+    let ind = "    ";
+    let names = |names: &[Arc<str>], quoted: bool| {
+        names
+            .iter()
+            .map(|name| match quoted {
+                true => format!("{name:?}"),
+                false => name_text_repr(name),
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+    let mut repr = String::from("schema {\n");
+    for (keyword, declared) in [
+        ("actor types", schema.actor_types()),
+        ("resource types", schema.resource_types()),
+        ("statuses", schema.statuses()),
+    ] {
+        if !declared.is_empty() {
+            repr.push_str(&format!("{ind}{keyword} {};\n", names(declared, false)));
+        }
+    }
+
+    let mut permissions: Vec<_> = schema.permissions().iter().collect();
+    permissions.sort_by_key(|(resource_type, _)| &resource_type.0);
+    for (resource_type, declared) in permissions {
+        repr.push_str(&format!(
+            "{ind}permissions for {resource_type} {};\n",
+            names(declared, true)
+        ));
+    }
+    repr.push_str("}\n\n");
+
+    repr
+}
+
+/// Writes the name as an identifier if it's valid, otherwise as a string.
+fn name_text_repr(name: &str) -> String {
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '/' | '-'));
 
-        format!("syntax = 0.16;\n\n\n{resources}{attr_resources}")
+    match is_identifier {
+        true => name.to_string(),
+        false => format!("{name:?}"),
     }
 }